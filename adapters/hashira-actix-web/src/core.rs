@@ -2,13 +2,23 @@ use std::{net::SocketAddr, str::FromStr};
 
 use actix_files::Files;
 use actix_web::{
+    http::header::{self, HeaderValue},
     web::{self},
     HttpRequest, HttpResponse,
 };
-use futures::{StreamExt, TryStreamExt};
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, CloseReason, ProtocolError, Session};
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    future::ready,
+    stream, SinkExt, StreamExt, TryStreamExt,
+};
 use hashira::{
     app::AppService,
-    web::{Body, Payload, RemoteAddr, Request, Response},
+    error::BoxError,
+    web::{
+        Body, CloseFrame, Message, Payload, RemoteAddr, Request, Response, WebSocket,
+        WebSocketHandler,
+    },
 };
 
 /// Returns a function which adds a configuration to the actix web `App`
@@ -58,26 +68,29 @@ pub async fn handle_request(
         .cloned()
         .expect("Unable to find hashira `AppService`");
 
-    let req = map_request(req, body).await?;
-    let res = service.handle(req).await;
-    let actix_web_response = map_response(res);
-    Ok(actix_web_response)
-}
+    // The body of a websocket upgrade is the connection, so we keep it to complete the upgrade
+    let (hashira_body, ws_payload) = if is_websocket_upgrade(&req) {
+        (Body::empty(), Some(body))
+    } else {
+        (stream_payload(body), None)
+    };
 
-async fn map_request(
-    actix_req: HttpRequest,
-    mut payload: actix_web::web::Payload,
-) -> actix_web::Result<Request> {
-    let mut request = Request::builder()
-        .uri(actix_req.uri())
-        .method(actix_req.method())
-        .version(actix_req.version());
+    let hashira_req = map_request(&req, hashira_body).await?;
+    let mut res = service.handle(hashira_req).await;
 
-    let headers = request.headers_mut().unwrap();
-    for (name, value) in actix_req.headers() {
-        headers.append(name, value.into());
+    // The handler accepted the websocket connection
+    if let (Some(payload), Some(handler)) = (
+        ws_payload,
+        res.extensions_mut().remove::<WebSocketHandler>(),
+    ) {
+        return upgrade_websocket(&req, payload, handler);
     }
 
+    let actix_web_response = map_response(res);
+    Ok(actix_web_response)
+}
+
+fn stream_payload(mut payload: actix_web::web::Payload) -> Body {
     // Sends the body as a stream
     let (sender, body) = Body::channel();
 
@@ -91,6 +104,20 @@ async fn map_request(
         }
     });
 
+    body
+}
+
+async fn map_request(actix_req: &HttpRequest, body: Body) -> actix_web::Result<Request> {
+    let mut request = Request::builder()
+        .uri(actix_req.uri())
+        .method(actix_req.method())
+        .version(actix_req.version());
+
+    let headers = request.headers_mut().unwrap();
+    for (name, value) in actix_req.headers() {
+        headers.append(name, value.into());
+    }
+
     // Add additional extensions
     let remote_addr = actix_req
         .connection_info()
@@ -125,6 +152,112 @@ fn map_response(res: Response) -> HttpResponse {
     }
 }
 
+fn is_websocket_upgrade(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::UPGRADE)
+        .and_then(|x| x.to_str().ok())
+        .map(|x| x.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false)
+}
+
+fn upgrade_websocket(
+    req: &HttpRequest,
+    payload: actix_web::web::Payload,
+    handler: WebSocketHandler,
+) -> actix_web::Result<HttpResponse> {
+    let (mut res, session, msg_stream) = actix_ws::handle(req, payload)?;
+
+    if let Some(protocol) = handler
+        .protocol()
+        .and_then(|x| HeaderValue::from_str(x).ok())
+    {
+        res.headers_mut()
+            .insert(header::SEC_WEBSOCKET_PROTOCOL, protocol);
+    }
+
+    // The actix message stream is not `Send`, so we forward the messages through channels
+    let (incoming_tx, incoming_rx) = mpsc::unbounded();
+    let (outgoing_tx, outgoing_rx) = mpsc::unbounded();
+
+    actix_web::rt::spawn(forward_messages(
+        session,
+        msg_stream.aggregate_continuations(),
+        incoming_tx,
+        outgoing_rx,
+    ));
+
+    let socket = WebSocket::from_split(outgoing_tx.sink_map_err(BoxError::from), incoming_rx);
+    actix_web::rt::spawn(handler.call(socket));
+
+    Ok(res)
+}
+
+enum WebSocketEvent {
+    Incoming(Result<AggregatedMessage, ProtocolError>),
+    Outgoing(Message),
+    Done,
+}
+
+async fn forward_messages(
+    mut session: Session,
+    msg_stream: AggregatedMessageStream,
+    incoming: UnboundedSender<Result<Message, BoxError>>,
+    outgoing: UnboundedReceiver<Message>,
+) {
+    // `Done` is emitted when the socket is dropped
+    let outgoing = outgoing
+        .map(WebSocketEvent::Outgoing)
+        .chain(stream::once(ready(WebSocketEvent::Done)));
+    let mut events = stream::select(msg_stream.map(WebSocketEvent::Incoming), outgoing);
+
+    while let Some(event) = events.next().await {
+        let result = match event {
+            WebSocketEvent::Incoming(Ok(msg)) => {
+                let _ = incoming.unbounded_send(Ok(map_from_actix_message(msg)));
+                continue;
+            }
+            WebSocketEvent::Incoming(Err(err)) => {
+                let _ = incoming.unbounded_send(Err(err.into()));
+                break;
+            }
+            WebSocketEvent::Outgoing(Message::Text(text)) => session.text(text).await,
+            WebSocketEvent::Outgoing(Message::Binary(bytes)) => session.binary(bytes).await,
+            WebSocketEvent::Outgoing(Message::Ping(bytes)) => session.ping(&bytes).await,
+            WebSocketEvent::Outgoing(Message::Pong(bytes)) => session.pong(&bytes).await,
+            WebSocketEvent::Outgoing(Message::Close(frame)) => {
+                let reason = frame.map(|f| CloseReason {
+                    code: f.code.into(),
+                    description: Some(f.reason),
+                });
+
+                let _ = session.close(reason).await;
+                return;
+            }
+            WebSocketEvent::Done => {
+                let _ = session.close(None).await;
+                return;
+            }
+        };
+
+        if result.is_err() {
+            break;
+        }
+    }
+}
+
+fn map_from_actix_message(msg: AggregatedMessage) -> Message {
+    match msg {
+        AggregatedMessage::Text(text) => Message::Text(text.to_string()),
+        AggregatedMessage::Binary(bytes) => Message::Binary(bytes),
+        AggregatedMessage::Ping(bytes) => Message::Ping(bytes),
+        AggregatedMessage::Pong(bytes) => Message::Pong(bytes),
+        AggregatedMessage::Close(reason) => Message::Close(reason.map(|r| CloseFrame {
+            code: r.code.into(),
+            reason: r.description.unwrap_or_default(),
+        })),
+    }
+}
+
 fn get_current_dir() -> std::path::PathBuf {
     let mut current_dir = std::env::current_exe().expect("failed to get current directory");
     current_dir.pop();
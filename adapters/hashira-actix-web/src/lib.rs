@@ -1,8 +1,12 @@
 pub mod core;
 
 use std::net::SocketAddr;
-use actix_web::{web::ServiceConfig, HttpServer};
-use hashira::{adapter::Adapter, app::AppService};
+use actix_web::{dev::Server, web::ServiceConfig, HttpServer};
+use hashira::{
+    adapter::{graceful_shutdown, Adapter},
+    app::AppService,
+    types::BoxFuture,
+};
 
 /// An adapter for `actix-web`.
 #[derive(Clone)]
@@ -21,33 +25,66 @@ impl Default for HashiraActixWeb<()> {
     }
 }
 
-#[hashira::async_trait]
-impl<F> Adapter for HashiraActixWeb<F>
+impl<F> HashiraActixWeb<F>
 where
     F: sealed::ConfigureActixService + Send + Clone + 'static,
 {
-    /// Starts the server.
-    async fn serve(self, app: AppService) -> Result<(), hashira::error::BoxError> {
+    fn create_server(self, app: &AppService, signals: bool) -> std::io::Result<Server> {
         let host = hashira::env::get_host().unwrap_or_else(|| String::from("127.0.0.1"));
         let port = hashira::env::get_port().unwrap_or(5000);
         let addr: SocketAddr = format!("{host}:{port}").as_str().parse().unwrap();
 
         println!("Server started at: http://{addr}");
 
-        // Create and run the server
-        let server = HttpServer::new(move || {
+        // Create the server
+        let service = app.clone();
+        let mut server = HttpServer::new(move || {
             let config = self.0.clone();
             actix_web::App::new()
                 .configure(move |cfg| config.configure(cfg))
-                .configure(core::router(app.clone()))
+                .configure(core::router(service.clone()))
         })
-        .bind(addr)?
-        .run();
+        .shutdown_timeout(app.shutdown_timeout().as_secs());
+
+        if !signals {
+            server = server.disable_signals();
+        }
+
+        Ok(server.bind(addr)?.run())
+    }
+}
+
+#[hashira::async_trait]
+impl<F> Adapter for HashiraActixWeb<F>
+where
+    F: sealed::ConfigureActixService + Send + Clone + 'static,
+{
+    /// Starts the server and stops it on `Ctrl-C` or a termination signal.
+    async fn serve(self, app: AppService) -> Result<(), hashira::error::BoxError> {
+        // Without a shutdown signal actix handles the os signals
+        let server = self.create_server(&app, true)?;
+        graceful_shutdown(&app, server, std::future::pending()).await
+    }
+
+    /// Starts the server and stops it when the signal completes.
+    async fn serve_with_shutdown(
+        self,
+        app: AppService,
+        signal: BoxFuture<()>,
+    ) -> Result<(), hashira::error::BoxError> {
+        let server = self.create_server(&app, false)?;
+
+        // Stop accepting connections when the signal completes, actix waits for the workers
+        // while the in-flight requests are drained, so both use the same shutdown timeout
+        let handle = server.handle();
+        let signal = async move {
+            signal.await;
 
-        // Awaits
-        server.await?;
+            // The stop command is sent before awaiting, the server completes when stopped
+            drop(handle.stop(true));
+        };
 
-        Ok(())
+        graceful_shutdown(&app, server, signal).await
     }
 }
 
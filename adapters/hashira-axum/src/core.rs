@@ -1,11 +1,20 @@
 use std::net::SocketAddr;
 
-use axum::{extract::ConnectInfo, response::IntoResponse, routing::get_service, Extension, Router};
-use futures::TryStreamExt;
+use axum::{
+    extract::{ws, ConnectInfo},
+    response::IntoResponse,
+    routing::get_service,
+    Extension, Router,
+};
+use futures::{future::ready, SinkExt, StreamExt, TryStreamExt};
 use hashira::{
     app::AppService,
+    error::BoxError,
     types::TryBoxStream,
-    web::{Body, Payload, RemoteAddr, Request, Response},
+    web::{
+        Body, CloseFrame, Message, Payload, RemoteAddr, Request, Response, WebSocket,
+        WebSocketHandler,
+    },
 };
 use hyper::{body::Bytes, StatusCode};
 use tower_http::services::ServeDir;
@@ -35,11 +44,20 @@ where
 pub async fn handle_request(
     Extension(service): Extension<AppService>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    ws: Option<ws::WebSocketUpgrade>,
     axum_req: Request<axum::body::Body>,
 ) -> impl IntoResponse {
     match map_request(axum_req, addr).await {
         Ok(req) => {
-            let res = service.handle(req).await;
+            let mut res = service.handle(req).await;
+
+            // The handler accepted the websocket connection
+            if let (Some(ws), Some(handler)) =
+                (ws, res.extensions_mut().remove::<WebSocketHandler>())
+            {
+                return upgrade_websocket(ws, handler);
+            }
+
             map_response(res)
         }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
@@ -70,6 +88,52 @@ fn map_response(res: Response) -> axum::response::Response {
     axum::response::Response::from_parts(parts, axum::body::boxed(body))
 }
 
+fn upgrade_websocket(
+    ws: ws::WebSocketUpgrade,
+    handler: WebSocketHandler,
+) -> axum::response::Response {
+    let ws = match handler.protocol() {
+        Some(protocol) => ws.protocols([protocol.to_owned()]),
+        None => ws,
+    };
+
+    ws.on_upgrade(move |socket| {
+        let (sink, stream) = socket.split();
+        let sink = sink
+            .sink_map_err(BoxError::from)
+            .with(|msg| ready(Ok::<_, BoxError>(map_to_axum_message(msg))));
+        let stream = stream.map_ok(map_from_axum_message).map_err(BoxError::from);
+
+        handler.call(WebSocket::from_split(sink, stream))
+    })
+}
+
+fn map_from_axum_message(msg: ws::Message) -> Message {
+    match msg {
+        ws::Message::Text(text) => Message::Text(text),
+        ws::Message::Binary(bytes) => Message::Binary(bytes.into()),
+        ws::Message::Ping(bytes) => Message::Ping(bytes.into()),
+        ws::Message::Pong(bytes) => Message::Pong(bytes.into()),
+        ws::Message::Close(frame) => Message::Close(frame.map(|f| CloseFrame {
+            code: f.code,
+            reason: f.reason.into_owned(),
+        })),
+    }
+}
+
+fn map_to_axum_message(msg: Message) -> ws::Message {
+    match msg {
+        Message::Text(text) => ws::Message::Text(text),
+        Message::Binary(bytes) => ws::Message::Binary(bytes.into()),
+        Message::Ping(bytes) => ws::Message::Ping(bytes.into()),
+        Message::Pong(bytes) => ws::Message::Pong(bytes.into()),
+        Message::Close(frame) => ws::Message::Close(frame.map(|f| ws::CloseFrame {
+            code: f.code,
+            reason: f.reason.into(),
+        })),
+    }
+}
+
 fn get_current_dir() -> std::path::PathBuf {
     let mut current_dir = std::env::current_exe().expect("failed to get current directory");
     current_dir.pop();
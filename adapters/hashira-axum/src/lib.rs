@@ -1,5 +1,10 @@
 pub mod core;
-use hashira::{adapter::Adapter, app::AppService};
+use futures::FutureExt;
+use hashira::{
+    adapter::{graceful_shutdown, Adapter},
+    app::AppService,
+    types::BoxFuture,
+};
 use std::net::SocketAddr;
 
 /// An adapter for `axum`.
@@ -10,19 +15,30 @@ pub struct HashiraAxum(Option<axum::Router>);
 impl Adapter for HashiraAxum {
     /// Starts the server.
     async fn serve(self, app: AppService) -> Result<(), hashira::error::BoxError> {
+        self.serve_with_shutdown(app, Box::pin(futures::future::pending()))
+            .await
+    }
+
+    /// Starts the server and stops it when the signal completes.
+    async fn serve_with_shutdown(
+        self,
+        app: AppService,
+        signal: BoxFuture<()>,
+    ) -> Result<(), hashira::error::BoxError> {
         let host = hashira::env::get_host().unwrap_or_else(|| String::from("127.0.0.1"));
         let port = hashira::env::get_port().unwrap_or(5000);
         let addr: SocketAddr = format!("{host}:{port}").as_str().parse().unwrap();
 
         println!("Server started at: http://{addr}");
 
-        let router = self.0.unwrap_or_default().merge(core::router(app));
+        let router = self.0.unwrap_or_default().merge(core::router(app.clone()));
+        let signal = signal.shared();
 
-        axum::Server::bind(&addr)
+        let server = axum::Server::bind(&addr)
             .serve(router.into_make_service_with_connect_info::<SocketAddr>())
-            .await?;
+            .with_graceful_shutdown(signal.clone());
 
-        Ok(())
+        graceful_shutdown(&app, server, signal).await
     }
 }
 
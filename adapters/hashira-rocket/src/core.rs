@@ -1,9 +1,11 @@
+use futures::{future::ready, SinkExt, StreamExt};
 use hashira::{
     app::AppService,
-    web::{Body, RemoteAddr, Response},
+    error::BoxError,
+    web::{header, Body, CloseFrame, Message, RemoteAddr, Response, WebSocket, WebSocketHandler},
 };
 use rocket::{
-    data::FromData,
+    data::{FromData, IoHandler, IoStream},
     fs::FileServer,
     futures::TryStreamExt,
     http::Method::*,
@@ -13,6 +15,11 @@ use rocket::{
     State,
 };
 use rocket::{Build, Rocket};
+use std::pin::Pin;
+use tokio_tungstenite::{
+    tungstenite::{self, protocol::Role},
+    WebSocketStream,
+};
 
 #[doc(hidden)]
 pub struct RequestWithoutBody(hashira::web::Request<()>);
@@ -85,7 +92,12 @@ impl Handler for DefaultRequestHandler {
         // let bytes = Box::pin(reader) as TryBoxStream<Bytes>;
 
         let req = req.0.map(move |_| Body::from(bytes));
-        let res = service.handle(req).await;
+        let mut res = service.handle(req).await;
+
+        // The handler accepted the websocket connection
+        if let Some(handler) = res.extensions_mut().remove::<WebSocketHandler>() {
+            return route::Outcome::Success(upgrade_websocket(res, handler));
+        }
 
         let rocket_res = map_response(res).await;
         route::Outcome::Success(rocket_res)
@@ -187,6 +199,76 @@ async fn map_response(res: Response) -> rocket::Response<'static> {
     builder.finalize()
 }
 
+struct WebSocketIoHandler(WebSocketHandler);
+
+#[rocket::async_trait]
+impl IoHandler for WebSocketIoHandler {
+    async fn io(self: Pin<Box<Self>>, io: IoStream) -> std::io::Result<()> {
+        let handler = Pin::into_inner(self).0;
+        let socket = WebSocketStream::from_raw_socket(io, Role::Server, None).await;
+        let (sink, stream) = socket.split();
+        let sink = sink
+            .sink_map_err(BoxError::from)
+            .with(|msg| ready(Ok::<_, BoxError>(map_to_tungstenite_message(msg))));
+        let stream = stream
+            .try_filter_map(|msg| ready(Ok(map_from_tungstenite_message(msg))))
+            .map_err(BoxError::from);
+
+        handler.call(WebSocket::from_split(sink, stream)).await;
+        Ok(())
+    }
+}
+
+fn upgrade_websocket(res: Response, handler: WebSocketHandler) -> rocket::Response<'static> {
+    let mut builder = rocket::Response::build();
+    builder.status(rocket::http::Status::SwitchingProtocols);
+
+    // Rocket sets the `Connection` and `Upgrade` headers when upgrading
+    for (name, value) in res.headers() {
+        if name == header::CONNECTION || name == header::UPGRADE {
+            continue;
+        }
+
+        let v = value.to_str().unwrap().to_string();
+        builder.header_adjoin(rocket::http::Header::new(name.to_string(), v));
+    }
+
+    builder.upgrade("websocket", WebSocketIoHandler(handler));
+    builder.finalize()
+}
+
+fn map_from_tungstenite_message(msg: tungstenite::Message) -> Option<Message> {
+    let msg = match msg {
+        tungstenite::Message::Text(text) => Message::Text(text),
+        tungstenite::Message::Binary(bytes) => Message::Binary(bytes.into()),
+        tungstenite::Message::Ping(bytes) => Message::Ping(bytes.into()),
+        tungstenite::Message::Pong(bytes) => Message::Pong(bytes.into()),
+        tungstenite::Message::Close(frame) => Message::Close(frame.map(|f| CloseFrame {
+            code: f.code.into(),
+            reason: f.reason.into_owned(),
+        })),
+        // Raw frames are not returned when reading
+        tungstenite::Message::Frame(_) => return None,
+    };
+
+    Some(msg)
+}
+
+fn map_to_tungstenite_message(msg: Message) -> tungstenite::Message {
+    match msg {
+        Message::Text(text) => tungstenite::Message::Text(text),
+        Message::Binary(bytes) => tungstenite::Message::Binary(bytes.into()),
+        Message::Ping(bytes) => tungstenite::Message::Ping(bytes.into()),
+        Message::Pong(bytes) => tungstenite::Message::Pong(bytes.into()),
+        Message::Close(frame) => {
+            tungstenite::Message::Close(frame.map(|f| tungstenite::protocol::CloseFrame {
+                code: f.code.into(),
+                reason: f.reason.into(),
+            }))
+        }
+    }
+}
+
 fn get_current_dir() -> std::path::PathBuf {
     let mut current_dir = std::env::current_exe().expect("failed to get current directory");
     current_dir.pop();
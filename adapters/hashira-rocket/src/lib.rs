@@ -1,6 +1,12 @@
 use std::net::SocketAddr;
 
-use hashira::{adapter::Adapter, app::AppService, error::BoxError};
+use futures::FutureExt;
+use hashira::{
+    adapter::{graceful_shutdown, Adapter},
+    app::AppService,
+    error::BoxError,
+    types::BoxFuture,
+};
 use rocket::{Build, Rocket};
 
 pub mod core;
@@ -29,12 +35,23 @@ impl Default for HashiraRocket {
 impl Adapter for HashiraRocket {
     /// Starts the server.
     async fn serve(self, app: AppService) -> Result<(), BoxError> {
+        self.serve_with_shutdown(app, Box::pin(futures::future::pending()))
+            .await
+    }
+
+    /// Starts the server and stops it when the signal completes.
+    async fn serve_with_shutdown(
+        self,
+        app: AppService,
+        signal: BoxFuture<()>,
+    ) -> Result<(), BoxError> {
         let host = hashira::env::get_host().unwrap_or_else(|| String::from("127.0.0.1"));
         let port = hashira::env::get_port().unwrap_or(5000);
         let addr: SocketAddr = format!("{host}:{port}").as_str().parse().unwrap();
 
         let shutdown = rocket::config::Shutdown {
             ctrlc: false, // hashira cli handle the shutdown
+            grace: app.shutdown_timeout().as_secs() as u32,
             ..rocket::config::Shutdown::default()
         };
 
@@ -42,7 +59,7 @@ impl Adapter for HashiraRocket {
 
         // Attach the router to the rocket
         rocket = {
-            let configure = core::router(app);
+            let configure = core::router(app.clone());
             configure(rocket)
         };
 
@@ -57,9 +74,15 @@ impl Adapter for HashiraRocket {
         let rocket = rocket.configure(figment).ignite().await?;
         println!("Server started at: http://{addr}");
 
-        // Start the server
-        rocket.launch().await?;
+        // Notify rocket to stop accepting connections when the signal completes
+        let handle = rocket.shutdown();
+        let signal = async move {
+            signal.await;
+            handle.notify();
+        };
 
-        Ok(())
+        // Start the server
+        let server = rocket.launch().map(|ret| ret.map(|_| ()));
+        graceful_shutdown(&app, server, signal).await
     }
 }
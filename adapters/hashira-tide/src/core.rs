@@ -1,10 +1,18 @@
 use std::{net::SocketAddr, str::FromStr};
 
-use futures::stream::TryStreamExt;
+use async_tungstenite::{
+    tungstenite::{self, protocol::Role},
+    WebSocketStream,
+};
+use futures::{future::ready, stream::TryStreamExt, SinkExt, StreamExt};
 
 use hashira::{
     app::AppService,
-    web::{Payload, RemoteAddr, Request, Response, ResponseExt},
+    error::BoxError,
+    web::{
+        CloseFrame, Message, Payload, RemoteAddr, Request, Response, ResponseExt, WebSocket,
+        WebSocketHandler,
+    },
 };
 
 // Returns a router for a `Tide` application.
@@ -69,7 +77,13 @@ pub async fn handle_request<S>(
     app_service: AppService,
 ) -> Result<tide::Response, tide::Error> {
     let req = map_request(tide_req).await?;
-    let res = app_service.handle(req).await;
+    let mut res = app_service.handle(req).await;
+
+    // The handler accepted the websocket connection
+    if let Some(handler) = res.extensions_mut().remove::<WebSocketHandler>() {
+        return upgrade_websocket(res, handler).await;
+    }
+
     let tide_res = map_response(res)?;
     Ok(tide_res)
 }
@@ -154,6 +168,72 @@ fn map_response(res: Response) -> Result<tide::Response, tide::Error> {
     Ok(tide_res)
 }
 
+async fn upgrade_websocket(
+    res: Response,
+    handler: WebSocketHandler,
+) -> Result<tide::Response, tide::Error> {
+    // The upgrade response is sent without body
+    let mut tide_res = tide::Response::new(tide::StatusCode::SwitchingProtocols);
+    for (name, value) in res.headers() {
+        tide_res.append_header(name.as_str(), value.to_str()?);
+    }
+
+    let http_res: &mut tide::http::Response = tide_res.as_mut();
+    let upgrade_receiver = http_res.recv_upgrade().await;
+
+    async_std::task::spawn(async move {
+        let Some(conn) = upgrade_receiver.await else {
+            log::error!("failed to upgrade websocket connection");
+            return;
+        };
+
+        let socket = WebSocketStream::from_raw_socket(conn, Role::Server, None).await;
+        let (sink, stream) = socket.split();
+        let sink = sink
+            .sink_map_err(BoxError::from)
+            .with(|msg| ready(Ok::<_, BoxError>(map_to_tungstenite_message(msg))));
+        let stream = stream
+            .try_filter_map(|msg| ready(Ok(map_from_tungstenite_message(msg))))
+            .map_err(BoxError::from);
+
+        handler.call(WebSocket::from_split(sink, stream)).await;
+    });
+
+    Ok(tide_res)
+}
+
+fn map_from_tungstenite_message(msg: tungstenite::Message) -> Option<Message> {
+    let msg = match msg {
+        tungstenite::Message::Text(text) => Message::Text(text),
+        tungstenite::Message::Binary(bytes) => Message::Binary(bytes.into()),
+        tungstenite::Message::Ping(bytes) => Message::Ping(bytes.into()),
+        tungstenite::Message::Pong(bytes) => Message::Pong(bytes.into()),
+        tungstenite::Message::Close(frame) => Message::Close(frame.map(|f| CloseFrame {
+            code: f.code.into(),
+            reason: f.reason.into_owned(),
+        })),
+        // Raw frames are not returned when reading
+        tungstenite::Message::Frame(_) => return None,
+    };
+
+    Some(msg)
+}
+
+fn map_to_tungstenite_message(msg: Message) -> tungstenite::Message {
+    match msg {
+        Message::Text(text) => tungstenite::Message::Text(text),
+        Message::Binary(bytes) => tungstenite::Message::Binary(bytes.into()),
+        Message::Ping(bytes) => tungstenite::Message::Ping(bytes.into()),
+        Message::Pong(bytes) => tungstenite::Message::Pong(bytes.into()),
+        Message::Close(frame) => {
+            tungstenite::Message::Close(frame.map(|f| tungstenite::protocol::CloseFrame {
+                code: f.code.into(),
+                reason: f.reason.into(),
+            }))
+        }
+    }
+}
+
 fn get_current_dir() -> std::path::PathBuf {
     let mut current_dir = std::env::current_exe().expect("failed to get current directory");
     current_dir.pop();
@@ -1,5 +1,13 @@
 pub mod core;
-use hashira::{adapter::Adapter, app::AppService};
+use futures::{
+    future::{self, Either},
+    FutureExt,
+};
+use hashira::{
+    adapter::{graceful_shutdown, Adapter},
+    app::AppService,
+    types::BoxFuture,
+};
 use std::net::SocketAddr;
 
 /// An adapter for `tide`.
@@ -12,25 +20,42 @@ where
     S: Clone + Send + Sync + 'static,
 {
     /// Starts the server.
-    async fn serve(mut self, app: AppService) -> Result<(), hashira::error::BoxError> {
+    async fn serve(self, app: AppService) -> Result<(), hashira::error::BoxError> {
+        self.serve_with_shutdown(app, Box::pin(futures::future::pending()))
+            .await
+    }
+
+    /// Starts the server and stops it when the signal completes.
+    async fn serve_with_shutdown(
+        mut self,
+        app: AppService,
+        signal: BoxFuture<()>,
+    ) -> Result<(), hashira::error::BoxError> {
         let host = hashira::env::get_host().unwrap_or_else(|| String::from("127.0.0.1"));
         let port = hashira::env::get_port().unwrap_or(5000);
         let addr: SocketAddr = format!("{host}:{port}").as_str().parse().unwrap();
 
         println!("Server started at: http://{addr}");
 
-        match self.0.take() {
-            Some(router) => {
-                let tide = crate::core::with_router(router, app);
-                tide.listen(addr).await?;
-            }
-            None => {
-                let tide = crate::core::router(app);
-                tide.listen(addr).await?;
+        let listen: BoxFuture<std::io::Result<()>> = match self.0.take() {
+            Some(router) => Box::pin(crate::core::with_router(router, app.clone()).listen(addr)),
+            None => Box::pin(crate::core::router(app.clone()).listen(addr)),
+        };
+
+        // tide don't support graceful shutdown, so we drop the listener when the signal completes
+        // and the in-flight requests keep running in its own tasks until drained
+        let signal = signal.shared();
+        let server = {
+            let signal = signal.clone();
+            async move {
+                match future::select(listen, signal).await {
+                    Either::Left((ret, _)) => ret,
+                    Either::Right(_) => future::pending().await,
+                }
             }
-        }
+        };
 
-        Ok(())
+        graceful_shutdown(&app, server, signal).await
     }
 }
 
@@ -1,11 +1,16 @@
-use futures::stream::TryStreamExt;
+use futures::{future::ready, stream::TryStreamExt, SinkExt, StreamExt};
 use hashira::{
     app::AppService,
+    error::BoxError,
     types::TryBoxStream,
-    web::{Body, Bytes, BytesMut, Payload, RemoteAddr, Request, Response},
+    web::{
+        header::{self, HeaderValue},
+        Body, Bytes, BytesMut, CloseFrame, Message, Payload, RemoteAddr, Request, Response,
+        WebSocket, WebSocketHandler,
+    },
 };
 use std::{convert::Infallible, fmt::Debug, net::SocketAddr};
-use warp::{path::FullPath, reject::Reject, Buf, Filter, Stream};
+use warp::{path::FullPath, reject::Reject, ws::Ws, Buf, Filter, Reply, Stream};
 
 struct HashiraRejection(Box<dyn std::error::Error + Send + Sync>);
 impl Debug for HashiraRejection {
@@ -54,6 +59,7 @@ fn hashira_filter(
         headers: warp::hyper::HeaderMap,
         method: warp::http::Method,
         remote_addr: Option<SocketAddr>,
+        ws: Option<Ws>,
         service: AppService,
     ) -> Result<impl warp::Reply, warp::Rejection> {
         {
@@ -88,8 +94,16 @@ fn hashira_filter(
             }
 
             // Send the request to hashira, and get the warp response
-            let warp_res = try_reject!(handle_request(warp_req, service.clone()).await);
-            Ok(warp_res)
+            let mut res = service.handle(map_request(warp_req)).await;
+
+            // The handler accepted the websocket connection
+            if let (Some(ws), Some(handler)) =
+                (ws, res.extensions_mut().remove::<WebSocketHandler>())
+            {
+                return Ok(upgrade_websocket(ws, handler));
+            }
+
+            Ok(map_response(res))
         }
     }
 
@@ -99,6 +113,7 @@ fn hashira_filter(
         .and(warp::filters::header::headers_cloned())
         .and(warp::method())
         .and(warp::filters::addr::remote())
+        .and(warp::ws().map(Some).or(warp::any().map(|| None)).unify())
         .and(with_service(app_service))
         .and_then(handler)
 }
@@ -131,6 +146,63 @@ fn map_response(res: Response) -> warp::hyper::Response<warp::hyper::Body> {
     warp::hyper::Response::from_parts(parts, body)
 }
 
+fn upgrade_websocket(ws: Ws, handler: WebSocketHandler) -> warp::reply::Response {
+    let protocol = handler
+        .protocol()
+        .and_then(|x| HeaderValue::from_str(x).ok());
+
+    let mut res = ws
+        .on_upgrade(move |socket| {
+            let (sink, stream) = socket.split();
+            let sink = sink
+                .sink_map_err(BoxError::from)
+                .with(|msg| ready(Ok::<_, BoxError>(map_to_warp_message(msg))));
+            let stream = stream.map_ok(map_from_warp_message).map_err(BoxError::from);
+
+            handler.call(WebSocket::from_split(sink, stream))
+        })
+        .into_response();
+
+    if let Some(protocol) = protocol {
+        res.headers_mut()
+            .insert(header::SEC_WEBSOCKET_PROTOCOL, protocol);
+    }
+
+    res
+}
+
+fn map_from_warp_message(msg: warp::ws::Message) -> Message {
+    if let Some((code, reason)) = msg.close_frame() {
+        return Message::Close(Some(CloseFrame {
+            code,
+            reason: reason.to_owned(),
+        }));
+    }
+
+    if msg.is_close() {
+        Message::Close(None)
+    } else if msg.is_text() {
+        Message::Text(String::from_utf8_lossy(msg.as_bytes()).into_owned())
+    } else if msg.is_ping() {
+        Message::Ping(msg.into_bytes().into())
+    } else if msg.is_pong() {
+        Message::Pong(msg.into_bytes().into())
+    } else {
+        Message::Binary(msg.into_bytes().into())
+    }
+}
+
+fn map_to_warp_message(msg: Message) -> warp::ws::Message {
+    match msg {
+        Message::Text(text) => warp::ws::Message::text(text),
+        Message::Binary(bytes) => warp::ws::Message::binary(bytes),
+        Message::Ping(bytes) => warp::ws::Message::ping(bytes),
+        Message::Pong(bytes) => warp::ws::Message::pong(bytes),
+        Message::Close(Some(frame)) => warp::ws::Message::close_with(frame.code, frame.reason),
+        Message::Close(None) => warp::ws::Message::close(),
+    }
+}
+
 fn get_current_dir() -> std::path::PathBuf {
     let mut current_dir = std::env::current_exe().expect("failed to get current directory");
     current_dir.pop();
@@ -1,6 +1,10 @@
 pub mod core;
 
-use hashira::adapter::Adapter;
+use futures::FutureExt;
+use hashira::{
+    adapter::{graceful_shutdown, Adapter},
+    types::BoxFuture,
+};
 use std::net::SocketAddr;
 
 // A placeholder for an empty filter.
@@ -28,26 +32,41 @@ where
     F: warp::Filter<Error = warp::Rejection> + Send + Sync + Clone + 'static,
     F::Extract: warp::reply::Reply,
 {
-    async fn serve(mut self, app: hashira::app::AppService) -> Result<(), hashira::error::BoxError> {
+    /// Starts the server.
+    async fn serve(self, app: hashira::app::AppService) -> Result<(), hashira::error::BoxError> {
+        self.serve_with_shutdown(app, Box::pin(futures::future::pending()))
+            .await
+    }
+
+    async fn serve_with_shutdown(
+        mut self,
+        app: hashira::app::AppService,
+        signal: BoxFuture<()>,
+    ) -> Result<(), hashira::error::BoxError> {
         let host = hashira::env::get_host().unwrap_or_else(|| String::from("127.0.0.1"));
         let port = hashira::env::get_port().unwrap_or(5000);
         let addr: SocketAddr = format!("{host}:{port}").as_str().parse().unwrap();
 
         println!("Server started at: http://{addr}");
 
-        let filter = crate::core::router(app);
+        let filter = crate::core::router(app.clone());
+        let signal = signal.shared();
 
-        match self.0.take() {
+        let server = match self.0.take() {
             Some(this) => {
                 let routes = this.or(filter);
-                warp::serve(routes).run(addr).await;
+                let (_, server) =
+                    warp::serve(routes).bind_with_graceful_shutdown(addr, signal.clone());
+                server.boxed()
             }
             None => {
-                warp::serve(filter).run(addr).await;
+                let (_, server) =
+                    warp::serve(filter).bind_with_graceful_shutdown(addr, signal.clone());
+                server.boxed()
             }
-        }
+        };
 
-        Ok(())
+        graceful_shutdown(&app, server.map(Ok::<_, std::convert::Infallible>), signal).await
     }
 }
 
@@ -1,5 +1,11 @@
 // #[action] <-- autogenerated route
 // #[action("/route")]
+// #[action("/route", cors = "path::to::function")]
+// #[action(cors = "path::to::function")]
+// #[action("/route", guard = "path::to::function")]
+// #[action("/route", body_limit = "1024 * 1024")]
+// #[action("/route", timeout = "std::time::Duration::from_secs(10)")]
+// #[action("/route", rate_limit = "path::to::function")]
 
 use proc_macro2::TokenStream;
 use syn::{parse::Parse, spanned::Spanned, ItemFn, LitStr};
@@ -7,6 +13,11 @@ use syn::{parse::Parse, spanned::Spanned, ItemFn, LitStr};
 #[derive(Clone)]
 pub struct ActionAttr {
     route: Option<String>,
+    cors: Option<syn::Path>,
+    guard: Option<syn::Path>,
+    body_limit: Option<syn::Expr>,
+    timeout: Option<syn::Expr>,
+    rate_limit: Option<syn::Path>,
 }
 
 impl Parse for ActionAttr {
@@ -15,9 +26,43 @@ impl Parse for ActionAttr {
             .parse()
             .map_err(|_| syn::Error::new(input.span(), "server actions should declare a route"))?;
 
-        Ok(ActionAttr {
-            route: route.map(|x| x.value()),
-        })
+        let mut attr = ActionAttr {
+            route: route.as_ref().map(|x| x.value()),
+            cors: None,
+            guard: None,
+            body_limit: None,
+            timeout: None,
+            rate_limit: None,
+        };
+
+        let mut first = route.is_none();
+
+        while !input.is_empty() {
+            if !first {
+                let _comma: syn::Token![,] = input.parse()?;
+            }
+
+            first = false;
+
+            let ident_span = input.span();
+            let ident: syn::Ident = input.parse()?;
+            let _equals: syn::Token![=] = input.parse()?;
+            let value: LitStr = input.parse()?;
+
+            match ident.to_string().as_str() {
+                "cors" => attr.cors = Some(value.parse()?),
+                "guard" => attr.guard = Some(value.parse()?),
+                "body_limit" => attr.body_limit = Some(value.parse()?),
+                "timeout" => attr.timeout = Some(value.parse()?),
+                "rate_limit" => attr.rate_limit = Some(value.parse()?),
+                _ => return Err(syn::Error::new(
+                    ident_span,
+                    "invalid signature, expected: #[action(\"/route\", cors = \"path::to::cors\", guard = \"path::to::guard\", body_limit = \"bytes\", timeout = \"duration\", rate_limit = \"path::to::rate_limit\")]",
+                )),
+            }
+        }
+
+        Ok(attr)
     }
 }
 
@@ -51,6 +96,46 @@ pub fn action_impl(attr: ActionAttr, item_fn: ItemFn) -> syn::Result<TokenStream
         }
     };
 
+    let cors = attr.cors.map(|cors| {
+        quote::quote! {
+            fn cors() -> ::std::option::Option<::hashira::cors::Cors> {
+                ::std::option::Option::Some(#cors())
+            }
+        }
+    });
+
+    let guard = attr.guard.map(|guard| {
+        quote::quote! {
+            fn guard() -> ::std::option::Option<::std::sync::Arc<dyn ::hashira::routing::Guard>> {
+                ::std::option::Option::Some(::std::sync::Arc::new(#guard()))
+            }
+        }
+    });
+
+    let body_limit = attr.body_limit.map(|limit| {
+        quote::quote! {
+            fn body_limit() -> ::std::option::Option<::hashira::web::BodyLimit> {
+                ::std::option::Option::Some(::hashira::web::BodyLimit::new(#limit))
+            }
+        }
+    });
+
+    let timeout = attr.timeout.map(|timeout| {
+        quote::quote! {
+            fn timeout() -> ::std::option::Option<::hashira::app::RequestTimeout> {
+                ::std::option::Option::Some(::std::convert::Into::into(#timeout))
+            }
+        }
+    });
+
+    let rate_limit = attr.rate_limit.map(|rate_limit| {
+        quote::quote! {
+            fn rate_limit() -> ::std::option::Option<::hashira::rate_limit::RateLimit> {
+                ::std::option::Option::Some(#rate_limit())
+            }
+        }
+    });
+
     // We rename the function to `_{name}`
     let mut new_item_fn = item_fn.clone();
     let new_item_fn_ident = syn::Ident::new(&format!("_{name}"), name.span());
@@ -85,6 +170,16 @@ pub fn action_impl(attr: ActionAttr, item_fn: ItemFn) -> syn::Result<TokenStream
                    #route
                 }
 
+                #cors
+
+                #guard
+
+                #body_limit
+
+                #timeout
+
+                #rate_limit
+
                 fn call(ctx: ::hashira::app::RequestContext, body: ::hashira::web::Body) -> ::hashira::types::BoxFuture<::hashira::Result<Self::Response>> {
                     let fut = ::hashira::actions::call_action(ctx, body, #new_item_fn_ident);
                     ::std::boxed::Box::pin(fut)
@@ -130,4 +225,4 @@ fn to_snake_case(s: &str) -> String {
         result.push(c.to_ascii_lowercase());
     }
     result
-}
\ No newline at end of file
+}
@@ -4,11 +4,13 @@ use syn::{parse::Parse, ItemFn, LitStr, Result};
 // #[page_component("/route")]
 // #[page_component("/route", render = "path::to::function")]
 // #[page_component(None, render = "path::to::function")]
+// #[page_component("/route", render = "path::to::function", guard = "path::to::function")]
 
 #[derive(Clone)]
 pub struct PageComponentAttr {
     route: Option<LitStr>,
     render: Option<Ident>,
+    guard: Option<syn::Path>,
 }
 
 impl Parse for PageComponentAttr {
@@ -32,39 +34,35 @@ impl Parse for PageComponentAttr {
             }
         };
 
-        let _comma: Option<syn::Token![,]> = input.parse()?;
-
-        if _comma.is_none() {
-            if !input.is_empty() {
-                return Err(input.error(
-                    "expected #[page_component(\"/route\", render = \"path::to::render\")]",
-                ));
-            }
+        let mut attr = PageComponentAttr {
+            route,
+            render: None,
+            guard: None,
+        };
 
-            return Ok(PageComponentAttr {
-                route,
-                render: None,
-            });
-        }
+        while !input.is_empty() {
+            let _comma: syn::Token![,] = input.parse().map_err(|_| {
+                input.error("expected #[page_component(\"/route\", render = \"path::to::render\")]")
+            })?;
 
-        let ident_span = input.span();
-        let ident: syn::Path = input.parse()?;
+            let ident_span = input.span();
+            let ident: syn::Path = input.parse()?;
+            let _equals: syn::Token![=] = input.parse()?;
+            let value: LitStr = input.parse()?;
 
-        if !ident.is_ident("render") {
-            return Err(syn::Error::new(
-                ident_span,
-                "invalid signature, expected: #[page_component(render = \"path::to::render\")]",
-            ));
+            if ident.is_ident("render") {
+                attr.render = Some(Ident::new(&value.value(), Span::call_site()));
+            } else if ident.is_ident("guard") {
+                attr.guard = Some(value.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    ident_span,
+                    "invalid signature, expected: #[page_component(render = \"path::to::render\", guard = \"path::to::guard\")]",
+                ));
+            }
         }
 
-        let _equals: syn::Token![=] = input.parse()?;
-        let render_str: LitStr = input.parse()?;
-        let render = Ident::new(&render_str.value(), Span::call_site());
-
-        Ok(PageComponentAttr {
-            route,
-            render: Some(render),
-        })
+        Ok(attr)
     }
 }
 
@@ -97,6 +95,14 @@ pub fn page_component_impl(attr: PageComponentAttr, item_fn: ItemFn) -> syn::Res
         }
     };
 
+    let guard = attr.guard.map(|guard| {
+        quote::quote! {
+            fn guard() -> Option<::std::sync::Arc<dyn ::hashira::routing::Guard>> {
+                Some(::std::sync::Arc::new(#guard()))
+            }
+        }
+    });
+
     // TODO: the id should always produce the same result across server and client
     // if the code is the same. Currently we are exposing information
     // about the module where the component is declared, which may or not be relevant.
@@ -113,6 +119,8 @@ pub fn page_component_impl(attr: PageComponentAttr, item_fn: ItemFn) -> syn::Res
                 #route
             }
 
+            #guard
+
             fn render<BASE>(ctx: ::hashira::app::RenderContext, body: ::hashira::web::Body)
                 -> ::hashira::types::BoxFuture<std::result::Result<::hashira::web::Response, ::hashira::error::BoxError>>
                 where
@@ -15,6 +15,7 @@ use syn::spanned::Spanned;
 /// - `#[page_component("/route")]`
 /// - `#[page_component("/route", loader = "path::to::function")]`
 /// - `#[page_component(None, loader = "path::to::function")]`
+/// - `#[page_component("/route", guard = "path::to::function")]`
 ///
 /// # Example
 ///
@@ -48,6 +49,11 @@ pub fn page_component(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// You can decorate a function using any:
 /// - `[action]` to create a server action with a generated route
 /// - `[action("/route/to/action")]` to create a server action with an explicit route.
+/// - `[action("/route/to/action", cors = "path::to::cors")]` to use the `Cors` returned by the function.
+/// - `[action("/route/to/action", guard = "path::to::guard")]` to protect the action with the `Guard` returned by the function.
+/// - `[action("/route/to/action", body_limit = "10 * 1024 * 1024")]` to set the max size in bytes of the request body.
+/// - `[action("/route/to/action", timeout = "std::time::Duration::from_secs(10)")]` to set the max time to process a request.
+/// - `[action("/route/to/action", rate_limit = "path::to::rate_limit")]` to limit the requests with the `RateLimit` returned by the function.
 ///
 /// # Example
 /// ```rs,no_run
@@ -1,6 +1,12 @@
 use super::{into_request_config::IntoRequestConfig, Action};
-use crate::{error::BoxError, web::IntoJsonResponse};
+use crate::{
+    components::{use_error_boundary, ErrorBoundaryHandle},
+    csrf::{use_csrf_token, CsrfToken},
+    error::{BoxError, ProblemDetails},
+    web::{IntoJsonResponse, ValidationErrors},
+};
 use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use serde::de::DeserializeOwned;
 use std::{fmt::Debug, marker::PhantomData, ops::Deref, rc::Rc};
 use web_sys::AbortSignal;
 use yew::{hook, use_state, Callback, UseStateHandle};
@@ -92,6 +98,7 @@ impl<A: Action> Deref for UseActionRef<A> {
 pub struct UseActionOptions<A: Action> {
     on_complete: Option<Callback<UseActionRef<A>>>,
     signal: Option<AbortSignal>,
+    error_boundary: bool,
 }
 
 impl<A: Action> UseActionOptions<A> {
@@ -111,6 +118,13 @@ impl<A: Action> UseActionOptions<A> {
         self.signal = Some(signal);
         self
     }
+
+    /// Reports the errors of the action to the nearest `ErrorBoundary`,
+    /// which renders its fallback in place of its children.
+    pub fn error_boundary(mut self) -> Self {
+        self.error_boundary = true;
+        self
+    }
 }
 
 impl<A: Action> Debug for UseActionOptions<A> {
@@ -118,6 +132,7 @@ impl<A: Action> Debug for UseActionOptions<A> {
         f.debug_struct("UseActionOptions")
             .field("on_complete", &self.on_complete)
             .field("signal", &self.signal)
+            .field("error_boundary", &self.error_boundary)
             .finish()
     }
 }
@@ -127,6 +142,7 @@ impl<A: Action> Clone for UseActionOptions<A> {
         Self {
             on_complete: self.on_complete.clone(),
             signal: self.signal.clone(),
+            error_boundary: self.error_boundary,
         }
     }
 }
@@ -136,6 +152,7 @@ impl<A: Action> Default for UseActionOptions<A> {
         Self {
             on_complete: Default::default(),
             signal: Default::default(),
+            error_boundary: false,
         }
     }
 }
@@ -148,6 +165,8 @@ where
     loading: UseStateHandle<bool>,
     result: UseStateHandle<Option<Rc<ActionResult<A>>>>,
     options: UseActionOptions<A>,
+    csrf: Option<CsrfToken>,
+    boundary: ErrorBoundaryHandle,
     _marker: PhantomData<T>,
 }
 
@@ -179,6 +198,29 @@ where
         self.result.as_deref().and_then(|x| x.as_ref().err())
     }
 
+    /// Returns the validation errors if the action was rejected with a `422 Unprocessable Entity`.
+    pub fn validation_errors(&self) -> Option<&ValidationErrors> {
+        self.error()
+            .and_then(|err| err.downcast_ref::<ValidationErrors>())
+    }
+
+    /// Returns the problem details if the action returned an `application/problem+json` error.
+    pub fn problem_details(&self) -> Option<&ProblemDetails> {
+        self.error().and_then(|err| err.downcast_ref::<ProblemDetails>())
+    }
+
+    /// Deserializes the custom members of the problem details of the error, if any.
+    pub fn error_data<E: DeserializeOwned>(&self) -> Option<E> {
+        self.problem_details().and_then(|problem| problem.data().ok())
+    }
+
+    /// Returns the validation errors of the given field.
+    pub fn field_errors(&self, field: &str) -> &[String] {
+        self.validation_errors()
+            .map(|errors| errors.field(field))
+            .unwrap_or_default()
+    }
+
     /// Sends a request to the server.
     #[cfg(not(target_arch = "wasm32"))]
     #[allow(unused_variables)]
@@ -203,6 +245,7 @@ where
     #[cfg(target_arch = "wasm32")]
     pub fn send_with_options(&self, obj: T, options: RequestOptions) -> Result<(), BoxError> {
         use crate::actions::into_request_config::RequestInitConfig;
+        use crate::components::BoundaryError;
         use crate::utils::wasm::fetch_json;
         use crate::error::JsError;
         use wasm_bindgen::{JsCast, JsValue};
@@ -252,6 +295,14 @@ where
             }
         };
 
+        // Sends the CSRF token if was not already set
+        let mut options = options;
+        if let Some(csrf) = &self.csrf {
+            if !options.headers.contains_key(csrf.header.as_str()) {
+                options = options.try_header(csrf.header.as_str(), csrf.value.as_str())?;
+            }
+        }
+
         let mut last_name = None;
         for (name, value) in options.headers {
             if let Some(name) = name {
@@ -276,6 +327,7 @@ where
 
         let request = web_sys::Request::new_with_str_and_init(&url, &init).map_err(JsError::new)?;
         let on_complete = self.options.on_complete.clone();
+        let boundary = Some(self.boundary.clone()).filter(|_| self.options.error_boundary);
 
         wasm_bindgen_futures::spawn_local(async move {
             let _guard = _guard;
@@ -285,6 +337,12 @@ where
                 on_complete.emit(UseActionRef(ret.clone()));
             }
 
+            if let Some(boundary) = boundary {
+                if let Some(error) = BoundaryError::from_result(ret.clone()) {
+                    boundary.dispatch(error);
+                }
+            }
+
             result.set(Some(ret));
         });
 
@@ -301,6 +359,8 @@ where
             loading: self.loading.clone(),
             result: self.result.clone(),
             options: self.options.clone(),
+            csrf: self.csrf.clone(),
+            boundary: self.boundary.clone(),
             _marker: self._marker,
         }
     }
@@ -363,11 +423,15 @@ where
 {
     let result = use_state(|| None);
     let loading = use_state(|| false);
+    let csrf = use_csrf_token();
+    let boundary = use_error_boundary();
 
     UseActionHandle {
         result,
         loading,
         options,
+        csrf,
+        boundary,
         _marker: PhantomData,
     }
 }
@@ -30,6 +30,31 @@ pub trait Action: 'static {
             | RouteMethod::DELETE
     }
 
+    /// Returns the CORS configuration of this action, if any.
+    fn cors() -> Option<crate::cors::Cors> {
+        None
+    }
+
+    /// Returns the guard of this action, if any.
+    fn guard() -> Option<std::sync::Arc<dyn crate::routing::Guard>> {
+        None
+    }
+
+    /// Returns the max size of the request body of this action, if any.
+    fn body_limit() -> Option<crate::web::BodyLimit> {
+        None
+    }
+
+    /// Returns the max time to process a request of this action, if any.
+    fn timeout() -> Option<crate::app::RequestTimeout> {
+        None
+    }
+
+    /// Returns the rate limit of this action, if any.
+    fn rate_limit() -> Option<crate::rate_limit::RateLimit> {
+        None
+    }
+
     /// Call this action and returns a response.
     fn call(ctx: RequestContext, body: Body) -> BoxFuture<crate::Result<Self::Response>>;
 }
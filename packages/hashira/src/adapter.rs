@@ -1,8 +1,67 @@
-use crate::{app::AppService, error::BoxError};
+use crate::{app::AppService, error::BoxError, types::BoxFuture};
+use futures::{future::Either, Future};
 
 /// Base trait for adapters.
 #[async_trait::async_trait]
 pub trait Adapter {
     /// Starts the server.
     async fn serve(self, app: AppService) -> Result<(), BoxError>;
+
+    /// Starts the server and stops it when the `signal` completes.
+    ///
+    /// After the signal the server stops accepting connections and waits for the in-flight requests
+    /// up to the [`shutdown_timeout`], then runs the `on_server_shutdown` hooks.
+    ///
+    /// The default implementation ignores the `signal` and calls [`Adapter::serve`],
+    /// adapters supporting graceful shutdown should override it.
+    ///
+    /// [`shutdown_timeout`]: crate::app::App::shutdown_timeout
+    async fn serve_with_shutdown(
+        self,
+        app: AppService,
+        signal: BoxFuture<()>,
+    ) -> Result<(), BoxError>
+    where
+        Self: Sized,
+    {
+        log::warn!("the adapter don't support graceful shutdown, the shutdown signal is ignored");
+        drop(signal);
+        self.serve(app).await
+    }
+}
+
+/// Runs the `server` until it completes, or until the `signal` completes and
+/// the in-flight requests are drained, then runs the shutdown hooks of the app.
+///
+/// The `server` should stop accepting new connections when the signal completes.
+pub async fn graceful_shutdown<S, E>(
+    app: &AppService,
+    server: S,
+    signal: impl Future<Output = ()>,
+) -> Result<(), BoxError>
+where
+    S: Future<Output = Result<(), E>>,
+    E: Into<BoxError>,
+{
+    let drained = async {
+        signal.await;
+
+        if !app.drain().await {
+            log::warn!(
+                "shutdown timeout elapsed with {} requests in-flight",
+                app.in_flight_requests()
+            );
+        }
+    };
+
+    let server = std::pin::pin!(server);
+    let drained = std::pin::pin!(drained);
+
+    let ret = match futures::future::select(server, drained).await {
+        Either::Left((ret, _)) => ret.map_err(Into::into),
+        Either::Right(_) => Ok(()),
+    };
+
+    app.shutdown().await;
+    ret
 }
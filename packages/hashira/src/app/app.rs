@@ -1,5 +1,6 @@
 use super::{
     router::{PageRouter, PageRouterWrapper},
+    shutdown::{OnServerShutdown, Shutdown, DEFAULT_SHUTDOWN_TIMEOUT},
     AppData, AppNested, AppService, AppServiceInner, DefaultHeaders, Handler, LayoutContext,
     RequestContext,
 };
@@ -10,7 +11,7 @@ use crate::{
         id::PageId,
         PageComponent,
     },
-    error::{BoxError, ServerError},
+    error::BoxError,
     routing::{ClientPageRoute, ErrorRouter, Route, ServerErrorRouter, ServerRouter},
     types::BoxFuture,
     web::{Body, FromRequest, IntoResponse, Redirect, Response},
@@ -18,7 +19,7 @@ use crate::{
 
 use http::{status::StatusCode, HeaderMap};
 use serde::de::DeserializeOwned;
-use std::{future::Future, marker::PhantomData, pin::Pin, sync::Arc};
+use std::{future::Future, marker::PhantomData, pin::Pin, sync::Arc, time::Duration};
 use yew::{html::ChildrenProps, BaseComponent, Html};
 
 type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send + Sync + 'static>>;
@@ -43,14 +44,27 @@ impl PageHandler {
         PageHandler(Box::new(move |ctx, mut body| {
             let handler = handler.clone();
             Box::pin(async move {
-                let args = match Args::from_request(&ctx, &mut body).await {
+                let extract = Args::from_request(&ctx, &mut body);
+
+                #[cfg(feature = "tracing")]
+                let extract = tracing::Instrument::instrument(
+                    extract,
+                    tracing::info_span!("extract", args = std::any::type_name::<Args>()),
+                );
+
+                let args = match extract.await {
                     Ok(x) => x,
                     Err(err) => {
-                        return ServerError::from_error(err).into_response();
+                        return crate::web::rejection_response(&ctx, err.into());
                     }
                 };
-                let ret = handler.call(args).await;
-                ret.into_response()
+
+                let ret = handler.call(args);
+
+                #[cfg(feature = "tracing")]
+                let ret = tracing::Instrument::instrument(ret, tracing::info_span!("handler"));
+
+                ret.await.into_response()
             })
         }))
     }
@@ -92,6 +106,8 @@ pub struct App<BASE> {
     server_error_router: ServerErrorRouter,
     app_data: AppData,
     default_headers: HeaderMap,
+    shutdown_hooks: Vec<OnServerShutdown>,
+    shutdown_timeout: Duration,
     _marker: PhantomData<BASE>,
 
     #[cfg(feature = "hooks")]
@@ -109,6 +125,8 @@ impl<BASE> App<BASE> {
             server_error_router: ServerErrorRouter::new(),
             app_data: Default::default(),
             default_headers: Default::default(),
+            shutdown_hooks: Default::default(),
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
             _marker: PhantomData,
 
             #[cfg(feature = "hooks")]
@@ -178,7 +196,41 @@ where
         {
             use super::InsertInRootRoute;
 
-            for (sub, route) in scope.server_router {
+            for (sub, mut route) in scope.server_router {
+                if let Some(cors) = &scope.cors {
+                    if route.extensions().get::<crate::cors::Cors>().is_none() {
+                        route.extensions_mut().insert(cors.clone());
+                    }
+                }
+
+                if let Some(timeout) = scope.timeout {
+                    if route.extensions().get::<super::RequestTimeout>().is_none() {
+                        route.extensions_mut().insert(timeout);
+                    }
+                }
+
+                if let Some(rate_limit) = &scope.rate_limit {
+                    if route
+                        .extensions()
+                        .get::<crate::rate_limit::RateLimit>()
+                        .is_none()
+                    {
+                        route.extensions_mut().insert(rate_limit.clone());
+                    }
+                }
+
+                // The guards of the scope run before the guards of the route
+                if !scope.guards.is_empty() {
+                    use crate::routing::RouteGuards;
+
+                    let mut guards = scope.guards.clone();
+                    if let Some(route_guards) = route.extensions_mut().remove::<RouteGuards>() {
+                        guards.extend(route_guards.0);
+                    }
+
+                    route.extensions_mut().insert(RouteGuards(guards));
+                }
+
                 let path = match sub.as_str() {
                     "/" => base_path.to_owned(),
                     _ if route.extensions().get::<InsertInRootRoute>().is_some() => sub.to_owned(),
@@ -235,6 +287,10 @@ where
                 COMP::render::<BASE>(render_ctx, body)
             });
 
+            if let Some(guard) = COMP::guard() {
+                route.add_guard(guard);
+            }
+
             route.extensions_mut().insert(HandlerKind::Page);
             self.route(route)
         }
@@ -326,8 +382,14 @@ where
             let mut route = Route::new(
                 &path,
                 method,
-                |ctx: RequestContext, body: Body| async move {
-                    let output = crate::try_response!(A::call(ctx, body).await);
+                |ctx: RequestContext, mut body: Body| async move {
+                    if let Err(err) = crate::csrf::verify_request(&ctx, &mut body).await {
+                        return crate::web::rejection_response(&ctx, err);
+                    }
+                    let output = match A::call(ctx.clone(), body).await {
+                        Ok(output) => output,
+                        Err(err) => return crate::web::rejection_response(&ctx, err),
+                    };
                     let json_res = crate::try_response!(output.into_json_response());
                     let (parts, body) = json_res.into_parts();
                     let bytes = crate::try_response!(serde_json::to_vec(&body));
@@ -336,6 +398,29 @@ where
                 },
             );
 
+            if let Some(cors) = A::cors() {
+                route.extensions_mut().insert(cors);
+            }
+
+            if let Some(guard) = A::guard() {
+                route.add_guard(guard);
+            }
+
+            if let Some(limit) = A::body_limit() {
+                route.extensions_mut().insert(limit);
+            }
+
+            if let Some(timeout) = A::timeout() {
+                route.extensions_mut().insert(timeout);
+            }
+
+            if let Some(rate_limit) = A::rate_limit() {
+                route.extensions_mut().insert(rate_limit);
+            }
+
+            route
+                .extensions_mut()
+                .insert(crate::metrics::ActionName(std::any::type_name::<A>()));
             route.extensions_mut().insert(HandlerKind::Action);
             self.route(route)
         }
@@ -389,6 +474,157 @@ where
         self
     }
 
+    /// Enables the CSRF protection for the server actions.
+    pub fn csrf(self, csrf: crate::csrf::CsrfProtection) -> Self {
+        self.app_data(csrf)
+    }
+
+    /// Enables the internationalization of the pages.
+    pub fn i18n(self, i18n: crate::i18n::I18n) -> Self {
+        self.app_data(i18n)
+    }
+
+    /// Registers the authenticator used to resolve the user of the requests.
+    pub fn authenticator<A>(self, authenticator: A) -> Self
+    where
+        A: crate::auth::Authenticator,
+    {
+        self.server_data(crate::auth::AuthenticatorService::new(authenticator))
+    }
+
+    /// Serializes the user of the registered authenticator into the page data,
+    /// so it can be read on the client with `use_user`.
+    ///
+    /// The user is sent to the browser, avoid including any sensitive field in it.
+    pub fn expose_user(self) -> Self {
+        self.server_data(crate::auth::ExposeUser)
+    }
+
+    /// Sets the max size of the request bodies, this can be overriden by routes and actions.
+    pub fn body_limit(self, limit: impl Into<crate::web::BodyLimit>) -> Self {
+        self.server_data(limit.into())
+    }
+
+    /// Sets the max time to process the requests, this can be overriden by scopes, routes and actions.
+    ///
+    /// The event streams and protocol upgrades are long-lived, so these are not ended by the stream timeout.
+    pub fn timeout(self, timeout: impl Into<super::RequestTimeout>) -> Self {
+        self.server_data(timeout.into())
+    }
+
+    /// Sets whether to display the details of the server errors of the pages in debug builds,
+    /// enabled by default, this is never displayed in release builds.
+    pub fn error_overlay(self, enabled: bool) -> Self {
+        self.server_data(super::error_overlay::ErrorOverlayEnabled(enabled))
+    }
+
+    /// Sets the limits used to read the multipart forms.
+    pub fn multipart_config(self, config: crate::web::MultipartConfig) -> Self {
+        self.server_data(config)
+    }
+
+    /// Sets the function used to render the rejections of the built-in extractors,
+    /// by default are returned as `application/problem+json`.
+    pub fn rejection_handler<F>(self, f: F) -> Self
+    where
+        F: Fn(crate::web::Rejection, &RequestContext) -> Response + Send + Sync + 'static,
+    {
+        self.server_data(crate::web::RejectionHandler(std::sync::Arc::new(f)))
+    }
+
+    /// Sets the CORS configuration for all the routes,
+    /// this can be overriden by scopes and actions.
+    pub fn cors(self, cors: crate::cors::Cors) -> Self {
+        self.app_data(cors)
+    }
+
+    /// Sets the rate limit for all the routes,
+    /// this can be overriden by scopes, routes and actions.
+    pub fn rate_limit(self, rate_limit: crate::rate_limit::RateLimit) -> Self {
+        self.server_data(rate_limit)
+    }
+
+    /// Enables the collection of metrics of the requests, renders and actions,
+    /// if the metrics have an endpoint a route is added to expose them.
+    pub fn metrics(self, metrics: crate::metrics::Metrics) -> Self {
+        let this = match metrics.endpoint_path() {
+            Some(path) => {
+                let route = Route::get(path, {
+                    let metrics = metrics.clone();
+                    move || {
+                        let res = metrics.to_response();
+                        async move { res }
+                    }
+                });
+
+                self.route(route)
+            }
+            None => self,
+        };
+
+        this.server_data(metrics)
+    }
+
+    /// Adds the liveness and readiness endpoints that run the given checks,
+    /// by default in `/healthz` and `/readyz`.
+    pub fn health_checks(self, checks: crate::health::HealthChecks) -> Self {
+        #[cfg(not(feature = "client"))]
+        let this = {
+            let [liveness, readiness] = checks.routes();
+            self.route(liveness).route(readiness)
+        };
+
+        #[cfg(feature = "client")]
+        let this = self;
+
+        this.server_data(checks)
+    }
+
+    /// Enables the maintenance mode, while enabled the requests are answered with a `503 Service Unavailable`.
+    pub fn maintenance(self, maintenance: crate::maintenance::Maintenance) -> Self {
+        self.server_data(maintenance)
+    }
+
+    /// Adds the page rendered during the maintenance,
+    /// without it the error page for `503 Service Unavailable` is rendered.
+    pub fn maintenance_page<COMP>(mut self) -> Self
+    where
+        COMP: PageComponent,
+        COMP::Properties: DeserializeOwned,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            use crate::app::RenderContext;
+            use futures::TryFutureExt;
+
+            self.server_error_router
+                .maintenance(ErrorPageHandler::new(move |ctx| {
+                    let head = super::page_head::PageHead::new();
+                    let render_layout = ctx.app_data::<RenderLayout>().cloned().unwrap();
+                    let render_ctx = RenderContext::new(ctx, head, render_layout);
+
+                    // Returns the future
+                    COMP::render::<BASE>(render_ctx, Body::empty()).map_ok(|x| x.into_response())
+                }));
+        }
+
+        self.add_maintenance_component::<COMP>();
+        self
+    }
+
+    /// Enables the client to report its panics to the server,
+    /// this adds a route to receive the reports.
+    pub fn client_error_reporting(self, reporting: crate::error::ClientErrorReporting) -> Self {
+        #[cfg(not(feature = "client"))]
+        let this = self.route(reporting.route());
+
+        #[cfg(feature = "client")]
+        let this = self;
+
+        // The client needs the endpoint to send the reports
+        this.app_data(reporting)
+    }
+
     /// Adds headers to always append in a response.
     #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
     pub fn default_headers(mut self, headers: DefaultHeaders) -> Self {
@@ -399,6 +635,31 @@ where
         self
     }
 
+    /// Adds a function to call when the server is shutting down, after the in-flight requests
+    /// completed, useful for closing database pools, flushing logs or stopping background tasks.
+    ///
+    /// The functions are called in the order they were added.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn on_server_shutdown<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(AppService) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            self.shutdown_hooks
+                .push(Box::new(move |service| Box::pin(f(service))));
+        }
+        self
+    }
+
+    /// Sets the max time to wait for the in-flight requests to complete when shutting down,
+    /// defaults to 30 seconds.
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
     /// Adds the given `Hooks`.
     #[cfg(feature = "hooks")]
     pub fn hooks(mut self, hooks: crate::events::Hooks) -> Self {
@@ -420,6 +681,8 @@ where
             server_error_router,
             default_headers,
             mut app_data,
+            shutdown_hooks,
+            shutdown_timeout,
             _marker: _,
 
             #[cfg(feature = "hooks")]
@@ -437,6 +700,10 @@ where
         #[cfg(feature = "hooks")]
         let hooks = Arc::new(hooks);
 
+        // Capture the location of the panics for the error overlay
+        #[cfg(all(debug_assertions, not(feature = "client")))]
+        crate::error::capture_panic_info();
+
         // Add startup app data
         app_data.insert::<RenderLayout>(layout); // The RenderContext require the RenderLayout
 
@@ -454,6 +721,7 @@ where
             client_error_router,
             server_error_router,
             default_headers,
+            shutdown: Shutdown::new(shutdown_hooks, shutdown_timeout),
 
             #[cfg(feature = "hooks")]
             hooks,
@@ -569,6 +837,35 @@ where
                 }
             }));
     }
+
+    fn add_maintenance_component<COMP>(&mut self)
+    where
+        COMP: PageComponent,
+        COMP::Properties: DeserializeOwned,
+    {
+        use crate::components::AnyComponent;
+
+        log::debug!(
+            "Registering maintenance component `{}`",
+            std::any::type_name::<COMP>()
+        );
+
+        let component = AnyComponent::<serde_json::Value>::new(|props_json| {
+            let props = serde_json::from_value(props_json).unwrap_or_else(|err| {
+                panic!(
+                    "Failed to deserialize `{}` component props. {err}",
+                    std::any::type_name::<COMP>()
+                )
+            });
+
+            yew::html! {
+                <COMP ..props/>
+            }
+        });
+
+        self.client_error_router
+            .maintenance(PageId::of::<COMP>(), component);
+    }
 }
 
 impl<BASE> Default for App<BASE> {
@@ -21,6 +21,22 @@ pub struct AppNested<BASE> {
     #[cfg(not(feature = "client"))]
     pub(crate) server_router: HashMap<String, Route>,
 
+    // CORS configuration for the routes of this scope
+    #[cfg(not(feature = "client"))]
+    pub(crate) cors: Option<crate::cors::Cors>,
+
+    // Guards for the routes of this scope
+    #[cfg(not(feature = "client"))]
+    pub(crate) guards: Vec<std::sync::Arc<dyn crate::routing::Guard>>,
+
+    // Timeout for the routes of this scope
+    #[cfg(not(feature = "client"))]
+    pub(crate) timeout: Option<super::RequestTimeout>,
+
+    // Rate limit for the routes of this scope
+    #[cfg(not(feature = "client"))]
+    pub(crate) rate_limit: Option<crate::rate_limit::RateLimit>,
+
     // Inner page router
     pub(crate) page_router: HashMap<String, ClientPageRoute>,
 
@@ -37,6 +53,14 @@ where
         AppNested {
             #[cfg(not(feature = "client"))]
             server_router: HashMap::new(),
+            #[cfg(not(feature = "client"))]
+            cors: None,
+            #[cfg(not(feature = "client"))]
+            guards: Vec::new(),
+            #[cfg(not(feature = "client"))]
+            timeout: None,
+            #[cfg(not(feature = "client"))]
+            rate_limit: None,
             page_router: HashMap::new(),
             _marker: PhantomData,
         }
@@ -54,6 +78,54 @@ where
         self
     }
 
+    /// Sets the CORS configuration for the routes of this scope,
+    /// routes with its own configuration are not overriden.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn cors(mut self, cors: crate::cors::Cors) -> Self {
+        #[cfg(not(feature = "client"))]
+        {
+            self.cors = Some(cors);
+        }
+
+        self
+    }
+
+    /// Adds a guard to all the routes of this scope,
+    /// these run before the guards of each route.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn guard(mut self, guard: impl crate::routing::Guard) -> Self {
+        #[cfg(not(feature = "client"))]
+        {
+            self.guards.push(std::sync::Arc::new(guard));
+        }
+
+        self
+    }
+
+    /// Sets the max time to process the requests of the routes of this scope,
+    /// routes with its own timeout are not overriden.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn timeout(mut self, timeout: impl Into<super::RequestTimeout>) -> Self {
+        #[cfg(not(feature = "client"))]
+        {
+            self.timeout = Some(timeout.into());
+        }
+
+        self
+    }
+
+    /// Sets the rate limit for the routes of this scope,
+    /// routes with its own rate limit are not overriden.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn rate_limit(mut self, rate_limit: crate::rate_limit::RateLimit) -> Self {
+        #[cfg(not(feature = "client"))]
+        {
+            self.rate_limit = Some(rate_limit);
+        }
+
+        self
+    }
+
     /// Adds a page for the given route.
     #[cfg_attr(feature = "client", allow(unused_variables))]
     pub fn page<COMP>(mut self) -> Self
@@ -85,6 +157,10 @@ where
                 COMP::render::<BASE>(render_ctx, body)
             });
 
+            if let Some(guard) = COMP::guard() {
+                route.add_guard(guard);
+            }
+
             route.extensions_mut().insert(HandlerKind::Page);
             self.route(route)
         }
@@ -107,8 +183,14 @@ where
             
             let route = A::route().to_string();
             let method = A::method();
-            let mut route = Route::new(&route, method, |ctx: RequestContext, body: Body| async move {
-                let output = crate::try_response!(A::call(ctx, body).await);
+            let mut route = Route::new(&route, method, |ctx: RequestContext, mut body: Body| async move {
+                if let Err(err) = crate::csrf::verify_request(&ctx, &mut body).await {
+                    return crate::web::rejection_response(&ctx, err);
+                }
+                let output = match A::call(ctx.clone(), body).await {
+                    Ok(output) => output,
+                    Err(err) => return crate::web::rejection_response(&ctx, err),
+                };
                 let json_res = crate::try_response!(output.into_json_response());
                 let (parts, body) = json_res.into_parts();
                 let bytes = crate::try_response!(serde_json::to_vec(&body));
@@ -116,7 +198,30 @@ where
                 Response::from_parts(parts, body)
             });
 
+            if let Some(cors) = A::cors() {
+                route.extensions_mut().insert(cors);
+            }
+
+            if let Some(guard) = A::guard() {
+                route.add_guard(guard);
+            }
+
+            if let Some(limit) = A::body_limit() {
+                route.extensions_mut().insert(limit);
+            }
+
+            if let Some(timeout) = A::timeout() {
+                route.extensions_mut().insert(timeout);
+            }
+
+            if let Some(rate_limit) = A::rate_limit() {
+                route.extensions_mut().insert(rate_limit);
+            }
+
             route.extensions_mut().insert(InsertInRootRoute);
+            route
+                .extensions_mut()
+                .insert(crate::metrics::ActionName(std::any::type_name::<A>()));
             route.extensions_mut().insert(HandlerKind::Action);
             self.route(route)
         }
@@ -1,13 +1,29 @@
-use super::{router::PageRouterWrapper, AppData, RequestContext};
+use super::{
+    error_overlay::{is_overlay_enabled, render_error_overlay, OverlayRoute},
+    router::PageRouterWrapper,
+    shutdown::Shutdown,
+    timeout::{with_stream_timeout, with_timeout},
+    AppData, ErrorPageHandler, RequestContext, RequestTimeout,
+};
 use crate::{
-    error::ServerError,
+    cors::Cors,
+    error::{catch_stream_panics, into_problem_response, ServerError, ServerPanic},
+    health::HealthChecks,
+    maintenance::Maintenance,
+    metrics::{ActionName, MatchedRoute, Metrics},
+    rate_limit::RateLimit,
     routing::{
-        ErrorRouter, HandlerKind, Params, ServerErrorRouter, ServerRouter, ServerRouterMatchError,
+        ErrorRouter, HandlerKind, Params, Route, RouteGuards, RouteMethod, ServerErrorRouter,
+        ServerRouter, ServerRouterMatchError,
+    },
+    web::{
+        Body, BodyLimit, IntoResponse, Request, RequestId, RequestStart, Response,
+        REQUEST_ID_HEADER,
     },
-    web::{Body, IntoResponse, Request, Response},
 };
-use http::{HeaderMap, StatusCode};
-use std::sync::Arc;
+use futures::FutureExt;
+use http::{header, HeaderMap, StatusCode};
+use std::{panic::AssertUnwindSafe, sync::Arc, time::Duration};
 
 pub(crate) struct AppServiceInner {
     pub(crate) server_router: ServerRouter,
@@ -16,6 +32,7 @@ pub(crate) struct AppServiceInner {
     pub(crate) client_error_router: Arc<ErrorRouter>,
     pub(crate) default_headers: HeaderMap,
     pub(crate) app_data: Arc<AppData>,
+    pub(crate) shutdown: Shutdown,
 
     #[cfg(feature = "hooks")]
     pub(crate) hooks: Arc<crate::events::Hooks>,
@@ -67,9 +84,103 @@ impl AppService {
         &self.0.client_error_router
     }
 
+    /// Returns the number of requests being processed,
+    /// a request with a streamed response is in-flight until the stream ends.
+    pub fn in_flight_requests(&self) -> usize {
+        self.0.shutdown.in_flight()
+    }
+
+    /// Returns the max time to wait for the in-flight requests when shutting down.
+    pub fn shutdown_timeout(&self) -> Duration {
+        self.0.shutdown.timeout()
+    }
+
+    /// Waits for the in-flight requests to complete up to the shutdown timeout,
+    /// returns `false` if the timeout elapsed before all the requests completed.
+    pub async fn drain(&self) -> bool {
+        self.0.shutdown.drain().await
+    }
+
+    /// Runs the `on_server_shutdown` hooks, the hooks are only called the first time.
+    ///
+    /// This should be called by the adapters after the requests were drained.
+    pub async fn shutdown(&self) {
+        let hooks = self.0.shutdown.take_hooks();
+
+        // We execute the hooks in the order they were added
+        for hook in hooks {
+            hook(self.clone()).await;
+        }
+    }
+
+    /// Enables or disables the maintenance mode at runtime.
+    ///
+    /// # Panics
+    /// If the app was not configured with [`App::maintenance`].
+    ///
+    /// [`App::maintenance`]: crate::app::App::maintenance
+    pub fn set_maintenance(&self, enabled: bool) {
+        self.0
+            .app_data
+            .get::<Maintenance>()
+            .expect("maintenance mode was not configured, use `App::maintenance`")
+            .set_enabled(enabled);
+    }
+
+    /// Returns `true` if the app is in maintenance mode.
+    pub fn is_maintenance(&self) -> bool {
+        self.0
+            .app_data
+            .get::<Maintenance>()
+            .map(|x| x.is_enabled())
+            .unwrap_or_default()
+    }
+
     /// Process the incoming request and return the response.
-    pub async fn handle(&self, req: Request) -> Response {
-        let mut res = self._handle(req).await;
+    pub async fn handle(&self, mut req: Request) -> Response {
+        let guard = self.0.shutdown.start_request();
+        let start = RequestStart::now();
+        let request_id = RequestId::from_headers(req.headers());
+        req.extensions_mut().insert(start);
+        req.extensions_mut().insert(request_id.clone());
+
+        let method = req.method().clone();
+
+        // The health checks are not traced or recorded by default
+        let record = !self
+            .0
+            .app_data
+            .get::<HealthChecks>()
+            .map(|checks| checks.is_unrecorded(req.uri().path()))
+            .unwrap_or_default();
+
+        #[cfg(feature = "tracing")]
+        let span = match record {
+            true => {
+                let span = crate::trace::request_span(&req, &request_id);
+                req.extensions_mut().insert(span.clone());
+                span
+            }
+            false => tracing::Span::none(),
+        };
+
+        let handle = self._handle(req);
+
+        #[cfg(feature = "tracing")]
+        let handle = tracing::Instrument::instrument(handle, span.clone());
+
+        // Panics in the handlers are already caught, this only catches panics in the hooks
+        let mut res = match AssertUnwindSafe(handle).catch_unwind().await {
+            Ok(res) => res,
+            Err(payload) => {
+                let panic = ServerPanic::from_payload(&*payload, None);
+                panic.log();
+
+                let mut res = StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                res.extensions_mut().insert(panic);
+                res
+            }
+        };
 
         // Merge the response headers with the default headers
         if !self.0.default_headers.is_empty() {
@@ -78,13 +189,38 @@ impl AppService {
             *res.headers_mut() = headers;
         }
 
-        res
+        // Record the request using the route pattern
+        let matched = res.extensions_mut().remove::<MatchedRoute>();
+        if let Some(metrics) = self.0.app_data.get::<Metrics>().filter(|_| record) {
+            let status = res.status();
+            let route = matched.as_ref().map(|x| x.path.as_str());
+            metrics.record_request(&method, route, status, start.elapsed());
+
+            if let Some(action) = matched.as_ref().and_then(|x| x.action) {
+                metrics.record_action(action, status);
+            }
+        }
+
+        // Send back the request id
+        res.headers_mut()
+            .entry(REQUEST_ID_HEADER)
+            .or_insert_with(|| request_id.to_header_value());
+
+        #[cfg(feature = "tracing")]
+        crate::trace::record_response(&span, &res, start);
+
+        guard.track(res)
     }
 
     async fn _handle(&self, req: Request) -> Response {
         let (parts, body) = req.into_parts();
         let req = Request::from_parts(parts, ());
 
+        // During the maintenance the request is not handled
+        if let Some(maintenance) = self.get_maintenance(&req) {
+            return self.maintenance_response(req, maintenance).await;
+        }
+
         // Handle the request normally
         #[cfg(not(feature = "hooks"))]
         {
@@ -111,7 +247,15 @@ impl AppService {
             let handler = hooks.iter().rev().fold(next, move |cur, next_handler| {
                 let next_handler = next_handler.clone_handler();
                 Box::new(move |req, body| {
-                    Box::pin(async move { next_handler.call(req, body, cur).await })
+                    let fut = async move { next_handler.call(req, body, cur).await };
+
+                    #[cfg(feature = "tracing")]
+                    let fut = tracing::Instrument::instrument(
+                        fut,
+                        tracing::info_span!("hook", name = "on_handle"),
+                    );
+
+                    Box::pin(fut)
                 })
             }) as Next;
 
@@ -133,17 +277,64 @@ impl AppService {
             path = path.trim_end_matches('/');
         }
 
+        if crate::cors::is_preflight(&req) {
+            if let Some(res) = self.handle_preflight(&req, path) {
+                return res;
+            }
+        }
+
         let method = req.method().into();
+        let mut req = req;
+        let route_match = self.0.server_router.at(path, method);
+
+        // The extractors read the body limit of the route from the request
+        if let Ok(mtch) = &route_match {
+            if let Some(limit) = mtch.value.extensions().get::<BodyLimit>() {
+                req.extensions_mut().insert(*limit);
+            }
+
+            // The error overlay displays the route that matched
+            if cfg!(debug_assertions) {
+                req.extensions_mut().insert(OverlayRoute {
+                    path: mtch.value.path().to_owned(),
+                    kind: mtch.value.extensions().get::<HandlerKind>().copied(),
+                    params: mtch.params.clone(),
+                });
+            }
+        }
+
         let req = Arc::new(req);
 
-        match self.0.server_router.at(path, method) {
+        match route_match {
             Ok(mtch) => {
                 let route = mtch.value;
                 let params = mtch.params;
+
+                #[cfg(feature = "tracing")]
+                crate::trace::record_route(&req, route.path());
+
                 let ctx = self.create_context(req.clone(), params, None);
 
-                let res = route.handler().call(ctx, body).await;
-                let status = res.status();
+                // Requests over the limit are rejected before running the guards
+                let rate_limit = match self.get_rate_limit(route) {
+                    Some(rate_limit) => rate_limit.check(&req).await,
+                    None => None,
+                };
+
+                let guards = route.extensions().get::<RouteGuards>();
+                let handle = async {
+                    if let Some(decision) = rate_limit.filter(|x| !x.is_allowed()) {
+                        return decision.error_response();
+                    }
+
+                    match guards {
+                        Some(guards) => match guards.check(&ctx).await {
+                            Ok(_) => route.handler().call(ctx.clone(), body).await,
+                            Err(res) => res,
+                        },
+                        None => route.handler().call(ctx.clone(), body).await,
+                    }
+                };
 
                 // Only component pages render error by default
                 let should_render = route
@@ -152,46 +343,247 @@ impl AppService {
                     .map(|kind| kind == &HandlerKind::Page)
                     .unwrap_or_default();
 
-                if status.is_client_error() || status.is_server_error() {
-                    // SAFETY: We already check the status is an error
-                    let error = ServerError::from_response(res);
-                    return self.handle_error(req, error, should_render).await;
+                let timeout = self.get_timeout(route);
+                let handle = AssertUnwindSafe(handle).catch_unwind();
+
+                // A panic in the handler only fails the current request
+                let res = match with_timeout(handle, timeout.first_byte_timeout()).await {
+                    Some(Ok(res)) => {
+                        let status = res.status();
+
+                        if status.is_client_error() || status.is_server_error() {
+                            // SAFETY: We already check the status is an error
+                            let error = ServerError::from_response(res);
+                            let params = ctx.params().clone();
+                            self.handle_error(req.clone(), params, error, should_render)
+                                .await
+                        } else {
+                            res
+                        }
+                    }
+                    Some(Err(payload)) => {
+                        let panic = ServerPanic::from_payload(&*payload, Some(route.path().into()));
+                        let params = ctx.params().clone();
+                        self.handle_panic(req.clone(), params, panic, should_render)
+                            .await
+                    }
+                    None => {
+                        log::warn!("request to `{}` timed out", route.path());
+                        let error =
+                            ServerError::new(timeout.timeout_status(), "the request timed out");
+                        let params = ctx.params().clone();
+                        self.handle_error(req.clone(), params, error, should_render)
+                            .await
+                    }
+                };
+
+                let res = match timeout.stream_timeout() {
+                    Some(stream_timeout) => with_stream_timeout(res, stream_timeout),
+                    None => res,
+                };
+
+                let mut res = catch_stream_panics(res, route.path());
+
+                // Write any cookie changes made by the handler
+                if let Some(cookies) = ctx.inner.cookies.get() {
+                    cookies.write_to_response(&mut res);
+                }
+
+                if let Some(cors) = self.get_cors(route) {
+                    cors.apply(&req, &mut res);
+                }
+
+                if let Some(decision) = rate_limit {
+                    decision.apply(&mut res);
+                }
+
+                if self.0.app_data.get::<Metrics>().is_some() {
+                    res.extensions_mut().insert(MatchedRoute {
+                        path: route.path().to_owned(),
+                        action: route.extensions().get::<ActionName>().map(|x| x.0),
+                    });
                 }
 
                 res
             }
             Err(ServerRouterMatchError::MethodMismatch) => {
                 let error = ServerError::from_status(StatusCode::METHOD_NOT_ALLOWED);
-                self.handle_error(req, error, true).await
+                self.handle_error(req, Params::default(), error, true).await
             }
             Err(_) => {
                 // we treat any other error as 404
                 let error = ServerError::from_status(StatusCode::NOT_FOUND);
-                self.handle_error(req, error, true).await
+                self.handle_error(req, Params::default(), error, true).await
             }
         }
     }
 
+    fn get_timeout(&self, route: &Route) -> RequestTimeout {
+        route
+            .extensions()
+            .get::<RequestTimeout>()
+            .or_else(|| self.0.app_data.get::<RequestTimeout>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn get_cors<'a>(&'a self, route: &'a Route) -> Option<&'a Cors> {
+        route
+            .extensions()
+            .get::<Cors>()
+            .or_else(|| self.0.app_data.get::<Cors>())
+    }
+
+    fn get_rate_limit<'a>(&'a self, route: &'a Route) -> Option<&'a RateLimit> {
+        route
+            .extensions()
+            .get::<RateLimit>()
+            .or_else(|| self.0.app_data.get::<RateLimit>())
+    }
+
+    fn handle_preflight(&self, req: &Request<()>, path: &str) -> Option<Response> {
+        // If there is no route for the path, we let the router return the 404
+        let route_methods = self.0.server_router.methods_at(path)?;
+        let requested_method = req
+            .headers()
+            .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.parse::<RouteMethod>().ok());
+
+        let cors = match requested_method {
+            Some(method) => match self.0.server_router.at(path, method) {
+                Ok(mtch) => self.get_cors(mtch.value),
+                Err(_) => self.0.app_data.get::<Cors>(),
+            },
+            None => self.0.app_data.get::<Cors>(),
+        }?;
+
+        Some(cors.preflight_response(req, route_methods))
+    }
+
+    fn get_maintenance(&self, req: &Request<()>) -> Option<&Maintenance> {
+        let maintenance = self.0.app_data.get::<Maintenance>()?;
+        if !maintenance.is_enabled() || maintenance.is_bypassed(req) {
+            return None;
+        }
+
+        // The health checks and static files are still served
+        let path = req.uri().path();
+        if let Some(checks) = self.0.app_data.get::<HealthChecks>() {
+            if checks.is_endpoint(path) {
+                return None;
+            }
+        }
+
+        if path.starts_with(&crate::env::get_static_dir()) {
+            return None;
+        }
+
+        Some(maintenance)
+    }
+
+    async fn maintenance_response(&self, req: Request<()>, maintenance: &Maintenance) -> Response {
+        let accepts_html = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|x| x.to_str().ok())
+            .map(|accept| accept.contains("text/html"))
+            .unwrap_or_default();
+
+        let error = ServerError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "the server is under maintenance",
+        );
+
+        // Browsers receive the maintenance page, not the error overlay
+        let mut res = match accepts_html {
+            true => {
+                let handler = self.0.server_error_router.find_maintenance();
+                self.render_error_page(Arc::new(req), error, handler).await
+            }
+            false => into_problem_response(error.into_response()),
+        };
+
+        let retry_after = maintenance.get_retry_after().as_secs();
+        res.headers_mut()
+            .insert(header::RETRY_AFTER, retry_after.into());
+        res
+    }
+
     async fn handle_error(
         &self,
         req: Arc<Request<()>>,
+        params: Params,
         error: ServerError,
         should_render: bool,
     ) -> Response {
-        // If the response is marked as not render, skip any error handler and return the response
-        if !should_render {
-            return error.into_response();
-        }
+        let hook_error = self.error_for_hooks(&error).await;
+
+        let response = if !should_render {
+            // If the response is marked as not render, skip any error handler and return the response
+            into_problem_response(error.into_response())
+        } else if is_overlay_enabled(&req, &self.0.app_data, error.status(), should_render) {
+            render_error_overlay(&req, &error, None)
+        } else {
+            self.render_error(req.clone(), error).await
+        };
 
+        self.call_error_hooks(req, params, hook_error, response)
+            .await
+    }
+
+    async fn handle_panic(
+        &self,
+        req: Arc<Request<()>>,
+        params: Params,
+        panic: ServerPanic,
+        should_render: bool,
+    ) -> Response {
+        panic.log();
+
+        // We only show the panic message in debug mode
+        let error = if cfg!(debug_assertions) {
+            ServerError::new(StatusCode::INTERNAL_SERVER_ERROR, &panic)
+        } else {
+            ServerError::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+        };
+
+        let hook_error = self.error_for_hooks(&error).await;
+        let status = error.status();
+        let mut response = if !should_render {
+            into_problem_response(error.into_response())
+        } else if is_overlay_enabled(&req, &self.0.app_data, status, true) {
+            render_error_overlay(&req, &error, Some(&panic))
+        } else {
+            self.render_error(req.clone(), error).await
+        };
+
+        response.extensions_mut().insert(panic);
+        self.call_error_hooks(req, params, hook_error, response)
+            .await
+    }
+
+    async fn render_error(&self, req: Arc<Request<()>>, error: ServerError) -> Response {
+        let handler = self.0.server_error_router.find(&error.status());
+        self.render_error_page(req, error, handler).await
+    }
+
+    async fn render_error_page(
+        &self,
+        req: Arc<Request<()>>,
+        error: ServerError,
+        handler: Option<&ErrorPageHandler>,
+    ) -> Response {
         let status = error.status();
-        let mut response = match self.0.server_error_router.find(&status) {
+        let mut response = match handler {
             Some(error_handler) => {
                 let params = Params::default();
                 let ctx = self.create_context(req, params, Some(error));
+                let render = AssertUnwindSafe(error_handler.call(ctx)).catch_unwind();
 
-                match error_handler.call(ctx).await {
-                    Ok(res) => res,
-                    Err(err) => match err.downcast::<ServerError>() {
+                match render.await {
+                    Ok(Ok(res)) => res,
+                    Ok(Err(err)) => match err.downcast::<ServerError>() {
                         Ok(err) => err.into_response(),
                         Err(err) => {
                             // If an error ocurred in a error handler we only show the error in debug mode
@@ -202,6 +594,11 @@ impl AppService {
                             }
                         }
                     },
+                    Err(payload) => {
+                        // We can't render other error page if the error page panicked
+                        ServerPanic::from_payload(&*payload, None).log();
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
                 }
             }
             None => error.into_response(),
@@ -209,14 +606,59 @@ impl AppService {
 
         // Ensure the status code of the response
         *response.status_mut() = status;
+        response
+    }
+
+    // Returns a copy of the error for the async error hooks, if any
+    #[cfg_attr(not(feature = "hooks"), allow(unused_variables))]
+    async fn error_for_hooks(&self, error: &ServerError) -> Option<ServerError> {
+        #[cfg(feature = "hooks")]
+        {
+            if !self.0.hooks.on_server_error_async_hooks.is_empty() {
+                return Some(error.clone_without_response().await);
+            }
+        }
 
+        None
+    }
+
+    #[allow(unused_mut)]
+    #[cfg_attr(not(feature = "hooks"), allow(unused_variables))]
+    async fn call_error_hooks(
+        &self,
+        req: Arc<Request<()>>,
+        params: Params,
+        error: Option<ServerError>,
+        mut response: Response,
+    ) -> Response {
         #[cfg(feature = "hooks")]
         {
             let hooks = &self.0.hooks;
 
             for on_error in hooks.on_server_error_hooks.iter() {
+                #[cfg(feature = "tracing")]
+                let _enter = tracing::info_span!("hook", name = "on_server_error").entered();
+
                 response = on_error.call(response);
             }
+
+            if let Some(error) = error {
+                let ctx = self.create_context(req, params, None);
+
+                for on_error in hooks.on_server_error_async_hooks.iter() {
+                    // Each hook receives its own copy of the error
+                    let error = error.clone_without_response().await;
+                    let fut = on_error.call(ctx.clone(), error, response);
+
+                    #[cfg(feature = "tracing")]
+                    let fut = tracing::Instrument::instrument(
+                        fut,
+                        tracing::info_span!("hook", name = "on_server_error_async"),
+                    );
+
+                    response = fut.await;
+                }
+            }
         }
 
         // Returns the error response
@@ -521,6 +963,105 @@ mod tests {
             .build();
     }
 
+    #[tokio::test]
+    async fn cookie_jar_response_test() {
+        use crate::web::{cookie::Cookie, CookieJar, Key};
+        use http::header;
+
+        let service = App::<Base>::new()
+            .app_data(Key::generate())
+            .route(Route::get("/login", |jar: CookieJar| async move {
+                jar.signed().add(Cookie::new("session", "1234"));
+                jar.add(Cookie::new("theme", "dark"));
+            }))
+            .build();
+
+        let res = service
+            .handle_request(create_req("/login", Method::GET), Default::default())
+            .await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get_all(header::SET_COOKIE).iter().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn cors_test() {
+        use crate::cors::Cors;
+        use http::header;
+
+        let service = App::<Base>::new()
+            .cors(Cors::new().allow_origin("https://example.com"))
+            .route(Route::get("/items", || async { "items" }))
+            .route(Route::post("/items", || async { "created" }))
+            .build();
+
+        let req = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/items")
+            .header(header::ORIGIN, "https://example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Default::default()).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            res.headers().get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET, POST"
+        );
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/items")
+            .header(header::ORIGIN, "https://example.com")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Default::default()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn localized_page_test() {
+        use crate::{
+            app::nested,
+            i18n::{use_translation, I18n},
+        };
+
+        #[function_component]
+        fn About() -> yew::Html {
+            let translation = use_translation();
+            yew::html! {
+                {translation.t("about.title")}
+            }
+        }
+
+        crate::impl_page_component!(About, "/about");
+
+        let i18n = I18n::new("en")
+            .catalog("en", [("about.title", "About us")])
+            .catalog("es", [("about.title", "Sobre nosotros")]);
+
+        let service = App::<Base>::new()
+            .i18n(i18n)
+            .nest("/:lang", nested::<Base>().page::<About>())
+            .build();
+
+        let res = send_request_get_text(&service, "/es/about", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = res.body();
+        assert!(body.contains("Sobre nosotros"), "body: {body}");
+        assert!(body.contains("<html lang=\"es\""), "body: {body}");
+        assert!(body.contains("hreflang=\"en\""), "body: {body}");
+        assert!(body.contains("href=\"/en/about\""), "body: {body}");
+    }
+
     #[test]
     fn app_data_test() {
         let service = App::<Base>::new()
@@ -0,0 +1,364 @@
+use super::AppData;
+use crate::{
+    error::{ServerError, ServerPanic},
+    routing::{HandlerKind, Params},
+    web::{Body, Request, Response},
+};
+use http::{header, StatusCode};
+use std::fmt::Write;
+
+/// Whether to show the error overlay in debug builds, enabled by default.
+pub(crate) struct ErrorOverlayEnabled(pub bool);
+
+/// The messages of an error and its sources, captured in debug builds
+/// when an error is converted into a response.
+#[derive(Debug, Clone)]
+pub(crate) struct ErrorChain(pub Vec<String>);
+
+impl ErrorChain {
+    pub fn new(error: &(dyn std::error::Error + 'static)) -> Self {
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+
+        while let Some(err) = source {
+            chain.push(err.to_string());
+            source = err.source();
+        }
+
+        ErrorChain(chain)
+    }
+}
+
+/// The route that matched the request, inserted in the request in debug builds.
+#[derive(Debug, Clone)]
+pub(crate) struct OverlayRoute {
+    pub path: String,
+    pub kind: Option<HandlerKind>,
+    pub params: Params,
+}
+
+/// Returns `true` if the error should be displayed in the overlay,
+/// only server errors of requests from a browser in debug builds are displayed.
+pub(crate) fn is_overlay_enabled(
+    req: &Request<()>,
+    app_data: &AppData,
+    status: StatusCode,
+    should_render: bool,
+) -> bool {
+    if !cfg!(debug_assertions) || !should_render || !status.is_server_error() {
+        return false;
+    }
+
+    if let Some(ErrorOverlayEnabled(false)) = app_data.get::<ErrorOverlayEnabled>() {
+        return false;
+    }
+
+    req.headers()
+        .get(header::ACCEPT)
+        .and_then(|x| x.to_str().ok())
+        .map(|accept| accept.contains("text/html"))
+        .unwrap_or_default()
+}
+
+/// Renders a page with the details of the error.
+pub(crate) fn render_error_overlay(
+    req: &Request<()>,
+    error: &ServerError,
+    panic: Option<&ServerPanic>,
+) -> Response {
+    let status = error.status();
+    let chain = match panic {
+        Some(panic) => vec![panic.to_string()],
+        None => error
+            .extensions()
+            .and_then(|ext| ext.get::<ErrorChain>())
+            .map(|chain| chain.0.clone())
+            .unwrap_or_else(|| vec![error.to_string()]),
+    };
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{status}</title><style>{OVERLAY_STYLES}</style></head><body>"
+    );
+
+    let _ = write!(
+        html,
+        "<header><span class=\"status\">{}</span><h1>{}</h1></header>",
+        status.as_u16(),
+        escape(&chain[0])
+    );
+
+    // Error chain
+    if chain.len() > 1 {
+        html.push_str("<section><h2>Caused by</h2><ol>");
+        for cause in chain.iter().skip(1) {
+            let _ = write!(html, "<li>{}</li>", escape(cause));
+        }
+        html.push_str("</ol></section>");
+    }
+
+    // Panic
+    if let Some(panic) = panic {
+        html.push_str("<section><h2>Panic</h2>");
+        if let Some(location) = panic.location() {
+            let _ = write!(html, "<p>at <code>{}</code></p>", escape(location));
+        }
+
+        match panic.backtrace() {
+            Some(backtrace) => {
+                let _ = write!(html, "<pre>{}</pre>", escape(backtrace));
+            }
+            None => {
+                html.push_str("<p class=\"hint\">Run with <code>RUST_BACKTRACE=1</code> to display a backtrace.</p>");
+            }
+        }
+
+        html.push_str("</section>");
+    }
+
+    // Route
+    let route = req.extensions().get::<OverlayRoute>();
+    let mut route_rows = vec![
+        ("Method", req.method().to_string()),
+        ("URI", req.uri().to_string()),
+    ];
+
+    if let Some(route) = route {
+        route_rows.push(("Route", route.path.clone()));
+
+        if let Some(kind) = route.kind {
+            route_rows.push(("Handler", format!("{kind:?}")));
+        }
+    }
+
+    write_table(&mut html, "Request", route_rows);
+
+    if let Some(route) = route.filter(|r| !r.params.is_empty()) {
+        let rows = route.params.iter().map(|(k, v)| (k.as_str(), v.clone()));
+        write_table(&mut html, "Params", rows);
+    }
+
+    let headers = req.headers().iter().map(|(name, value)| {
+        (
+            name.as_str(),
+            String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        )
+    });
+    write_table(&mut html, "Headers", headers);
+
+    html.push_str("<footer>This page is only displayed in debug builds.</footer></body></html>");
+
+    let mut res = Response::new(Body::from(html));
+    *res.status_mut() = status;
+    res.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    res
+}
+
+fn write_table<'a>(
+    html: &mut String,
+    title: &str,
+    rows: impl IntoIterator<Item = (&'a str, String)>,
+) {
+    let _ = write!(html, "<section><h2>{title}</h2><table>");
+    for (name, value) in rows {
+        let _ = write!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape(name),
+            escape(&value)
+        );
+    }
+    html.push_str("</table></section>");
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const OVERLAY_STYLES: &str = r#"
+body {
+    margin: 0;
+    padding: 2rem;
+    font-family: system-ui, -apple-system, "Segoe UI", Roboto, sans-serif;
+    background: #1e1e1e;
+    color: #e6e6e6;
+}
+
+header {
+    display: flex;
+    align-items: center;
+    gap: 1rem;
+    padding-bottom: 1rem;
+    border-bottom: 2px solid #e5484d;
+}
+
+header .status {
+    padding: 0.25rem 0.75rem;
+    border-radius: 4px;
+    background: #e5484d;
+    color: white;
+    font-weight: bold;
+}
+
+h1 {
+    margin: 0;
+    font-size: 1.4rem;
+    white-space: pre-wrap;
+}
+
+h2 {
+    font-size: 1rem;
+    text-transform: uppercase;
+    color: #a0a0a0;
+}
+
+pre, code {
+    font-family: ui-monospace, Menlo, Consolas, monospace;
+}
+
+pre {
+    padding: 1rem;
+    overflow-x: auto;
+    background: #141414;
+    font-size: 0.8rem;
+}
+
+table {
+    border-collapse: collapse;
+    width: 100%;
+    font-size: 0.9rem;
+}
+
+th, td {
+    text-align: left;
+    vertical-align: top;
+    padding: 0.3rem 0.6rem;
+    border-bottom: 1px solid #333;
+    word-break: break-all;
+}
+
+th {
+    width: 20%;
+    color: #a0a0a0;
+    font-weight: normal;
+}
+
+.hint, footer {
+    color: #808080;
+    font-size: 0.85rem;
+}
+
+footer {
+    margin-top: 2rem;
+}
+"#;
+
+#[cfg(test)]
+#[cfg(debug_assertions)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use crate::{
+        app::App,
+        routing::{HandlerKind, Route},
+        web::Request,
+    };
+    use http::{header, StatusCode};
+    use yew::{function_component, html::ChildrenProps};
+
+    #[function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("failed to load user")]
+    struct LoadUserError(#[source] std::io::Error);
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .header(header::ACCEPT, "text/html,application/xhtml+xml")
+            .header("x-test", "<value>")
+            .body(Default::default())
+            .unwrap()
+    }
+
+    fn page(route: Route) -> Route {
+        let mut route = route;
+        route.extensions_mut().insert(HandlerKind::Page);
+        route
+    }
+
+    async fn get_html(service: &crate::app::AppService, path: &str) -> (StatusCode, String) {
+        let res = service.handle(create_req(path)).await;
+        let status = res.status();
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn error_overlay_test() {
+        let service = App::<Base>::new()
+            .route(page(Route::get("/users/:id", || async move {
+                let err = std::io::Error::new(std::io::ErrorKind::NotFound, "connection refused");
+                let ret: crate::Result<&str> = Err(LoadUserError(err).into());
+                ret
+            })))
+            .route(page(Route::get("/panic", || async move {
+                if true {
+                    panic!("something went wrong");
+                }
+
+                "unreachable"
+            })))
+            .build();
+
+        let (status, html) = get_html(&service, "/users/12").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(html.contains("failed to load user"), "body: {html}");
+        assert!(html.contains("connection refused"), "body: {html}");
+        assert!(html.contains("/users/:id"), "body: {html}");
+        assert!(html.contains("Page"), "body: {html}");
+        assert!(html.contains("<td>12</td>"), "body: {html}");
+        assert!(html.contains("&lt;value&gt;"), "body: {html}");
+
+        let (status, html) = get_html(&service, "/panic").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(html.contains("something went wrong"), "body: {html}");
+        assert!(html.contains("error_overlay.rs"), "body: {html}");
+    }
+
+    #[tokio::test]
+    async fn error_overlay_disabled_test() {
+        let service = App::<Base>::new()
+            .error_overlay(false)
+            .route(page(Route::get("/error", || async move {
+                StatusCode::INTERNAL_SERVER_ERROR
+            })))
+            .build();
+
+        let (status, html) = get_html(&service, "/error").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(
+            !html.contains("Caused by") && !html.contains("<table>"),
+            "body: {html}"
+        );
+    }
+}
@@ -8,6 +8,8 @@ mod handler;
 mod layout_context;
 mod render_context;
 mod request_context;
+mod shutdown;
+mod timeout;
 
 pub use app::*;
 pub use app_data::*;
@@ -18,9 +20,14 @@ pub use handler::*;
 pub use layout_context::*;
 pub use render_context::*;
 pub use request_context::*;
+pub use shutdown::*;
+pub use timeout::*;
 
 //
 pub mod router;
 
 //
 pub(crate) mod page_head;
+
+//
+pub(crate) mod error_overlay;
@@ -1,9 +1,12 @@
 use super::{router::PageRouterWrapper, AppData};
 use crate::{
+    auth::{AuthenticatorService, Identity},
+    flash::Flash,
     routing::{ErrorRouter, Params},
-    web::Request, error::ServerError,
+    web::{CookieJar, FlashRejection, Key, Request, RequestId, RequestStart}, error::ServerError,
 };
-use std::sync::Arc;
+use once_cell::sync::OnceCell;
+use std::{sync::Arc, time::Duration};
 
 #[cfg_attr(feature = "client", allow(dead_code))]
 pub(crate) struct RequestContextInner {
@@ -13,6 +16,9 @@ pub(crate) struct RequestContextInner {
     pub(crate) error_router: Arc<ErrorRouter>,
     pub(crate) request: Arc<Request<()>>,
     pub(crate) error: Option<ServerError>,
+    pub(crate) cookies: OnceCell<CookieJar>,
+    pub(crate) identity: tokio::sync::OnceCell<Identity>,
+    pub(crate) flash: OnceCell<Flash>,
 }
 
 /// Contains information about the current request.
@@ -39,6 +45,9 @@ impl RequestContext {
             request,
             client_router,
             error_router,
+            cookies: OnceCell::new(),
+            identity: tokio::sync::OnceCell::new(),
+            flash: OnceCell::new(),
         };
 
         RequestContext {
@@ -63,11 +72,78 @@ impl RequestContext {
         &self.inner.params
     }
 
+    /// Returns the id of the current request.
+    pub fn request_id(&self) -> Option<&RequestId> {
+        self.inner.request.extensions().get::<RequestId>()
+    }
+
+    /// Returns the time elapsed since the server started processing the request.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.inner
+            .request
+            .extensions()
+            .get::<RequestStart>()
+            .map(|start| start.elapsed())
+    }
+
     /// Returns an error that ocurred, if any.
     pub fn error(&self) -> Option<&ServerError> {
         self.inner.error.as_ref()
     }
 
+    /// Returns the cookies of the current request.
+    ///
+    /// The changes made to the returned jar are written to the response.
+    pub fn cookies(&self) -> CookieJar {
+        self.inner
+            .cookies
+            .get_or_init(|| {
+                let key = self.app_data::<Key>().cloned();
+                CookieJar::from_request(self.request(), key)
+            })
+            .clone()
+    }
+
+    /// Returns the flash messages of the current request.
+    ///
+    /// # Errors
+    /// If there is no [`Key`] in the `app_data` to sign the flash messages cookie.
+    ///
+    /// [`Key`]: crate::web::Key
+    pub fn flash(&self) -> Result<Flash, FlashRejection> {
+        self.inner
+            .flash
+            .get_or_try_init(|| Flash::from_cookies(self.cookies()))
+            .cloned()
+    }
+
+    /// Returns the locale of the current request, if the i18n is enabled.
+    pub fn locale(&self) -> Option<String> {
+        let i18n = self.app_data::<crate::i18n::I18n>()?;
+        Some(i18n.negotiate(self))
+    }
+
+    /// Returns the identity of the user of the current request.
+    ///
+    /// # Errors
+    /// If no `Authenticator` was registered or the authentication fails.
+    pub async fn identity(&self) -> crate::Result<Identity> {
+        let identity = self
+            .inner
+            .identity
+            .get_or_try_init(|| async {
+                let authenticator = self
+                    .app_data::<AuthenticatorService>()
+                    .cloned()
+                    .ok_or("no `Authenticator` was registered")?;
+
+                authenticator.authenticate(self.clone()).await
+            })
+            .await?;
+
+        Ok(identity.clone())
+    }
+
     /// Returns the the data for the given type.
     pub fn app_data<T>(&self) -> Option<&T>
     where
@@ -0,0 +1,234 @@
+use super::AppService;
+use crate::{
+    types::{BoxFuture, TryBoxStream},
+    web::{is_long_lived, Body, Payload, Response},
+};
+use bytes::Bytes;
+use futures::{future::Either, StreamExt};
+use futures_timer::Delay;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+/// The default time to wait for the in-flight requests to complete when shutting down.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A function called when the server is shutting down.
+pub type OnServerShutdown = Box<dyn Fn(AppService) -> BoxFuture<()> + Send + Sync>;
+
+/// Keeps track of the shutdown state of the server.
+pub(crate) struct Shutdown {
+    hooks: Mutex<Vec<OnServerShutdown>>,
+    timeout: Duration,
+    in_flight: Arc<InFlight>,
+}
+
+impl Shutdown {
+    pub fn new(hooks: Vec<OnServerShutdown>, timeout: Duration) -> Self {
+        Shutdown {
+            hooks: Mutex::new(hooks),
+            timeout,
+            in_flight: Default::default(),
+        }
+    }
+
+    /// Returns the max time to wait for the in-flight requests.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the number of requests being processed.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.count.load(Ordering::SeqCst)
+    }
+
+    /// Marks the start of a request, the request is considered in-flight until the guard is dropped.
+    pub fn start_request(&self) -> InFlightGuard {
+        self.in_flight.count.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(self.in_flight.clone())
+    }
+
+    /// Waits for all the in-flight requests to complete, returns `false` if the timeout elapsed before.
+    pub async fn drain(&self) -> bool {
+        let idle = Box::pin(self.in_flight.wait_idle());
+        let timeout = Delay::new(self.timeout);
+
+        match futures::future::select(idle, timeout).await {
+            Either::Left(_) => true,
+            Either::Right(_) => false,
+        }
+    }
+
+    /// Takes the shutdown hooks, so these can only be called once.
+    pub fn take_hooks(&self) -> Vec<OnServerShutdown> {
+        let mut hooks = self.hooks.lock().expect("failed to lock shutdown hooks");
+        std::mem::take(&mut *hooks)
+    }
+}
+
+#[derive(Default)]
+struct InFlight {
+    count: AtomicUsize,
+    notify: Notify,
+}
+
+impl InFlight {
+    async fn wait_idle(&self) {
+        loop {
+            // We need to create the future before checking to not miss any notification
+            let notified = self.notify.notified();
+
+            if self.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+}
+
+/// Decrements the in-flight requests when dropped.
+pub(crate) struct InFlightGuard(Arc<InFlight>);
+
+impl InFlightGuard {
+    /// Keeps the request in-flight until the body of the response is fully sent,
+    /// the long-lived responses like the event streams are not waited for.
+    pub fn track(self, mut res: Response) -> Response {
+        if is_long_lived(&res) {
+            return res;
+        }
+
+        let body = res.body_mut();
+
+        match body.take() {
+            Some(Payload::Stream(stream)) => {
+                let stream = stream.map(move |chunk| {
+                    let _guard = &self;
+                    chunk
+                });
+
+                *body = Body::from(Box::pin(stream) as TryBoxStream<Bytes>);
+            }
+            Some(Payload::Bytes(bytes)) => *body = Body::from(bytes),
+            None => {}
+        }
+
+        res
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.notify.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{app::App, routing::Route, web::Body, web::Request};
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(path: &str) -> Request {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[cfg(not(feature = "client"))]
+    #[tokio::test]
+    async fn on_server_shutdown_test() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+
+        let service = App::<Base>::new()
+            .on_server_shutdown({
+                let calls = calls.clone();
+                move |_| {
+                    let calls = calls.clone();
+                    async move { calls.lock().unwrap().push("close pool") }
+                }
+            })
+            .on_server_shutdown({
+                let calls = calls.clone();
+                move |_| {
+                    let calls = calls.clone();
+                    async move { calls.lock().unwrap().push("flush logs") }
+                }
+            })
+            .build();
+
+        service.shutdown().await;
+        service.shutdown().await;
+
+        assert_eq!(*calls.lock().unwrap(), vec!["close pool", "flush logs"]);
+    }
+
+    #[tokio::test]
+    async fn drain_test() {
+        let (tx, body) = Body::channel();
+        let body = Arc::new(Mutex::new(Some(body)));
+
+        let service = App::<Base>::new()
+            .shutdown_timeout(Duration::from_secs(5))
+            .route(Route::get("/hello", || async { "Hello World!" }))
+            .route(Route::get("/stream", move || {
+                let body = body.lock().unwrap().take().unwrap();
+                async move { body }
+            }))
+            .build();
+
+        let res = service.handle(create_req("/hello")).await;
+        assert_eq!(service.in_flight_requests(), 0);
+        drop(res);
+
+        // The request is in-flight until the stream ends
+        let res = service.handle(create_req("/stream")).await;
+        assert_eq!(service.in_flight_requests(), 1);
+
+        let drain = tokio::spawn({
+            let service = service.clone();
+            async move { service.drain().await }
+        });
+
+        tx.send(Ok("done".into())).unwrap();
+        drop(tx);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "done");
+        assert!(drain.await.unwrap());
+        assert_eq!(service.in_flight_requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_timeout_test() {
+        let (_tx, body) = Body::channel();
+        let body = Arc::new(Mutex::new(Some(body)));
+
+        let service = App::<Base>::new()
+            .shutdown_timeout(Duration::from_millis(10))
+            .route(Route::get("/stream", move || {
+                let body = body.lock().unwrap().take().unwrap();
+                async move { body }
+            }))
+            .build();
+
+        let _res = service.handle(create_req("/stream")).await;
+        assert!(!service.drain().await);
+        assert_eq!(service.in_flight_requests(), 1);
+    }
+}
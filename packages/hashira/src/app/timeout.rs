@@ -0,0 +1,326 @@
+use crate::{
+    types::TryBoxStream,
+    web::{is_long_lived, Body, Payload, Response},
+};
+use bytes::Bytes;
+use futures::{future::Either, Future, Stream};
+use futures_timer::Delay;
+use http::StatusCode;
+use pin_project_lite::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The max time the server spends processing a request.
+///
+/// The `first_byte` timeout limits the time to produce the response, which includes
+/// running the guards, the extractors and the handler, when exceeded the server responds
+/// with a `504 Gateway Timeout` error that is rendered as any other error page.
+///
+/// The `stream` timeout limits the total time spent streaming the body of a response,
+/// like a rendered page, at that point the status was already sent so the stream
+/// just ends with a [`StreamTimeoutError`]. The long-lived responses like
+/// the [`Sse`] event streams are not limited by the `stream` timeout.
+///
+/// It can be set for all the routes with [`App::timeout`], for a scope with [`AppNested::timeout`],
+/// for a single route with [`Route::timeout`] or for an action with
+/// `#[action("/path", timeout = "std::time::Duration::from_secs(10)")]`.
+///
+/// [`App::timeout`]: crate::app::App::timeout
+/// [`AppNested::timeout`]: crate::app::AppNested::timeout
+/// [`Route::timeout`]: crate::routing::Route::timeout
+/// [`Sse`]: crate::web::Sse
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RequestTimeout {
+    first_byte: Option<Duration>,
+    stream: Option<Duration>,
+    status: StatusCode,
+}
+
+impl RequestTimeout {
+    /// Constructs a timeout with the given max time to produce the response.
+    pub fn new(first_byte: Duration) -> Self {
+        RequestTimeout {
+            first_byte: Some(first_byte),
+            ..Self::none()
+        }
+    }
+
+    /// Constructs a timeout that allows a request to take any time,
+    /// useful to disable the global timeout on a route.
+    pub fn none() -> Self {
+        RequestTimeout {
+            first_byte: None,
+            stream: None,
+            status: StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    /// Sets the max time spent streaming the body of the response.
+    pub fn stream(mut self, stream: Duration) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Sets the status returned when the response is not produced in time,
+    /// defaults to `504 Gateway Timeout`.
+    ///
+    /// # Panics
+    /// If the status is not a `503 Service Unavailable` or a `504 Gateway Timeout`.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        assert!(
+            status == StatusCode::SERVICE_UNAVAILABLE || status == StatusCode::GATEWAY_TIMEOUT,
+            "timeout status should be 503 or 504, but was {status}"
+        );
+
+        self.status = status;
+        self
+    }
+
+    /// Returns the max time to produce the response, if any.
+    pub fn first_byte_timeout(&self) -> Option<Duration> {
+        self.first_byte
+    }
+
+    /// Returns the max time spent streaming the body of the response, if any.
+    pub fn stream_timeout(&self) -> Option<Duration> {
+        self.stream
+    }
+
+    /// Returns the status returned when the response is not produced in time.
+    pub fn timeout_status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl Default for RequestTimeout {
+    fn default() -> Self {
+        RequestTimeout::none()
+    }
+}
+
+impl From<Duration> for RequestTimeout {
+    fn from(value: Duration) -> Self {
+        RequestTimeout::new(value)
+    }
+}
+
+/// The error returned by a response stream that exceeded its timeout.
+#[derive(Debug, Error)]
+#[error("response stream exceeded the timeout of {0:?}")]
+pub struct StreamTimeoutError(Duration);
+
+impl StreamTimeoutError {
+    /// Returns the timeout that was exceeded.
+    pub fn timeout(&self) -> Duration {
+        self.0
+    }
+}
+
+/// Waits for the future to complete, returns `None` if the timeout elapsed before.
+pub(crate) async fn with_timeout<F>(fut: F, timeout: Option<Duration>) -> Option<F::Output>
+where
+    F: Future,
+{
+    let Some(timeout) = timeout else {
+        return Some(fut.await);
+    };
+
+    let fut = Box::pin(fut);
+    match futures::future::select(fut, Delay::new(timeout)).await {
+        Either::Left((ret, _)) => Some(ret),
+        Either::Right(_) => None,
+    }
+}
+
+/// Ends the body stream of the response with an error if is not completed in the given time.
+///
+/// The long-lived responses like the event streams are not affected.
+pub(crate) fn with_stream_timeout(mut res: Response, timeout: Duration) -> Response {
+    if is_long_lived(&res) {
+        return res;
+    }
+
+    let body = res.body_mut();
+
+    match body.take() {
+        Some(Payload::Stream(stream)) => {
+            let stream = TimeoutStream {
+                stream,
+                timeout,
+                delay: None,
+                done: false,
+            };
+
+            *body = Body::from(Box::pin(stream) as TryBoxStream<Bytes>);
+        }
+        Some(Payload::Bytes(bytes)) => *body = Body::from(bytes),
+        None => {}
+    }
+
+    res
+}
+
+pin_project! {
+    struct TimeoutStream {
+        #[pin]
+        stream: TryBoxStream<Bytes>,
+        timeout: Duration,
+        delay: Option<Delay>,
+        done: bool,
+    }
+}
+
+impl Stream for TimeoutStream {
+    type Item = <TryBoxStream<Bytes> as Stream>::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        // The timer starts when the stream is polled for the first time
+        let timeout = *this.timeout;
+        let delay = this.delay.get_or_insert_with(|| Delay::new(timeout));
+
+        if let Poll::Ready(chunk) = this.stream.poll_next(cx) {
+            return Poll::Ready(chunk);
+        }
+
+        match Pin::new(delay).poll(cx) {
+            Poll::Ready(_) => {
+                log::warn!("response stream exceeded the timeout of {timeout:?}");
+                *this.done = true;
+                Poll::Ready(Some(Err(StreamTimeoutError(timeout).into())))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::{RequestTimeout, StreamTimeoutError};
+    use crate::{
+        app::{nested, App},
+        routing::{HandlerKind, Route},
+        types::TryBoxStream,
+        web::{Body, Bytes, Request},
+    };
+    use futures::StreamExt;
+    use futures_timer::Delay;
+    use http::StatusCode;
+    use std::time::Duration;
+    use yew::{function_component, html::ChildrenProps};
+
+    #[function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    fn sleep_route(path: &str) -> Route {
+        Route::get(path, || async move {
+            Delay::new(Duration::from_millis(200)).await;
+            "done"
+        })
+    }
+
+    #[tokio::test]
+    async fn first_byte_timeout_test() {
+        #[function_component]
+        fn ErrorFallbackTest() -> yew::Html {
+            yew::html! {
+                "test - timeout"
+            }
+        }
+
+        crate::impl_page_component!(ErrorFallbackTest);
+
+        let mut page = sleep_route("/page");
+        page.extensions_mut().insert(HandlerKind::Page);
+
+        let service = App::<Base>::new()
+            .timeout(Duration::from_millis(20))
+            .error_page_fallback::<ErrorFallbackTest>()
+            .route(sleep_route("/slow"))
+            .route(page)
+            .route(sleep_route("/no-timeout").timeout(RequestTimeout::none()))
+            .nest(
+                "/scope",
+                nested::<Base>()
+                    .timeout(
+                        RequestTimeout::new(Duration::from_millis(20))
+                            .status(StatusCode::SERVICE_UNAVAILABLE),
+                    )
+                    .route(sleep_route("/slow"))
+                    .route(sleep_route("/fast").timeout(Duration::from_secs(5))),
+            )
+            .build();
+
+        let res = service.handle(create_req("/slow")).await;
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let res = service.handle(create_req("/page")).await;
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let html = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(html.contains("test - timeout"), "body: {html}");
+
+        let res = service.handle(create_req("/no-timeout")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.handle(create_req("/scope/slow")).await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let res = service.handle(create_req("/scope/fast")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn stream_timeout_test() {
+        let service = App::<Base>::new()
+            .route(
+                Route::get("/stream", || async move {
+                    let stream = futures::stream::iter(0..3).then(|i| async move {
+                        if i > 0 {
+                            Delay::new(Duration::from_millis(200)).await;
+                        }
+
+                        Ok(Bytes::from(i.to_string()))
+                    });
+
+                    Body::from(Box::pin(stream) as TryBoxStream<Bytes>)
+                })
+                .timeout(RequestTimeout::none().stream(Duration::from_millis(20))),
+            )
+            .build();
+
+        let res = service.handle(create_req("/stream")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let mut stream = res.into_body().into_stream();
+        assert_eq!(stream.next().await.unwrap().unwrap(), "0");
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        let err = err.downcast_ref::<StreamTimeoutError>().unwrap();
+        assert_eq!(err.timeout(), Duration::from_millis(20));
+
+        assert!(stream.next().await.is_none());
+    }
+}
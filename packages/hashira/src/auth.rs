@@ -0,0 +1,412 @@
+#![cfg_attr(feature = "client", allow(dead_code))]
+
+use crate::{
+    app::RequestContext,
+    context::PageDataHandle,
+    error::{BoxError, ServerError},
+    routing::Guard,
+    types::BoxFuture,
+    web::{Body, FromRequest, IntoResponse, Redirect, Response},
+};
+use http::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{any::Any, ops::Deref, sync::Arc};
+use yew::{hook, use_context};
+
+/// Authenticates the user of a request.
+///
+/// Register it with [`App::authenticator`] to use the [`Identity`] and
+/// [`Authenticated`] extractors and the [`AuthGuard`].
+///
+/// The user is only serialized into the page data when enabled with [`App::expose_user`],
+/// so it can be read on the client with [`use_user`].
+///
+/// [`App::authenticator`]: crate::app::App::authenticator
+/// [`App::expose_user`]: crate::app::App::expose_user
+#[async_trait::async_trait]
+pub trait Authenticator: Send + Sync + 'static {
+    /// The type of the user.
+    type User: Serialize + Send + Sync + 'static;
+
+    /// Returns the user of the request, or `None` if the request is not authenticated.
+    async fn authenticate(&self, ctx: &RequestContext) -> crate::Result<Option<Self::User>>;
+}
+
+type AuthenticateFn = dyn Fn(RequestContext) -> BoxFuture<crate::Result<Identity>> + Send + Sync;
+
+/// A type erased `Authenticator` stored in the `AppData`.
+#[derive(Clone)]
+pub(crate) struct AuthenticatorService(Arc<AuthenticateFn>);
+
+impl AuthenticatorService {
+    pub(crate) fn new<A: Authenticator>(authenticator: A) -> Self {
+        let authenticator = Arc::new(authenticator);
+        AuthenticatorService(Arc::new(move |ctx| {
+            let authenticator = authenticator.clone();
+            Box::pin(async move {
+                match authenticator.authenticate(&ctx).await? {
+                    Some(user) => Identity::new(user),
+                    None => Ok(Identity::anonymous()),
+                }
+            })
+        }))
+    }
+
+    pub(crate) async fn authenticate(&self, ctx: RequestContext) -> crate::Result<Identity> {
+        (self.0)(ctx).await
+    }
+}
+
+/// The identity of the user of the current request.
+///
+/// The identity is resolved once per request using the registered [`Authenticator`].
+#[derive(Clone, Default)]
+pub struct Identity {
+    user: Option<Arc<dyn Any + Send + Sync>>,
+    json: Option<serde_json::Value>,
+}
+
+impl Identity {
+    /// Constructs an identity for the given user.
+    pub fn new<U>(user: U) -> crate::Result<Self>
+    where
+        U: Serialize + Send + Sync + 'static,
+    {
+        let json = serde_json::to_value(&user)?;
+        Ok(Identity {
+            user: Some(Arc::new(user)),
+            json: Some(json),
+        })
+    }
+
+    /// Constructs an identity without user.
+    pub fn anonymous() -> Self {
+        Identity::default()
+    }
+
+    /// Returns `true` if there is an user.
+    pub fn is_authenticated(&self) -> bool {
+        self.user.is_some()
+    }
+
+    /// Returns the user if is of the given type.
+    pub fn user<U: 'static>(&self) -> Option<&U> {
+        self.user.as_ref().and_then(|user| user.downcast_ref::<U>())
+    }
+
+    /// Returns the serialized user.
+    pub fn user_json(&self) -> Option<&serde_json::Value> {
+        self.json.as_ref()
+    }
+}
+
+impl FromRequest for Identity {
+    type Error = BoxError;
+    type Fut = BoxFuture<Result<Identity, BoxError>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        let ctx = ctx.clone();
+        Box::pin(async move { ctx.identity().await })
+    }
+}
+
+/// Extracts the authenticated user, returns `401` if the request is not authenticated.
+#[derive(Debug, Clone)]
+pub struct Authenticated<U>(pub U);
+
+impl<U> Authenticated<U> {
+    /// Returns the user.
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+}
+
+impl<U> Deref for Authenticated<U> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<U> FromRequest for Authenticated<U>
+where
+    U: Clone + Send + Sync + 'static,
+{
+    type Error = BoxError;
+    type Fut = BoxFuture<Result<Authenticated<U>, BoxError>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        let ctx = ctx.clone();
+        Box::pin(async move {
+            let identity = ctx.identity().await?;
+
+            if !identity.is_authenticated() {
+                return Err(ServerError::from_status(StatusCode::UNAUTHORIZED).into());
+            }
+
+            match identity.user::<U>() {
+                Some(user) => Ok(Authenticated(user.clone())),
+                None => Err(format!(
+                    "the authenticated user is not of type `{}`",
+                    std::any::type_name::<U>()
+                )
+                .into()),
+            }
+        })
+    }
+}
+
+type AuthorizeFn = dyn Fn(&Identity) -> bool + Send + Sync;
+
+/// A guard that requires an authenticated user.
+///
+/// Returns `401` when there is no user, or `403` if the user is not authorized,
+/// use [`AuthGuard::redirect_to`] to redirect instead.
+#[derive(Clone, Default)]
+pub struct AuthGuard {
+    redirect: Option<String>,
+    authorize: Option<Arc<AuthorizeFn>>,
+}
+
+impl AuthGuard {
+    /// Constructs a guard that only requires an authenticated user.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Redirects to the given path when the request is not authenticated,
+    /// for example a login page.
+    pub fn redirect_to(mut self, path: impl Into<String>) -> Self {
+        self.redirect = Some(path.into());
+        self
+    }
+
+    /// Requires the user to satisfy the given condition,
+    /// users of other type are not authorized.
+    pub fn authorize<U, F>(mut self, f: F) -> Self
+    where
+        U: 'static,
+        F: Fn(&U) -> bool + Send + Sync + 'static,
+    {
+        self.authorize = Some(Arc::new(move |identity: &Identity| {
+            identity.user::<U>().map(&f).unwrap_or(false)
+        }));
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Guard for AuthGuard {
+    async fn check(&self, ctx: &RequestContext) -> Result<(), Response> {
+        let identity = match ctx.identity().await {
+            Ok(identity) => identity,
+            Err(err) => return Err(ServerError::from_error(err).into_response()),
+        };
+
+        if !identity.is_authenticated() {
+            return match &self.redirect {
+                Some(path) => match Redirect::see_other(path.as_str()) {
+                    Ok(redirect) => Err(redirect.into_response()),
+                    Err(err) => {
+                        log::error!("invalid login redirect `{path}`: {err}");
+                        Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+                    }
+                },
+                None => Err(StatusCode::UNAUTHORIZED.into_response()),
+            };
+        }
+
+        match &self.authorize {
+            Some(authorize) if !authorize(&identity) => Err(StatusCode::FORBIDDEN.into_response()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Marks the user of the requests to be serialized into the page data, stored in the `AppData`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExposeUser;
+
+/// Returns the serialized user for the page data, if is exposed and an authenticator is registered.
+pub(crate) async fn get_page_user(ctx: &RequestContext) -> Option<serde_json::Value> {
+    ctx.app_data::<ExposeUser>()?;
+    ctx.app_data::<AuthenticatorService>()?;
+
+    match ctx.identity().await {
+        Ok(identity) => identity.json,
+        Err(err) => {
+            log::error!("failed to authenticate request: {err}");
+            None
+        }
+    }
+}
+
+/// Returns the user of the current page, if any.
+///
+/// The user is only available if is exposed with [`App::expose_user`].
+///
+/// [`App::expose_user`]: crate::app::App::expose_user
+#[hook]
+pub fn use_user<U>() -> Option<U>
+where
+    U: DeserializeOwned + 'static,
+{
+    let page_data = use_context::<PageDataHandle>()?;
+    let user = page_data.user.clone()?;
+
+    match serde_json::from_value(user) {
+        Ok(user) => Some(user),
+        Err(err) => {
+            log::error!(
+                "failed to deserialize user as `{}`: {err}",
+                std::any::type_name::<U>()
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthGuard, Authenticated, Authenticator, Identity};
+    use crate::{
+        app::{App, RequestContext},
+        routing::Route,
+        web::{Request, Response},
+    };
+    use http::{header, StatusCode};
+    use serde::Serialize;
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct User {
+        name: String,
+        admin: bool,
+    }
+
+    struct HeaderAuthenticator;
+
+    #[async_trait::async_trait]
+    impl Authenticator for HeaderAuthenticator {
+        type User = User;
+
+        async fn authenticate(&self, ctx: &RequestContext) -> crate::Result<Option<User>> {
+            let user = ctx
+                .request()
+                .headers()
+                .get("x-user")
+                .and_then(|x| x.to_str().ok())
+                .map(|name| User {
+                    name: name.to_owned(),
+                    admin: name == "admin",
+                });
+
+            Ok(user)
+        }
+    }
+
+    async fn get_text(res: Response) -> String {
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    fn create_req(path: &str, user: Option<&str>) -> crate::web::Request {
+        let mut builder = Request::builder().uri(path);
+        if let Some(user) = user {
+            builder = builder.header("x-user", user);
+        }
+
+        builder.body(Default::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn authenticated_extractor_test() {
+        let service = App::<Base>::new()
+            .authenticator(HeaderAuthenticator)
+            .route(Route::get("/me", |user: Authenticated<User>| async move {
+                user.name.clone()
+            }))
+            .route(Route::get("/anyone", |identity: Identity| async move {
+                identity.is_authenticated().to_string()
+            }))
+            .build();
+
+        let res = service.handle(create_req("/me", Some("ferris"))).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.handle(create_req("/me", None)).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = service.handle(create_req("/anyone", None)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn auth_guard_test() {
+        let service = App::<Base>::new()
+            .authenticator(HeaderAuthenticator)
+            .route(
+                Route::get("/admin", || async { "admin" })
+                    .guard(AuthGuard::new().authorize(|user: &User| user.admin)),
+            )
+            .route(
+                Route::get("/dashboard", || async { "dashboard" })
+                    .guard(AuthGuard::new().redirect_to("/login")),
+            )
+            .build();
+
+        let res = service.handle(create_req("/admin", None)).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = service.handle(create_req("/admin", Some("ferris"))).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = service.handle(create_req("/admin", Some("admin"))).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.handle(create_req("/dashboard", None)).await;
+        assert_eq!(res.status(), StatusCode::SEE_OTHER);
+        assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/login");
+    }
+
+    #[cfg(not(feature = "client"))]
+    #[tokio::test]
+    async fn expose_user_test() {
+        #[yew::function_component]
+        fn Profile() -> yew::Html {
+            yew::html! {
+                "profile"
+            }
+        }
+
+        crate::impl_page_component!(Profile, "/profile");
+
+        let service = App::<Base>::new()
+            .authenticator(HeaderAuthenticator)
+            .page::<Profile>()
+            .build();
+
+        let res = service.handle(create_req("/profile", Some("ferris"))).await;
+        let html = get_text(res).await;
+        assert!(html.contains("profile"), "body: {html}");
+        assert!(!html.contains("ferris"), "body: {html}");
+
+        let service = App::<Base>::new()
+            .authenticator(HeaderAuthenticator)
+            .expose_user()
+            .page::<Profile>()
+            .build();
+
+        let res = service.handle(create_req("/profile", Some("ferris"))).await;
+        let html = get_text(res).await;
+        assert!(html.contains("ferris"), "body: {html}");
+    }
+}
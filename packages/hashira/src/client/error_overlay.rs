@@ -0,0 +1,60 @@
+use crate::error::ClientPanic;
+
+const ERROR_OVERLAY_ID: &str = "__hashira_error_overlay__";
+
+/// Displays an overlay with the panic message and location over the page.
+///
+/// After a panic the wasm module can't run the `yew` scheduler anymore,
+/// so the overlay is created directly in the DOM.
+pub(crate) fn show_error_overlay(panic: &ClientPanic) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+
+    let Some(body) = document.body() else {
+        return;
+    };
+
+    let overlay = match document.get_element_by_id(ERROR_OVERLAY_ID) {
+        Some(overlay) => overlay,
+        None => {
+            let Ok(overlay) = document.create_element("div") else {
+                return;
+            };
+
+            overlay.set_id(ERROR_OVERLAY_ID);
+            let _ = overlay.set_attribute("style", OVERLAY_STYLES);
+
+            if body.append_child(&overlay).is_err() {
+                return;
+            }
+
+            overlay
+        }
+    };
+
+    let location = panic
+        .location()
+        .map(|location| format!("<p>at <code>{}</code></p>", escape(location)))
+        .unwrap_or_default();
+
+    overlay.set_inner_html(&format!(
+        r#"<button onclick="this.parentElement.remove()" style="float: right; cursor: pointer;">Close</button>
+        <h2 style="margin-top: 0; color: #e5484d;">Client panic</h2>
+        <pre style="white-space: pre-wrap; font-size: 1rem;">{}</pre>
+        {location}
+        <p style="color: #808080; font-size: 0.85rem;">This overlay is only displayed in debug builds, see the console for more details.</p>"#,
+        escape(panic.message())
+    ));
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const OVERLAY_STYLES: &str = "position: fixed; inset: 0; z-index: 2147483647; overflow: auto; \
+    padding: 2rem; background: rgba(20, 20, 20, 0.95); color: #e6e6e6; \
+    font-family: system-ui, -apple-system, 'Segoe UI', Roboto, sans-serif;";
@@ -1,7 +1,10 @@
+mod error_overlay;
+
 use wasm_bindgen::JsCast;
 use crate::app::AppService;
 use crate::components::{PageData, PageProps, HASHIRA_WASM_LOADER};
 use crate::context::ServerContext;
+use crate::error::{ClientErrorReporting, ClientPanic};
 
 use yew::html::ChildrenProps;
 use yew::BaseComponent;
@@ -13,6 +16,12 @@ pub fn mount<BASE>(service: AppService)
 where
     BASE: BaseComponent<Properties = ChildrenProps>,
 {
+    // Set the hook first to also catch the panics during hydration
+    set_panic_hook(&service);
+
+    #[cfg(feature = "hooks")]
+    set_client_hooks(&service);
+
     let page_data_element = find_element_by_id(HASHIRA_PAGE_DATA).unwrap();
     let content = page_data_element
         .text_content()
@@ -59,18 +68,39 @@ where
     }
 }
 
-// TODO: during development show a modal with the error,
-// this way the error is not just hidden by the console
-#[allow(dead_code, unused_variables)]
+#[cfg(feature = "hooks")]
+thread_local! {
+    static CLIENT_HOOKS: std::cell::RefCell<Option<std::sync::Arc<crate::events::Hooks>>> = Default::default();
+}
+
+#[cfg(feature = "hooks")]
+fn set_client_hooks(service: &AppService) {
+    let hooks = service
+        .app_data()
+        .get::<std::sync::Arc<crate::events::Hooks>>()
+        .cloned();
+
+    CLIENT_HOOKS.with(|x| *x.borrow_mut() = hooks);
+}
+
+/// Returns the hooks of the app mounted in the client.
+#[cfg(feature = "hooks")]
+pub(crate) fn client_hooks() -> Option<std::sync::Arc<crate::events::Hooks>> {
+    CLIENT_HOOKS.with(|x| x.borrow().clone())
+}
+
 fn set_panic_hook(service: &AppService) {
-    #[cfg(feature = "hooks")]
-    {
-        use crate::events::Hooks;
-        use std::sync::Arc;
+    let service = service.clone();
+
+    yew::set_custom_panic_hook(Box::new(move |info| {
+        // Send the error to the console
+        console_error_panic_hook::hook(info);
 
-        let service = service.clone();
+        #[cfg(feature = "hooks")]
+        {
+            use crate::events::Hooks;
+            use std::sync::Arc;
 
-        yew::set_custom_panic_hook(Box::new(move |info| {
             let hooks = service
                 .app_data()
                 .get::<Arc<Hooks>>()
@@ -79,10 +109,49 @@ fn set_panic_hook(service: &AppService) {
             for on_error in hooks.on_client_error_hooks.iter() {
                 on_error.call(info);
             }
+        }
+
+        let panic = client_panic(info);
+
+        if let Some(reporting) = service.app_data().get::<ClientErrorReporting>() {
+            report_panic(reporting.endpoint_path(), &panic);
+        }
+
+        if cfg!(debug_assertions) {
+            error_overlay::show_error_overlay(&panic);
+        }
+    }));
+}
+
+#[allow(deprecated)]
+fn client_panic(info: &std::panic::PanicInfo) -> ClientPanic {
+    let payload = info.payload();
+    let message = if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        String::from("Box<dyn Any>")
+    };
+
+    let location = info.location().map(|loc| loc.to_string());
+    let url = web_sys::window().and_then(|w| w.location().href().ok());
+    ClientPanic::new(message, location, url)
+}
+
+// We use `sendBeacon` because the wasm module can't await a request after a panic
+fn report_panic(endpoint: &str, panic: &ClientPanic) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    let Ok(json) = serde_json::to_string(panic) else {
+        return;
+    };
 
-            // Send the error to the console
-            console_error_panic_hook::hook(info);
-        }));
+    match window.navigator().send_beacon_with_opt_str(endpoint, Some(&json)) {
+        Ok(true) => {}
+        _ => log::warn!("failed to report panic to `{endpoint}`"),
     }
 }
 
@@ -0,0 +1,362 @@
+use crate::error::BoxError;
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+use yew::{
+    function_component, hook, use_context, use_effect, use_mut_ref, use_state, Callback, Children,
+    ContextProvider, Html, Properties, UseStateSetter,
+};
+
+// Allows to share the error of an action result without cloning it.
+trait AsBoxError {
+    fn as_box_error(&self) -> &BoxError;
+}
+
+impl AsBoxError for BoxError {
+    fn as_box_error(&self) -> &BoxError {
+        self
+    }
+}
+
+impl<T> AsBoxError for crate::Result<T> {
+    fn as_box_error(&self) -> &BoxError {
+        self.as_ref().err().expect("expected an error result")
+    }
+}
+
+/// An error reported to an [`ErrorBoundary`].
+#[derive(Clone)]
+pub struct BoundaryError(Rc<dyn AsBoxError>);
+
+impl BoundaryError {
+    /// Constructs a new boundary error.
+    pub fn new(error: impl Into<BoxError>) -> Self {
+        BoundaryError(Rc::new(error.into()))
+    }
+
+    /// Constructs a boundary error that shares the error of the result, if any.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub(crate) fn from_result<T: 'static>(result: Rc<crate::Result<T>>) -> Option<Self> {
+        match result.is_err() {
+            true => Some(BoundaryError(result)),
+            false => None,
+        }
+    }
+
+    /// Returns the underlying error.
+    pub fn error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.0.as_box_error().as_ref()
+    }
+
+    /// Returns a reference to the underlying error if is of type `E`.
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.0.as_box_error().downcast_ref::<E>()
+    }
+}
+
+impl PartialEq for BoundaryError {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for BoundaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.0.as_box_error(), f)
+    }
+}
+
+impl Display for BoundaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.as_box_error())
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct BoundaryContext {
+    fallback: Callback<BoundaryError, Html>,
+    set_error: UseStateSetter<Option<BoundaryError>>,
+}
+
+/// A handle to report errors to the nearest [`ErrorBoundary`].
+#[derive(Clone, PartialEq)]
+pub struct ErrorBoundaryHandle {
+    boundary: Option<BoundaryContext>,
+    hooks: Option<HooksRef>,
+    pending: Rc<RefCell<Option<BoundaryError>>>,
+}
+
+impl ErrorBoundaryHandle {
+    /// Returns the fallback to render in place of the failing component,
+    /// the error is reported to the boundary after the component renders.
+    ///
+    /// If there is no boundary the error is only reported and nothing is rendered.
+    pub fn throw(&self, error: impl Into<BoxError>) -> Html {
+        let error = BoundaryError::new(error);
+        let fallback = match &self.boundary {
+            Some(boundary) => boundary.fallback.emit(error.clone()),
+            None => Html::default(),
+        };
+
+        // Effects don't run on the server, so there the error is only reported
+        if cfg!(feature = "client") {
+            *self.pending.borrow_mut() = Some(error);
+        } else {
+            self.report(&error);
+        }
+
+        fallback
+    }
+
+    /// Returns the value of the result, or returns the fallback to render and reports the error.
+    ///
+    /// Use it with the `Result` returned by a hook, for example the result of a suspending hook.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use hashira::components::use_error_boundary;
+    /// use yew::{function_component, html, Html};
+    ///
+    /// #[function_component]
+    /// fn Counter() -> Html {
+    ///     let boundary = use_error_boundary();
+    ///     let count = match boundary.catch("12".parse::<u32>()) {
+    ///         Ok(count) => count,
+    ///         Err(fallback) => return fallback,
+    ///     };
+    ///
+    ///     html! { <span>{count}</span> }
+    /// }
+    /// ```
+    pub fn catch<T, E>(&self, result: Result<T, E>) -> Result<T, Html>
+    where
+        E: Into<BoxError>,
+    {
+        result.map_err(|err| self.throw(err))
+    }
+
+    /// Reports the error and renders the fallback of the boundary in place of its children,
+    /// this must not be called while rendering.
+    pub(crate) fn dispatch(&self, error: BoundaryError) {
+        self.report(&error);
+
+        if let Some(boundary) = &self.boundary {
+            boundary.set_error.set(Some(error));
+        }
+    }
+
+    fn report(&self, error: &BoundaryError) {
+        log::error!("component failed to render: {error}");
+
+        if let Some(hooks) = &self.hooks {
+            hooks.call(error);
+        }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ErrorBoundaryProps {
+    /// Renders the error reported by a child component.
+    pub fallback: Callback<BoundaryError, Html>,
+
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Renders a fallback when any of its children reports an error
+/// with the [`ErrorBoundaryHandle`] returned by [`use_error_boundary`],
+/// or when an action created with [`UseActionOptions::error_boundary`] fails.
+///
+/// The server and the hydration render the fallback in place of the failing component,
+/// after that the fallback replaces all the children of the boundary.
+///
+/// Only the reported errors are caught, a panic on the server responds with an error page
+/// and panics on the client abort the wasm module.
+///
+/// [`UseActionOptions::error_boundary`]: crate::actions::UseActionOptions::error_boundary
+///
+/// # Example
+/// ```rust,no_run
+/// use hashira::components::{BoundaryError, ErrorBoundary};
+/// use yew::{function_component, html, Html};
+///
+/// #[function_component]
+/// fn Dashboard() -> Html {
+///     html! {
+///         <ErrorBoundary fallback={|err: BoundaryError| html! { <p>{"failed: "}{err.to_string()}</p> }}>
+///             <h1>{"Dashboard"}</h1>
+///         </ErrorBoundary>
+///     }
+/// }
+/// ```
+#[function_component]
+pub fn ErrorBoundary(props: &ErrorBoundaryProps) -> Html {
+    let error = use_state(|| None::<BoundaryError>);
+
+    if let Some(error) = &*error {
+        return props.fallback.emit(error.clone());
+    }
+
+    let boundary = BoundaryContext {
+        fallback: props.fallback.clone(),
+        set_error: error.setter(),
+    };
+
+    yew::html! {
+        <ContextProvider<BoundaryContext> context={boundary}>
+            {for props.children.iter()}
+        </ContextProvider<BoundaryContext>>
+    }
+}
+
+/// Returns a handle to report errors to the nearest [`ErrorBoundary`].
+#[hook]
+pub fn use_error_boundary() -> ErrorBoundaryHandle {
+    let boundary = use_context::<BoundaryContext>();
+    let hooks = use_hooks_ref();
+    let pending = use_mut_ref(|| None);
+
+    let handle = ErrorBoundaryHandle {
+        boundary,
+        hooks,
+        pending,
+    };
+
+    // The errors thrown while rendering are reported after the render
+    {
+        let handle = handle.clone();
+        use_effect(move || {
+            let error = handle.pending.borrow_mut().take();
+            if let Some(error) = error {
+                handle.dispatch(error);
+            }
+        });
+    }
+
+    handle
+}
+
+// The `on_render_error` hooks of the app.
+#[cfg(feature = "hooks")]
+#[derive(Clone)]
+struct HooksRef(std::sync::Arc<crate::events::Hooks>);
+
+#[cfg(feature = "hooks")]
+impl HooksRef {
+    fn call(&self, error: &BoundaryError) {
+        for on_error in self.0.on_render_error_hooks.iter() {
+            on_error.call(error);
+        }
+    }
+}
+
+#[cfg(feature = "hooks")]
+impl PartialEq for HooksRef {
+    fn eq(&self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "hooks")]
+#[hook]
+fn use_hooks_ref() -> Option<HooksRef> {
+    #[cfg(feature = "client")]
+    {
+        crate::client::client_hooks().map(HooksRef)
+    }
+
+    #[cfg(not(feature = "client"))]
+    {
+        crate::context::use_server_context()
+            .and_then(|ctx| {
+                ctx.app_data::<std::sync::Arc<crate::events::Hooks>>()
+                    .cloned()
+            })
+            .map(HooksRef)
+    }
+}
+
+#[cfg(not(feature = "hooks"))]
+#[derive(Clone, PartialEq)]
+struct HooksRef;
+
+#[cfg(not(feature = "hooks"))]
+impl HooksRef {
+    fn call(&self, _: &BoundaryError) {}
+}
+
+#[cfg(not(feature = "hooks"))]
+#[hook]
+fn use_hooks_ref() -> Option<HooksRef> {
+    None
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::{use_error_boundary, BoundaryError, ErrorBoundary};
+    use std::rc::Rc;
+    use yew::{function_component, html, Html, LocalServerRenderer};
+
+    #[function_component]
+    fn UserName() -> Html {
+        let boundary = use_error_boundary();
+        let id = match boundary.catch("abc".parse::<u32>()) {
+            Ok(id) => id,
+            Err(fallback) => return fallback,
+        };
+
+        html! { <span>{id}</span> }
+    }
+
+    #[function_component]
+    fn Counter() -> Html {
+        let boundary = use_error_boundary();
+        let count = match boundary.catch("10".parse::<u32>()) {
+            Ok(count) => count,
+            Err(fallback) => return fallback,
+        };
+
+        html! { <span>{count}</span> }
+    }
+
+    #[function_component]
+    fn App() -> Html {
+        let fallback = |err: BoundaryError| {
+            let is_parse_error = err.downcast_ref::<std::num::ParseIntError>().is_some();
+            html! { <p>{format!("failed: {is_parse_error}")}</p> }
+        };
+
+        html! {
+            <>
+                <ErrorBoundary fallback={fallback}>
+                    <UserName/>
+                </ErrorBoundary>
+                <ErrorBoundary fallback={fallback}>
+                    <Counter/>
+                </ErrorBoundary>
+                // Without boundary nothing is rendered
+                <UserName/>
+            </>
+        }
+    }
+
+    #[test]
+    fn boundary_error_from_result_test() {
+        let ok: crate::Result<u32> = Ok(10);
+        assert!(BoundaryError::from_result(Rc::new(ok)).is_none());
+
+        let err: crate::Result<u32> = "abc".parse::<u32>().map_err(Into::into);
+        let error = BoundaryError::from_result(Rc::new(err)).unwrap();
+        assert!(error.downcast_ref::<std::num::ParseIntError>().is_some());
+        assert_eq!(error, error.clone());
+    }
+
+    #[tokio::test]
+    async fn error_boundary_ssr_test() {
+        let html = LocalServerRenderer::<App>::new()
+            .hydratable(false)
+            .render()
+            .await;
+
+        assert_eq!(html, "<p>failed: true</p><span>10</span>");
+    }
+}
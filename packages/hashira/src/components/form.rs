@@ -2,9 +2,11 @@ use http::Method;
 use web_sys::{window, FormData};
 use yew::html::onsubmit::Event;
 use yew::{function_component, AttrValue, Callback, Children, NodeRef, Properties};
-use yew::{Classes, TargetCast};
+use yew::{hook, use_context, Classes, ContextProvider, TargetCast};
 
 use crate::actions::{Action, AnyForm, RequestOptions, UseActionHandle};
+use crate::csrf::use_csrf_token;
+use crate::web::ValidationErrors;
 
 #[derive(Properties)]
 pub struct FormProps<A>
@@ -79,12 +81,16 @@ where
 }
 
 /// A form with extra functionality.
+///
+/// The validation errors returned by the action are available to the fields of the form
+/// using [`use_field_errors`] or the [`FieldErrors`] component.
 #[function_component]
 pub fn ActionForm<A>(props: &FormProps<A>) -> yew::Html
 where
     A: Action + 'static,
 {
     let action = props.action.clone();
+    let csrf = use_csrf_token();
     let loading = action.is_loading();
     let method = props.method.clone();
     let reload = props.reload;
@@ -96,6 +102,7 @@ where
         props.enc_type.clone()
     };
 
+    let validation_errors = action.validation_errors().cloned().unwrap_or_default();
     let form_enctype = enc_type.clone();
     let on_submit = move |event: yew::html::onsubmit::Event| {
         event.prevent_default();
@@ -142,7 +149,42 @@ where
             action={A::route()}
             enctype={form_enctype}
         >
-            {for props.children.iter()}
+            if let Some(csrf) = csrf {
+                <input type="hidden" name={csrf.field} value={csrf.value} />
+            }
+            <ContextProvider<ValidationErrors> context={validation_errors}>
+                {for props.children.iter()}
+            </ContextProvider<ValidationErrors>>
         </form>
     }
 }
+
+/// Returns the validation errors of the given field of the current [`ActionForm`].
+#[hook]
+pub fn use_field_errors(field: &str) -> Vec<String> {
+    use_context::<ValidationErrors>()
+        .map(|errors| errors.field(field).to_vec())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct FieldErrorsProps {
+    /// Name of the field.
+    pub name: AttrValue,
+
+    /// Classes of each error message.
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// Displays the validation errors of a field of the current [`ActionForm`].
+#[function_component]
+pub fn FieldErrors(props: &FieldErrorsProps) -> yew::Html {
+    let errors = use_field_errors(&props.name);
+
+    yew::html! {
+        {for errors.into_iter().map(|error| yew::html! {
+            <span class={props.class.clone()}>{error}</span>
+        })}
+    }
+}
@@ -1,4 +1,5 @@
 mod any;
+mod error_boundary;
 mod form;
 mod markers;
 mod page;
@@ -7,6 +8,7 @@ mod props_with_children;
 mod root;
 
 pub use any::*;
+pub use error_boundary::*;
 pub use form::*;
 pub use markers::*;
 pub use page::*;
@@ -54,7 +54,12 @@ where
     let page_data = &props.page_data;
 
     if let Some(error) = &page_data.error {
-        return match error_router.find(&error.status) {
+        // The maintenance page is rendered with its own component instead of the error page
+        let component = error_router
+            .find_maintenance(&page_data.id)
+            .or_else(|| error_router.find(&error.status));
+
+        return match component {
             Some(comp) => {
                 let props = page_data.props.clone();
                 yew::html! {
@@ -132,4 +137,20 @@ pub struct PageData {
 
     /// Params of the page, if any.
     pub params: Params,
+
+    /// The CSRF token of the session, if the protection is enabled.
+    #[serde(default)]
+    pub csrf: Option<crate::csrf::CsrfToken>,
+
+    /// The serialized user of the request, if an `Authenticator` is registered.
+    #[serde(default)]
+    pub user: Option<serde_json::Value>,
+
+    /// The flash messages to display in this page.
+    #[serde(default)]
+    pub flash: Vec<crate::flash::FlashMessage>,
+
+    /// The locale of the page and its translations, if the i18n is enabled.
+    #[serde(default)]
+    pub i18n: Option<crate::i18n::LocaleData>,
 }
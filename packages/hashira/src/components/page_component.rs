@@ -12,6 +12,11 @@ pub trait PageComponent: BaseComponent {
     /// The route of this page.
     fn route() -> Option<&'static str>;
 
+    /// Returns the guard of this page, if any.
+    fn guard() -> Option<std::sync::Arc<dyn crate::routing::Guard>> {
+        None
+    }
+
     /// A function that renders this page component.
     fn render<BASE>(ctx: RenderContext, body: Body) -> BoxFuture<Result<Response, BoxError>>
     where
@@ -102,7 +107,7 @@ pub(crate) mod macros {
                     _body: $crate::web::Body
                 ) -> $crate::types::BoxFuture<Result<$crate::web::Response, $crate::error::BoxError>>
                 where
-                    BASE: ::yew::BaseComponent<Properties = ChildrenProps>,
+                    BASE: ::yew::BaseComponent<Properties = ::yew::html::ChildrenProps>,
                 {
                     std::boxed::Box::pin(async move {
                         let res = ctx.render::<Self, BASE>().await;
@@ -123,7 +128,7 @@ pub(crate) mod macros {
                     _body: $crate::web::Body
                 ) -> $crate::types::BoxFuture<Result<$crate::web::Response, $crate::error::BoxError>>
                 where
-                    BASE: ::yew::BaseComponent<Properties = ChildrenProps>,
+                    BASE: ::yew::BaseComponent<Properties = ::yew::html::ChildrenProps>,
                 {
                     std::boxed::Box::pin(async move {
                         let res = ctx.render::<Self, BASE>().await;
@@ -0,0 +1,377 @@
+use crate::{
+    routing::RouteMethod,
+    web::{Body, Request, Response},
+};
+use http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use std::{str::FromStr, time::Duration};
+
+#[derive(Debug, Clone)]
+enum AllowedOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+#[derive(Debug, Clone)]
+enum AllowedHeaders {
+    Any,
+    List(Vec<HeaderName>),
+}
+
+/// Cross-origin resource sharing configuration.
+///
+/// It can be registered for all the routes with [`App::cors`], for the routes of a scope
+/// with [`AppNested::cors`] or for a single action with `#[action("/path", cors = "path::to::fn")]`,
+/// the most specific configuration is used.
+///
+/// Preflight requests are answered automatically, if no methods are specified
+/// the methods registered for the route are allowed.
+///
+/// [`App::cors`]: crate::app::App::cors
+/// [`AppNested::cors`]: crate::app::AppNested::cors
+#[derive(Debug, Clone)]
+pub struct Cors {
+    allowed_origins: AllowedOrigins,
+    allowed_methods: Option<Vec<Method>>,
+    allowed_headers: AllowedHeaders,
+    exposed_headers: Vec<HeaderName>,
+    allow_credentials: bool,
+    max_age: Option<Duration>,
+}
+
+impl Cors {
+    /// Constructs a configuration that don't allow any origin.
+    pub fn new() -> Self {
+        Cors {
+            allowed_origins: AllowedOrigins::List(vec![]),
+            allowed_methods: None,
+            allowed_headers: AllowedHeaders::List(vec![]),
+            exposed_headers: vec![],
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+
+    /// Constructs a configuration that allow any origin and headers.
+    pub fn permissive() -> Self {
+        Cors::new().allow_any_origin().allow_any_header()
+    }
+
+    /// Allow requests from any origin.
+    pub fn allow_any_origin(mut self) -> Self {
+        self.allowed_origins = AllowedOrigins::Any;
+        self
+    }
+
+    /// Allow requests from the given origin, for example: `https://example.com`.
+    ///
+    /// # Panic
+    /// If the origin is not a valid header value.
+    pub fn allow_origin(mut self, origin: &str) -> Self {
+        let origin = HeaderValue::from_str(origin).expect("invalid origin");
+
+        match &mut self.allowed_origins {
+            AllowedOrigins::Any => {}
+            AllowedOrigins::List(origins) => origins.push(origin),
+        }
+
+        self
+    }
+
+    /// Allow the given method, by default the methods of the route are allowed.
+    pub fn allow_method(mut self, method: Method) -> Self {
+        self.allowed_methods
+            .get_or_insert_with(Vec::new)
+            .push(method);
+        self
+    }
+
+    /// Allow any request header.
+    pub fn allow_any_header(mut self) -> Self {
+        self.allowed_headers = AllowedHeaders::Any;
+        self
+    }
+
+    /// Allow the given request header.
+    ///
+    /// # Panic
+    /// If the name is not a valid header name.
+    pub fn allow_header(mut self, name: &str) -> Self {
+        let name = HeaderName::from_str(name).expect("invalid header name");
+
+        match &mut self.allowed_headers {
+            AllowedHeaders::Any => {}
+            AllowedHeaders::List(headers) => headers.push(name),
+        }
+
+        self
+    }
+
+    /// Exposes the given response header to the client.
+    ///
+    /// # Panic
+    /// If the name is not a valid header name.
+    pub fn expose_header(mut self, name: &str) -> Self {
+        let name = HeaderName::from_str(name).expect("invalid header name");
+        self.exposed_headers.push(name);
+        self
+    }
+
+    /// Whether if allow to send credentials as cookies and authorization headers.
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// Sets how long the results of a preflight request can be cached.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    fn allow_origin_value(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        match &self.allowed_origins {
+            // The wildcard is not allowed with credentials, so we send back the origin
+            AllowedOrigins::Any if self.allow_credentials => Some(origin.clone()),
+            AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+            AllowedOrigins::List(origins) if origins.contains(origin) => Some(origin.clone()),
+            AllowedOrigins::List(_) => None,
+        }
+    }
+
+    fn is_method_allowed(&self, method: &Method, route_methods: RouteMethod) -> bool {
+        match &self.allowed_methods {
+            Some(methods) => methods.contains(method),
+            None => RouteMethod::from_str(method.as_str())
+                .map(|m| route_methods.matches(&m))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Returns the response for a preflight request.
+    pub(crate) fn preflight_response<B>(
+        &self,
+        req: &Request<B>,
+        route_methods: RouteMethod,
+    ) -> Response {
+        let headers = req.headers();
+        let requested_method = headers
+            .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| Method::from_str(x).ok());
+
+        let allow_origin = headers
+            .get(header::ORIGIN)
+            .and_then(|origin| self.allow_origin_value(origin));
+
+        let (Some(allow_origin), Some(requested_method)) = (allow_origin, requested_method) else {
+            return Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(Body::empty())
+                .unwrap();
+        };
+
+        if !self.is_method_allowed(&requested_method, route_methods) {
+            return Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(Body::empty())
+                .unwrap();
+        }
+
+        let mut res = Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)
+            .header(header::VARY, "Origin")
+            .body(Body::empty())
+            .unwrap();
+
+        let res_headers = res.headers_mut();
+
+        let allow_methods = match &self.allowed_methods {
+            Some(methods) => methods.iter().map(|m| m.to_string()).collect::<Vec<_>>(),
+            None => route_methods
+                .to_methods()
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+        };
+
+        if let Ok(value) = HeaderValue::from_str(&allow_methods.join(", ")) {
+            res_headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, value);
+        }
+
+        let allow_headers = match &self.allowed_headers {
+            // We just send back the requested headers
+            AllowedHeaders::Any => headers.get(header::ACCESS_CONTROL_REQUEST_HEADERS).cloned(),
+            AllowedHeaders::List(names) if !names.is_empty() => {
+                let names = names.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+                HeaderValue::from_str(&names.join(", ")).ok()
+            }
+            AllowedHeaders::List(_) => None,
+        };
+
+        if let Some(value) = allow_headers {
+            res_headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, value);
+        }
+
+        if self.allow_credentials {
+            res_headers.insert(
+                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+
+        if let Some(max_age) = self.max_age {
+            res_headers.insert(
+                header::ACCESS_CONTROL_MAX_AGE,
+                HeaderValue::from(max_age.as_secs()),
+            );
+        }
+
+        res
+    }
+
+    /// Adds the CORS headers to the response of a cross-origin request.
+    pub(crate) fn apply<B>(&self, req: &Request<B>, res: &mut Response) {
+        let Some(origin) = req.headers().get(header::ORIGIN) else {
+            return;
+        };
+
+        let Some(allow_origin) = self.allow_origin_value(origin) else {
+            return;
+        };
+
+        let headers = res.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        headers.append(header::VARY, HeaderValue::from_static("Origin"));
+
+        if self.allow_credentials {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+
+        if !self.exposed_headers.is_empty() {
+            let names = self
+                .exposed_headers
+                .iter()
+                .map(|x| x.as_str())
+                .collect::<Vec<_>>();
+
+            if let Ok(value) = HeaderValue::from_str(&names.join(", ")) {
+                headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, value);
+            }
+        }
+    }
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Cors::new()
+    }
+}
+
+/// Returns `true` if the request is a CORS preflight request.
+pub(crate) fn is_preflight<B>(req: &Request<B>) -> bool {
+    req.method() == Method::OPTIONS
+        && req.headers().contains_key(header::ORIGIN)
+        && req
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cors;
+    use crate::{routing::RouteMethod, web::Request};
+    use http::{header, Method, StatusCode};
+    use std::time::Duration;
+
+    #[test]
+    fn preflight_test() {
+        let cors = Cors::new()
+            .allow_origin("https://example.com")
+            .allow_header("content-type")
+            .max_age(Duration::from_secs(60));
+
+        let req = Request::builder()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, "https://example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(())
+            .unwrap();
+
+        let res = cors.preflight_response(&req, RouteMethod::POST | RouteMethod::PUT);
+        let headers = res.headers();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "POST, PUT"
+        );
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "content-type"
+        );
+        assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "60");
+    }
+
+    #[test]
+    fn preflight_rejected_test() {
+        let cors = Cors::new().allow_origin("https://example.com");
+
+        let other_origin = Request::builder()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, "https://evil.example")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(())
+            .unwrap();
+
+        let res = cors.preflight_response(&other_origin, RouteMethod::POST);
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let other_method = Request::builder()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, "https://example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "DELETE")
+            .body(())
+            .unwrap();
+
+        let res = cors.preflight_response(&other_method, RouteMethod::POST);
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn apply_test() {
+        let cors = Cors::permissive()
+            .allow_credentials(true)
+            .expose_header("x-total-count");
+
+        let req = Request::builder()
+            .header(header::ORIGIN, "https://example.com")
+            .body(())
+            .unwrap();
+
+        let mut res = Default::default();
+        cors.apply(&req, &mut res);
+
+        let headers = res.headers();
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            headers
+                .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+                .unwrap(),
+            "true"
+        );
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_EXPOSE_HEADERS).unwrap(),
+            "x-total-count"
+        );
+    }
+}
@@ -0,0 +1,470 @@
+#![cfg_attr(feature = "client", allow(dead_code))]
+
+use crate::{
+    app::RequestContext,
+    context::PageDataHandle,
+    error::{BoxError, ServerError},
+    web::{cookie::Cookie, Body, Bytes, BytesRejection, FromRequest, Request, RequestExt},
+};
+use http::{header, HeaderName, Method, StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use yew::{hook, use_context};
+
+/// Default name of the cookie that holds the CSRF token.
+pub const DEFAULT_CSRF_COOKIE: &str = "__hashira_csrf";
+
+/// Default name of the header used to send the CSRF token.
+pub const DEFAULT_CSRF_HEADER: &str = "x-csrf-token";
+
+/// Default name of the form field used to send the CSRF token.
+pub const DEFAULT_CSRF_FIELD: &str = "_csrf";
+
+/// How the requests are verified.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsrfMode {
+    /// Requires a token that matches the one stored in the session cookie.
+    Token,
+
+    /// Checks the `Origin` and `Sec-Fetch-Site` headers.
+    Origin,
+
+    /// Checks the origin of the request and requires a token.
+    TokenAndOrigin,
+}
+
+/// Configuration for the CSRF protection of the server actions.
+///
+/// When registered with [`App::csrf`], a token is stored in a session cookie and sent
+/// to the client with the page data, the `<Form>` component and `use_action` send it back,
+/// and actions called with `POST`, `PUT`, `PATCH` or `DELETE` are rejected with `403` on mismatch.
+///
+/// The form field is only read from `application/x-www-form-urlencoded` bodies, the multipart
+/// forms must send the token in the header, as the `<Form>` component does when submitted.
+///
+/// If a [`Key`] is registered in the `app_data` the cookie is signed.
+///
+/// [`App::csrf`]: crate::app::App::csrf
+/// [`Key`]: crate::web::Key
+#[derive(Debug, Clone)]
+pub struct CsrfProtection {
+    mode: CsrfMode,
+    cookie_name: String,
+    header_name: HeaderName,
+    field_name: String,
+    trusted_origins: Vec<String>,
+}
+
+impl CsrfProtection {
+    /// Constructs a configuration that checks the origin and the token of the requests.
+    pub fn new() -> Self {
+        CsrfProtection {
+            mode: CsrfMode::TokenAndOrigin,
+            cookie_name: DEFAULT_CSRF_COOKIE.to_owned(),
+            header_name: HeaderName::from_static(DEFAULT_CSRF_HEADER),
+            field_name: DEFAULT_CSRF_FIELD.to_owned(),
+            trusted_origins: Vec::new(),
+        }
+    }
+
+    /// Sets how the requests are verified.
+    pub fn mode(mut self, mode: CsrfMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the name of the cookie that holds the token.
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets the name of the header used to send the token.
+    ///
+    /// # Panic
+    /// If the name is not a valid header name.
+    pub fn header_name(mut self, name: &str) -> Self {
+        self.header_name = HeaderName::try_from(name).expect("invalid header name");
+        self
+    }
+
+    /// Sets the name of the form field used to send the token,
+    /// only read from `application/x-www-form-urlencoded` bodies.
+    pub fn field_name(mut self, name: impl Into<String>) -> Self {
+        self.field_name = name.into();
+        self
+    }
+
+    /// Adds an origin allowed to call the actions, for example: `https://example.com`.
+    pub fn trusted_origin(mut self, origin: impl Into<String>) -> Self {
+        self.trusted_origins.push(origin.into());
+        self
+    }
+
+    fn checks_token(&self) -> bool {
+        matches!(self.mode, CsrfMode::Token | CsrfMode::TokenAndOrigin)
+    }
+
+    fn checks_origin(&self) -> bool {
+        matches!(self.mode, CsrfMode::Origin | CsrfMode::TokenAndOrigin)
+    }
+}
+
+impl Default for CsrfProtection {
+    fn default() -> Self {
+        CsrfProtection::new()
+    }
+}
+
+/// The CSRF token sent to the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsrfToken {
+    /// The token value.
+    pub value: String,
+
+    /// The header used to send the token.
+    pub header: String,
+
+    /// The form field used to send the token.
+    pub field: String,
+}
+
+/// Returns the CSRF token of the current page, if the CSRF protection is enabled.
+#[hook]
+pub fn use_csrf_token() -> Option<CsrfToken> {
+    use_context::<PageDataHandle>().and_then(|page_data| page_data.csrf.clone())
+}
+
+/// Returns the token for the current session creating a new one if needed.
+pub(crate) fn get_or_create_token(ctx: &RequestContext) -> Option<CsrfToken> {
+    let csrf = ctx.app_data::<CsrfProtection>()?;
+
+    if !csrf.checks_token() {
+        return None;
+    }
+
+    let value = match get_session_token(ctx, csrf) {
+        Some(token) => token,
+        None => {
+            let token = generate_token();
+            let cookie = Cookie::build(csrf.cookie_name.clone(), token.clone())
+                .path("/")
+                .http_only(true)
+                .same_site(cookie::SameSite::Lax)
+                .finish();
+
+            if ctx.app_data::<crate::web::Key>().is_some() {
+                ctx.cookies().signed().add(cookie);
+            } else {
+                ctx.cookies().add(cookie);
+            }
+
+            token
+        }
+    };
+
+    Some(CsrfToken {
+        value,
+        header: csrf.header_name.to_string(),
+        field: csrf.field_name.clone(),
+    })
+}
+
+/// Verifies the request is not a cross-site request forgery.
+///
+/// Requests with safe methods or when the CSRF protection is not enabled are always valid,
+/// if the form body cannot be read the [`BytesRejection`] is returned.
+pub(crate) async fn verify_request(ctx: &RequestContext, body: &mut Body) -> Result<(), BoxError> {
+    let Some(csrf) = ctx.app_data::<CsrfProtection>() else {
+        return Ok(());
+    };
+
+    let req = ctx.request();
+    if is_safe_method(req.method()) {
+        return Ok(());
+    }
+
+    if csrf.checks_origin() && !is_allowed_origin(csrf, req) {
+        return Err(
+            ServerError::new(StatusCode::FORBIDDEN, "request origin is not allowed").into(),
+        );
+    }
+
+    if csrf.checks_token() {
+        let Some(expected) = get_session_token(ctx, csrf) else {
+            return Err(ServerError::new(StatusCode::FORBIDDEN, "csrf token not found").into());
+        };
+
+        let actual = match req.headers().get(&csrf.header_name) {
+            Some(value) => value.to_str().ok().map(|x| x.to_owned()),
+            None => read_form_token(ctx, body, &csrf.field_name).await?,
+        };
+
+        match actual {
+            Some(actual) if constant_time_eq(actual.as_bytes(), expected.as_bytes()) => {}
+            _ => {
+                return Err(ServerError::new(StatusCode::FORBIDDEN, "invalid csrf token").into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn get_session_token(ctx: &RequestContext, csrf: &CsrfProtection) -> Option<String> {
+    let cookie = if ctx.app_data::<crate::web::Key>().is_some() {
+        ctx.cookies().signed().get(&csrf.cookie_name)
+    } else {
+        ctx.cookies().get(&csrf.cookie_name)
+    };
+
+    cookie.map(|c| c.value().to_owned())
+}
+
+fn is_allowed_origin(csrf: &CsrfProtection, req: &Request<()>) -> bool {
+    let headers = req.headers();
+    let fetch_site = headers.get("sec-fetch-site").and_then(|x| x.to_str().ok());
+
+    if matches!(fetch_site, Some("same-origin") | Some("none")) {
+        return true;
+    }
+
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|x| x.to_str().ok()) else {
+        // Without an origin we can only rely on the fetch metadata
+        return !matches!(fetch_site, Some("cross-site") | Some("same-site"));
+    };
+
+    if csrf.trusted_origins.iter().any(|x| x == origin) {
+        return true;
+    }
+
+    let origin_host = origin
+        .parse::<Uri>()
+        .ok()
+        .and_then(|uri| uri.authority().map(|x| x.to_string()));
+
+    let host = headers
+        .get(header::HOST)
+        .and_then(|x| x.to_str().ok())
+        .map(|x| x.to_owned())
+        .or_else(|| req.uri().authority().map(|x| x.to_string()));
+
+    matches!((origin_host, host), (Some(origin_host), Some(host)) if origin_host == host)
+}
+
+// Reads the token from an url-encoded form body, the body is restored after being read
+async fn read_form_token(
+    ctx: &RequestContext,
+    body: &mut Body,
+    field: &str,
+) -> Result<Option<String>, BytesRejection> {
+    let Some(content_type) = ctx.request().content_type() else {
+        return Ok(None);
+    };
+
+    if content_type.essence_str() != mime::APPLICATION_WWW_FORM_URLENCODED.essence_str() {
+        return Ok(None);
+    }
+
+    // The body is read within the body limit of the route
+    let bytes = Bytes::from_request(ctx, body).await?;
+
+    let token = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+        .ok()
+        .and_then(|fields| {
+            fields
+                .into_iter()
+                .find(|(name, _)| name == field)
+                .map(|(_, value)| value)
+        });
+
+    *body = Body::from(bytes);
+    Ok(token)
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+fn generate_token() -> String {
+    use rand::RngCore;
+
+    let mut bytes = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_request, CsrfMode, CsrfProtection, DEFAULT_CSRF_COOKIE};
+    use crate::{
+        app::{
+            router::{PageRouter, PageRouterWrapper},
+            AppData, RequestContext,
+        },
+        error::ServerError,
+        routing::{ErrorRouter, Params},
+        types::TryBoxStream,
+        web::{Body, BodyLimit, Bytes, Rejection, Request},
+    };
+    use http::{header, Method, StatusCode};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn token_header_test() {
+        let csrf = CsrfProtection::new().mode(CsrfMode::Token);
+
+        let valid = Request::builder()
+            .method(Method::POST)
+            .header(header::COOKIE, format!("{DEFAULT_CSRF_COOKIE}=abc"))
+            .header("x-csrf-token", "abc")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(valid, csrf.clone());
+        assert!(verify_request(&ctx, &mut Body::empty()).await.is_ok());
+
+        let invalid = Request::builder()
+            .method(Method::POST)
+            .header(header::COOKIE, format!("{DEFAULT_CSRF_COOKIE}=abc"))
+            .header("x-csrf-token", "xyz")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(invalid, csrf);
+        let err = verify_request(&ctx, &mut Body::empty()).await.unwrap_err();
+        assert_eq!(ServerError::from_error(err).status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn token_form_field_test() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .header(header::COOKIE, format!("{DEFAULT_CSRF_COOKIE}=abc"))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(req, CsrfProtection::new().mode(CsrfMode::Token));
+        let mut body = Body::from("name=Sayaka&_csrf=abc");
+        assert!(verify_request(&ctx, &mut body).await.is_ok());
+
+        // The body is still readable
+        let bytes = body.into_bytes().await.unwrap();
+        assert_eq!(bytes, "name=Sayaka&_csrf=abc");
+    }
+
+    #[tokio::test]
+    async fn token_form_field_body_limit_test() {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .header(header::COOKIE, format!("{DEFAULT_CSRF_COOKIE}=abc"))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(())
+            .unwrap();
+
+        req.extensions_mut().insert(BodyLimit::new(8));
+
+        let ctx = create_request_context(req, CsrfProtection::new().mode(CsrfMode::Token));
+        let mut body = Body::from("name=Sayaka&_csrf=abc");
+        let err = verify_request(&ctx, &mut body).await.unwrap_err();
+        let rejection = Rejection::from_error(err).unwrap();
+        assert_eq!(rejection.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn token_form_field_read_error_test() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .header(header::COOKIE, format!("{DEFAULT_CSRF_COOKIE}=abc"))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(req, CsrfProtection::new().mode(CsrfMode::Token));
+        let stream = futures::stream::once(async { Err("connection reset".into()) });
+        let mut body = Body::from(Box::pin(stream) as TryBoxStream<Bytes>);
+
+        // The read error is returned instead of a csrf failure
+        let err = verify_request(&ctx, &mut body).await.unwrap_err();
+        let rejection = Rejection::from_error(err).unwrap();
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn origin_test() {
+        let csrf = CsrfProtection::new()
+            .mode(CsrfMode::Origin)
+            .trusted_origin("https://partner.example");
+
+        let same_origin = Request::builder()
+            .method(Method::POST)
+            .header(header::HOST, "example.com")
+            .header(header::ORIGIN, "https://example.com")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(same_origin, csrf.clone());
+        assert!(verify_request(&ctx, &mut Body::empty()).await.is_ok());
+
+        let trusted = Request::builder()
+            .method(Method::POST)
+            .header(header::HOST, "example.com")
+            .header(header::ORIGIN, "https://partner.example")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(trusted, csrf.clone());
+        assert!(verify_request(&ctx, &mut Body::empty()).await.is_ok());
+
+        let cross_site = Request::builder()
+            .method(Method::POST)
+            .header(header::HOST, "example.com")
+            .header(header::ORIGIN, "https://evil.example")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(cross_site, csrf.clone());
+        assert!(verify_request(&ctx, &mut Body::empty()).await.is_err());
+
+        let fetch_site = Request::builder()
+            .method(Method::DELETE)
+            .header("sec-fetch-site", "cross-site")
+            .body(())
+            .unwrap();
+
+        let ctx = create_request_context(fetch_site, csrf);
+        assert!(verify_request(&ctx, &mut Body::empty()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn safe_method_test() {
+        let req = Request::builder().method(Method::GET).body(()).unwrap();
+        let ctx = create_request_context(req, CsrfProtection::new());
+        assert!(verify_request(&ctx, &mut Body::empty()).await.is_ok());
+    }
+
+    fn create_request_context(req: Request<()>, csrf: CsrfProtection) -> RequestContext {
+        let mut app_data = AppData::default();
+        app_data.insert(csrf);
+
+        RequestContext::new(
+            Arc::new(req),
+            Arc::new(app_data),
+            PageRouterWrapper::from(PageRouter::new()),
+            Arc::new(ErrorRouter::new()),
+            None,
+            Params::default(),
+        )
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// The default endpoint where the client reports its panics.
+pub const DEFAULT_CLIENT_ERRORS_ENDPOINT: &str = "/_hashira/client-errors";
+
+// Max size of a panic report sent by the client
+const MAX_REPORT_SIZE: usize = 16 * 1024;
+
+/// Information of a panic ocurred in the wasm client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientPanic {
+    message: String,
+    location: Option<String>,
+    url: Option<String>,
+}
+
+impl ClientPanic {
+    /// Constructs a new client panic information.
+    pub fn new(message: impl Into<String>, location: Option<String>, url: Option<String>) -> Self {
+        ClientPanic {
+            message: message.into(),
+            location,
+            url,
+        }
+    }
+
+    /// Returns the panic message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the source location of the panic, if any.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Returns the url of the page where the panic ocurred, if any.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+impl Display for ClientPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "panicked at '{}'", self.message)?;
+
+        if let Some(location) = &self.location {
+            write!(f, ", {location}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ClientPanic {}
+
+/// Enables the client to send its panics to the server.
+///
+/// The panics are sent to the endpoint with a `POST` request, are logged as errors
+/// in the server and passed to the `on_client_error_report` hooks.
+#[derive(Debug, Clone)]
+pub struct ClientErrorReporting {
+    endpoint: String,
+}
+
+impl ClientErrorReporting {
+    /// Constructs a reporting that sends the panics to the default endpoint.
+    pub fn new() -> Self {
+        ClientErrorReporting {
+            endpoint: DEFAULT_CLIENT_ERRORS_ENDPOINT.to_owned(),
+        }
+    }
+
+    /// Sets the endpoint where the client sends its panics.
+    pub fn endpoint(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        crate::routing::assert_valid_route(&path).expect("invalid client errors endpoint");
+        self.endpoint = path;
+        self
+    }
+
+    /// Returns the endpoint where the client sends its panics.
+    pub fn endpoint_path(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Returns the route that receives the panics of the client.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn route(&self) -> crate::routing::Route {
+        use crate::{app::RequestContext, routing::Route, web::Bytes};
+        use http::StatusCode;
+
+        Route::post(&self.endpoint, |ctx: RequestContext, bytes: Bytes| async move {
+            // The client sends the report with `navigator.sendBeacon` as `text/plain`
+            let report = match serde_json::from_slice::<ClientPanic>(&bytes) {
+                Ok(report) => report,
+                Err(_) => return StatusCode::BAD_REQUEST,
+            };
+
+            log::error!(
+                "client panicked at `{}`: {report}",
+                report.url().unwrap_or("<unknown>")
+            );
+
+            #[cfg(feature = "hooks")]
+            {
+                use crate::events::Hooks;
+                use std::sync::Arc;
+
+                if let Some(hooks) = ctx.app_data::<Arc<Hooks>>() {
+                    for hook in hooks.on_client_error_report_hooks.iter() {
+                        hook.call(&report, &ctx);
+                    }
+                }
+            }
+
+            #[cfg(not(feature = "hooks"))]
+            let _ = ctx;
+
+            StatusCode::NO_CONTENT
+        })
+        .body_limit(MAX_REPORT_SIZE)
+    }
+}
+
+impl Default for ClientErrorReporting {
+    fn default() -> Self {
+        ClientErrorReporting::new()
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::{ClientErrorReporting, ClientPanic, DEFAULT_CLIENT_ERRORS_ENDPOINT};
+    use crate::{app::App, web::Request};
+    use http::{Method, StatusCode};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(body: String) -> Request {
+        Request::builder()
+            .method(Method::POST)
+            .uri(DEFAULT_CLIENT_ERRORS_ENDPOINT)
+            .header("content-type", "text/plain;charset=UTF-8")
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_error_report_test() {
+        let app = App::<Base>::new().client_error_reporting(ClientErrorReporting::new());
+
+        #[cfg(feature = "hooks")]
+        let (app, reports) = {
+            use std::sync::{Arc, Mutex};
+
+            let reports = Arc::new(Mutex::new(Vec::new()));
+            let hooks = crate::events::Hooks::new().on_client_error_report({
+                let reports = reports.clone();
+                move |report: &ClientPanic, _: &crate::app::RequestContext| {
+                    reports.lock().unwrap().push(report.clone());
+                }
+            });
+
+            (app.hooks(hooks), reports)
+        };
+
+        let service = app.build();
+
+        let report = ClientPanic::new(
+            "index out of bounds",
+            Some("src/lib.rs:10:5".to_owned()),
+            Some("http://localhost:5000/users".to_owned()),
+        );
+
+        let body = serde_json::to_string(&report).unwrap();
+        let res = service.handle(create_req(body)).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let res = service.handle(create_req("not json".to_owned())).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        #[cfg(feature = "hooks")]
+        assert_eq!(reports.lock().unwrap().as_slice(), &[report]);
+    }
+}
@@ -1,8 +1,17 @@
+mod client_panic;
+pub use client_panic::*;
+
 mod js_error;
 pub use js_error::*;
 
+mod problem_details;
+pub use problem_details::*;
+
 mod server_error;
 pub use server_error::*;
 
+mod server_panic;
+pub use server_panic::*;
+
 /// A boxed error.
 pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
@@ -0,0 +1,339 @@
+use crate::web::{Body, IntoResponse, Response};
+use http::{header, HeaderValue, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt::Display;
+
+/// The content type of a problem details response.
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+const ABOUT_BLANK: &str = "about:blank";
+
+/// An error response body as described in [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807).
+///
+/// The errors of the actions and the routes that are not pages are sent as
+/// `application/problem+json`, any custom member is stored in the extensions.
+///
+/// # Example
+/// ```rust,no_run
+/// use hashira::error::ProblemDetails;
+/// use http::StatusCode;
+///
+/// let problem = ProblemDetails::new(StatusCode::CONFLICT)
+///     .with_type("https://example.com/problems/username-taken")
+///     .with_detail("the username `hashira` is already taken")
+///     .with_extension("code", "USERNAME_TAKEN");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type", default = "about_blank")]
+    type_uri: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+
+    #[serde(with = "crate::web::serde::status_code")]
+    status: StatusCode,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
+
+    #[serde(flatten)]
+    extensions: Map<String, Value>,
+}
+
+fn about_blank() -> String {
+    ABOUT_BLANK.to_owned()
+}
+
+impl ProblemDetails {
+    /// Constructs a problem with the given status, using the reason of the status as title.
+    pub fn new(status: StatusCode) -> Self {
+        ProblemDetails {
+            type_uri: about_blank(),
+            title: status.canonical_reason().map(|s| s.to_owned()),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Sets the URI that identifies the problem type, defaults to `about:blank`.
+    pub fn with_type(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Sets a short summary of the problem type.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets an explanation specific to this occurrence of the problem.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the URI that identifies this occurrence of the problem.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds a custom member to the problem.
+    ///
+    /// # Panics
+    /// If the value fails to serialize.
+    pub fn with_extension(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).expect("failed to serialize problem extension");
+        self.extensions.insert(name.into(), value);
+        self
+    }
+
+    /// Adds the fields of the given value as custom members of the problem.
+    ///
+    /// # Panics
+    /// If the value fails to serialize or is not serialized as an object.
+    pub fn with_data(mut self, data: impl Serialize) -> Self {
+        match serde_json::to_value(data).expect("failed to serialize problem data") {
+            Value::Object(fields) => self.extensions.extend(fields),
+            value => panic!("problem data should be an object, but was: {value}"),
+        }
+
+        self
+    }
+
+    /// Returns the URI that identifies the problem type.
+    pub fn type_uri(&self) -> &str {
+        &self.type_uri
+    }
+
+    /// Returns the summary of the problem type, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the status code.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the explanation of this occurrence of the problem, if any.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Returns the URI that identifies this occurrence of the problem, if any.
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// Returns the custom member with the given name, if any.
+    pub fn extension(&self, name: &str) -> Option<&Value> {
+        self.extensions.get(name)
+    }
+
+    /// Returns all the custom members.
+    pub fn extensions(&self) -> &Map<String, Value> {
+        &self.extensions
+    }
+
+    /// Deserializes the custom members into the given type.
+    pub fn data<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(Value::Object(self.extensions.clone()))
+    }
+
+    pub(crate) fn set_status(&mut self, status: StatusCode) {
+        self.status = status;
+    }
+
+    pub(crate) fn set_detail_if_none(&mut self, detail: &str) {
+        if self.detail.is_none() {
+            self.detail = Some(detail.to_owned());
+        }
+    }
+}
+
+impl Display for ProblemDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.title, &self.detail) {
+            (_, Some(detail)) => write!(f, "{detail}"),
+            (Some(title), None) => write!(f, "{title}"),
+            (None, None) => write!(f, "{}", self.status),
+        }
+    }
+}
+
+impl std::error::Error for ProblemDetails {}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        let json = serde_json::to_vec(&self).expect("failed to serialize problem details");
+
+        let mut res = Response::new(Body::from(json));
+        *res.status_mut() = status;
+        res.headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+        res
+    }
+}
+
+/// Replaces the body of an error response with its problem details, if any.
+pub(crate) fn into_problem_response(mut res: Response) -> Response {
+    let Some(mut problem) = res.extensions_mut().remove::<ProblemDetails>() else {
+        return res;
+    };
+
+    problem.set_status(res.status());
+
+    let (mut parts, _) = res.into_parts();
+    let (problem_parts, body) = problem.into_response().into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.extend(problem_parts.headers);
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::{ProblemDetails, PROBLEM_JSON};
+    use crate::{
+        app::App,
+        error::ServerError,
+        routing::{HandlerKind, Route},
+        web::Request,
+    };
+    use http::{header, StatusCode};
+    use serde::{Deserialize, Serialize};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ErrorData {
+        code: String,
+        retry: bool,
+    }
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    async fn get_problem(res: crate::web::Response) -> ProblemDetails {
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON
+        );
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn problem_details_serialize_test() {
+        let problem = ProblemDetails::new(StatusCode::CONFLICT)
+            .with_detail("username taken")
+            .with_data(ErrorData {
+                code: "USERNAME_TAKEN".to_owned(),
+                retry: false,
+            });
+
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Conflict",
+                "status": 409,
+                "detail": "username taken",
+                "code": "USERNAME_TAKEN",
+                "retry": false
+            })
+        );
+
+        let problem: ProblemDetails = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            problem.data::<ErrorData>().unwrap(),
+            ErrorData {
+                code: "USERNAME_TAKEN".to_owned(),
+                retry: false
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn problem_details_response_test() {
+        let mut page = Route::get("/page", || async move {
+            let ret: crate::Result<&str> = Err("page failed".into());
+            ret
+        });
+
+        page.extensions_mut().insert(HandlerKind::Page);
+
+        let service = App::<Base>::new()
+            .route(Route::get("/api/message", || async move {
+                let ret: crate::Result<&str> = Err("something failed".into());
+                ret
+            }))
+            .route(Route::get("/api/conflict", || async move {
+                let err = ServerError::new(StatusCode::CONFLICT, "username taken")
+                    .with_type("https://example.com/problems/username-taken")
+                    .with_extension("code", "USERNAME_TAKEN");
+
+                let ret: crate::Result<&str> = Err(err.into());
+                ret
+            }))
+            .route(Route::get("/api/custom", || async move {
+                (StatusCode::BAD_REQUEST, "custom body")
+            }))
+            .route(page)
+            .build();
+
+        let res = service.handle(create_req("/api/message")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let problem = get_problem(res).await;
+        assert_eq!(problem.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(problem.title(), Some("Internal Server Error"));
+        assert_eq!(problem.detail(), Some("something failed"));
+
+        let res = service.handle(create_req("/api/conflict")).await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let problem = get_problem(res).await;
+        assert_eq!(
+            problem.type_uri(),
+            "https://example.com/problems/username-taken"
+        );
+        assert_eq!(problem.detail(), Some("username taken"));
+        assert_eq!(problem.extension("code").unwrap(), "USERNAME_TAKEN");
+
+        // Responses created by the handlers are not changed
+        let res = service.handle(create_req("/api/custom")).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "custom body");
+
+        // Pages still render the error
+        let res = service.handle(create_req("/page")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_ne!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON
+        );
+    }
+}
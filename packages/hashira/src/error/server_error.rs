@@ -1,7 +1,17 @@
-use super::BoxError;
+use super::{BoxError, ProblemDetails};
 use crate::web::{IntoResponse, Response, ResponseExt};
 use http::StatusCode;
-use std::fmt::{Debug, Display};
+use serde::Serialize;
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+type SourceError = Arc<dyn std::error::Error + Send + Sync>;
+
+/// The error a response was created from, used to keep the source of a `ServerError`.
+#[derive(Clone)]
+pub(crate) struct ErrorSource(pub SourceError);
 
 enum Responder {
     Message(String),
@@ -12,6 +22,8 @@ enum Responder {
 pub struct ServerError {
     status: StatusCode,
     responder: Option<Responder>,
+    problem: Option<Box<ProblemDetails>>,
+    source: Option<SourceError>,
 }
 
 impl ServerError {
@@ -22,16 +34,21 @@ impl ServerError {
         ServerError {
             status,
             responder: Some(Responder::Message(msg.to_string())),
+            problem: None,
+            source: None,
         }
     }
 
     fn _from_response(status: StatusCode, response: Response) -> Self {
         assert_status_code(status);
         let res = response.into_response();
+        let source = res.extensions().get::<ErrorSource>().map(|x| x.0.clone());
 
         ServerError {
             status,
             responder: Some(Responder::Response(res)),
+            problem: None,
+            source,
         }
     }
 
@@ -53,6 +70,8 @@ impl ServerError {
         ServerError {
             status,
             responder: None,
+            problem: None,
+            source: None,
         }
     }
 
@@ -64,11 +83,80 @@ impl ServerError {
             return *error.downcast().unwrap();
         }
 
+        if error.is::<ProblemDetails>() {
+            return ServerError::from(*error.downcast::<ProblemDetails>().unwrap());
+        }
+
         let msg = error.to_string();
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
             responder: Some(Responder::Message(msg)),
+            problem: None,
+            source: Some(Arc::from(error)),
+        }
+    }
+
+    /// Returns the error this was created from, if any.
+    pub fn source_error(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
+        self.source.as_deref()
+    }
+
+    /// Sets the URI that identifies the type of this error in the problem details.
+    pub fn with_type(self, type_uri: impl Into<String>) -> Self {
+        self.map_problem(|problem| problem.with_type(type_uri))
+    }
+
+    /// Sets the title of the problem details of this error.
+    pub fn with_title(self, title: impl Into<String>) -> Self {
+        self.map_problem(|problem| problem.with_title(title))
+    }
+
+    /// Sets the URI that identifies this occurrence of the error in the problem details.
+    pub fn with_instance(self, instance: impl Into<String>) -> Self {
+        self.map_problem(|problem| problem.with_instance(instance))
+    }
+
+    /// Adds a custom member to the problem details of this error.
+    ///
+    /// # Panics
+    /// If the value fails to serialize.
+    pub fn with_extension(self, name: impl Into<String>, value: impl Serialize) -> Self {
+        self.map_problem(|problem| problem.with_extension(name, value))
+    }
+
+    /// Adds the fields of the given value as custom members of the problem details of this error.
+    ///
+    /// # Panics
+    /// If the value fails to serialize or is not serialized as an object.
+    pub fn with_data(self, data: impl Serialize) -> Self {
+        self.map_problem(|problem| problem.with_data(data))
+    }
+
+    fn map_problem(mut self, f: impl FnOnce(ProblemDetails) -> ProblemDetails) -> Self {
+        let problem = self
+            .problem
+            .take()
+            .map(|x| *x)
+            .unwrap_or_else(|| ProblemDetails::new(self.status));
+
+        self.problem = Some(Box::new(f(problem)));
+        self
+    }
+
+    /// Returns the problem details of this error,
+    /// the message of the error is used as the `detail`.
+    pub fn problem_details(&self) -> ProblemDetails {
+        let mut problem = self
+            .problem
+            .as_deref()
+            .cloned()
+            .unwrap_or_else(|| ProblemDetails::new(self.status));
+
+        if let Some(msg) = self.message() {
+            problem.set_detail_if_none(msg);
         }
+
+        problem
     }
 
     /// Returns the status code.
@@ -87,6 +175,27 @@ impl ServerError {
         }
     }
 
+    // Returns the extensions of the response of this error, if any.
+    pub(crate) fn extensions(&self) -> Option<&http::Extensions> {
+        match &self.responder {
+            Some(Responder::Response(res)) => Some(res.extensions()),
+            _ => None,
+        }
+    }
+
+    // Returns a copy of this error without the response, if any, but keeping its message.
+    #[allow(dead_code)]
+    pub(crate) async fn clone_without_response(&self) -> Self {
+        let responder = self.try_get_message().await.map(Responder::Message);
+
+        ServerError {
+            status: self.status,
+            responder,
+            problem: self.problem.clone(),
+            source: self.source.clone(),
+        }
+    }
+
     // Attempts to get the error message from the error or the response.
     #[allow(dead_code)]
     pub(crate) async fn try_get_message(&self) -> Option<String> {
@@ -130,10 +239,31 @@ impl From<(StatusCode, String)> for ServerError {
 
 impl std::error::Error for ServerError {}
 
+impl From<ProblemDetails> for ServerError {
+    fn from(problem: ProblemDetails) -> Self {
+        let status = problem.status();
+        assert_status_code(status);
+
+        ServerError {
+            status,
+            responder: problem.detail().map(|x| Responder::Message(x.to_owned())),
+            problem: Some(Box::new(problem)),
+            source: None,
+        }
+    }
+}
+
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
         let status = self.status();
-        match self.responder {
+
+        // Responses created by the handlers only have problem details if were set explicitly
+        let problem = match (&self.responder, &self.problem) {
+            (Some(Responder::Response(_)), None) => None,
+            _ => Some(self.problem_details()),
+        };
+
+        let mut res = match self.responder {
             Some(responder) => {
                 let mut res = match responder {
                     Responder::Message(msg) => msg.into_response(),
@@ -144,7 +274,18 @@ impl IntoResponse for ServerError {
                 res
             }
             None => status.into_response(),
+        };
+
+        // The actions and the routes that are not pages send the problem details
+        if let Some(problem) = problem {
+            res.extensions_mut().insert(problem);
         }
+
+        if let Some(source) = self.source {
+            res.extensions_mut().insert(ErrorSource(source));
+        }
+
+        res
     }
 }
 
@@ -0,0 +1,334 @@
+use crate::{
+    types::TryBoxStream,
+    web::{Body, Payload, Response},
+};
+use bytes::Bytes;
+use futures::StreamExt;
+use std::{any::Any, cell::RefCell, fmt::Display, panic::AssertUnwindSafe, sync::Once};
+
+/// Information of a panic ocurred while handling a request.
+///
+/// When a handler panics the server responds with a `500` error and the error hooks
+/// receive a response with this information in its extensions.
+///
+/// Panics can only be caught if the binary is compiled with `panic = "unwind"`,
+/// which is the default, with `panic = "abort"` the process is terminated instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerPanic {
+    message: String,
+    route: Option<String>,
+    location: Option<String>,
+    backtrace: Option<String>,
+}
+
+impl ServerPanic {
+    /// Constructs a panic information from the payload of the panic.
+    pub fn from_payload(payload: &(dyn Any + Send), route: Option<String>) -> Self {
+        // A panic caught in other thread, as the components rendered in the yew runtime
+        if let Some(panic) = payload.downcast_ref::<ServerPanic>() {
+            return ServerPanic {
+                route: route.or_else(|| panic.route.clone()),
+                ..panic.clone()
+            };
+        }
+
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            (*s).to_owned()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            String::from("Box<dyn Any>")
+        };
+
+        let (location, backtrace) = LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .map(|info| (Some(info.location), info.backtrace))
+            .unwrap_or_default();
+
+        ServerPanic {
+            message,
+            route,
+            location,
+            backtrace,
+        }
+    }
+
+    /// Returns the panic message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the pattern of the route where the panic ocurred, if any.
+    pub fn route(&self) -> Option<&str> {
+        self.route.as_deref()
+    }
+
+    /// Returns the source location of the panic, this is only captured in debug builds.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Returns the backtrace of the panic, this is only captured in debug builds
+    /// when backtraces are enabled with `RUST_BACKTRACE=1`.
+    pub fn backtrace(&self) -> Option<&str> {
+        self.backtrace.as_deref()
+    }
+
+    pub(crate) fn log(&self) {
+        match &self.route {
+            Some(route) => log::error!("panic while handling route `{route}`: {}", self.message),
+            None => log::error!("panic while handling request: {}", self.message),
+        }
+    }
+}
+
+impl Display for ServerPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "panicked at '{}'", self.message)
+    }
+}
+
+impl std::error::Error for ServerPanic {}
+
+struct PanicInfo {
+    location: String,
+    backtrace: Option<String>,
+}
+
+thread_local! {
+    // The information of the last panic in this thread, taken when the panic is caught
+    static LAST_PANIC: RefCell<Option<PanicInfo>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook that captures the location and backtrace of the panics,
+/// the previous hook is still called after.
+#[cfg_attr(any(feature = "client", not(debug_assertions)), allow(dead_code))]
+pub(crate) fn capture_panic_info() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let prev_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|loc| loc.to_string())
+                .unwrap_or_else(|| String::from("<unknown>"));
+
+            let backtrace = std::backtrace::Backtrace::capture();
+            let backtrace = match backtrace.status() {
+                std::backtrace::BacktraceStatus::Captured => Some(backtrace.to_string()),
+                _ => None,
+            };
+
+            LAST_PANIC.with(|last| {
+                *last.borrow_mut() = Some(PanicInfo {
+                    location,
+                    backtrace,
+                });
+            });
+
+            prev_hook(info);
+        }));
+    });
+}
+
+/// Catches the panics while streaming the body of the response,
+/// at that point the status was already sent so the stream just ends with an error.
+pub(crate) fn catch_stream_panics(mut res: Response, route: &str) -> Response {
+    let body = res.body_mut();
+
+    match body.take() {
+        Some(Payload::Stream(stream)) => {
+            let route = route.to_owned();
+            let stream = AssertUnwindSafe(stream)
+                .catch_unwind()
+                .map(move |ret| match ret {
+                    Ok(chunk) => chunk,
+                    Err(payload) => {
+                        let panic = ServerPanic::from_payload(&*payload, Some(route.clone()));
+                        panic.log();
+                        Err(panic.into())
+                    }
+                });
+
+            *body = Body::from(Box::pin(stream) as TryBoxStream<Bytes>);
+        }
+        Some(Payload::Bytes(bytes)) => *body = Body::from(bytes),
+        None => {}
+    }
+
+    res
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::ServerPanic;
+    use crate::{
+        app::App,
+        cors::Cors,
+        routing::{HandlerKind, Route},
+        types::TryBoxStream,
+        web::{Body, Bytes, Request, Response},
+    };
+    use futures::StreamExt;
+    use http::{header, StatusCode};
+    use yew::{function_component, html::ChildrenProps};
+
+    #[function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    async fn get_text(res: Response) -> String {
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn handler_panic_test() {
+        #[function_component]
+        fn ErrorFallbackTest() -> yew::Html {
+            yew::html! {
+                "test - oh oh"
+            }
+        }
+
+        crate::impl_page_component!(ErrorFallbackTest);
+
+        let mut route = Route::get("/panic/:id", || async move {
+            if true {
+                panic!("something went wrong");
+            }
+
+            "unreachable"
+        });
+
+        route.extensions_mut().insert(HandlerKind::Page);
+
+        let service = App::<Base>::new()
+            .error_page_fallback::<ErrorFallbackTest>()
+            .route(route)
+            .route(Route::get("/ok", || async { "ok" }))
+            .build();
+
+        let res = service.handle(create_req("/panic/1")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let panic = res.extensions().get::<ServerPanic>().cloned().unwrap();
+        assert_eq!(panic.message(), "something went wrong");
+        assert_eq!(panic.route(), Some("/panic/:id"));
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let html = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(html.contains("test - oh oh"), "body: {html}");
+
+        // The server still handle other requests
+        let res = service.handle(create_req("/ok")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn component_panic_test() {
+        #[function_component]
+        fn PanicPage() -> yew::Html {
+            if true {
+                panic!("failed to render");
+            }
+
+            yew::html! {
+                "unreachable"
+            }
+        }
+
+        #[function_component]
+        fn ErrorFallbackTest() -> yew::Html {
+            yew::html! {
+                "test - render failed"
+            }
+        }
+
+        crate::impl_page_component!(PanicPage, "/panic");
+        crate::impl_page_component!(ErrorFallbackTest);
+
+        let service = App::<Base>::new()
+            .error_page_fallback::<ErrorFallbackTest>()
+            .page::<PanicPage>()
+            .build();
+
+        let res = service.handle(create_req("/panic")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let panic = res.extensions().get::<ServerPanic>().cloned().unwrap();
+        assert_eq!(panic.message(), "failed to render");
+        assert_eq!(panic.route(), Some("/panic"));
+
+        let html = get_text(res).await;
+        assert!(html.contains("test - render failed"), "body: {html}");
+        assert!(!html.contains("unreachable"), "body: {html}");
+    }
+
+    #[tokio::test]
+    async fn panic_response_headers_test() {
+        let service = App::<Base>::new()
+            .cors(Cors::permissive())
+            .route(Route::get("/panic", || async move {
+                if true {
+                    panic!("something went wrong");
+                }
+
+                "unreachable"
+            }))
+            .build();
+
+        let mut req = create_req("/panic");
+        req.headers_mut()
+            .insert(header::ORIGIN, "https://example.com".parse().unwrap());
+
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.extensions().get::<ServerPanic>().is_some());
+        assert!(res
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn stream_panic_test() {
+        let service = App::<Base>::new()
+            .route(Route::get("/stream", || async move {
+                let stream = futures::stream::iter(0..3).map(|i| {
+                    if i == 1 {
+                        panic!("failed to stream");
+                    }
+
+                    Ok(Bytes::from(i.to_string()))
+                });
+
+                Body::from(Box::pin(stream) as TryBoxStream<Bytes>)
+            }))
+            .build();
+
+        let res = service.handle(create_req("/stream")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let mut stream = res.into_body().into_stream();
+        assert_eq!(stream.next().await.unwrap().unwrap(), "0");
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        let panic = err.downcast_ref::<ServerPanic>().unwrap();
+        assert_eq!(panic.message(), "failed to stream");
+        assert_eq!(panic.route(), Some("/stream"));
+
+        assert!(stream.next().await.is_none());
+    }
+}
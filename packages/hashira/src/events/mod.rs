@@ -1,14 +1,16 @@
 mod on_before_render;
 mod on_chunk_render;
 mod on_client_error;
+mod on_client_error_report;
 mod on_client_init;
 mod on_handle;
+mod on_render_error;
 mod on_server_error;
 mod on_server_init;
 
 pub use {
-    on_before_render::*, on_chunk_render::*, on_client_error::*, on_client_init::*, on_handle::*,
-    on_server_error::*, on_server_init::*,
+    on_before_render::*, on_chunk_render::*, on_client_error::*, on_client_error_report::*, on_client_init::*, on_handle::*,
+    on_render_error::*, on_server_error::*, on_server_init::*,
 };
 
 use std::fmt::Display;
@@ -22,7 +24,10 @@ pub struct Hooks {
     pub(crate) on_server_initialize_hooks: Vec<Box<dyn OnServerInitialize + Send + Sync>>,
     pub(crate) on_client_initialize_hooks: Vec<Box<dyn OnClientInitialize + Send + Sync>>,
     pub(crate) on_server_error_hooks: Vec<Box<dyn OnServerError + Send + Sync>>,
+    pub(crate) on_server_error_async_hooks: Vec<Box<dyn OnServerErrorAsync + Send + Sync>>,
     pub(crate) on_client_error_hooks: Vec<Box<dyn OnClientError + Send + Sync>>,
+    pub(crate) on_client_error_report_hooks: Vec<Box<dyn OnClientErrorReport + Send + Sync>>,
+    pub(crate) on_render_error_hooks: Vec<Box<dyn OnRenderError + Send + Sync>>,
 }
 
 impl Hooks {
@@ -112,6 +117,20 @@ impl Hooks {
         self
     }
 
+    /// Adds an async hook to be executed when a server error occurs,
+    /// these run after the `on_server_error` hooks.
+    #[allow(unused_mut, unused_variables)]
+    pub fn on_server_error_async<F>(mut self, f: F) -> Self
+    where
+        F: OnServerErrorAsync + Send + Sync + 'static,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            self.on_server_error_async_hooks.push(Box::new(f));
+        }
+        self
+    }
+
     /// Adds a hook to be executed when a client error occurs.
     #[allow(unused_mut, unused_variables)]
     pub fn on_client_error<F>(mut self, f: F) -> Self
@@ -125,6 +144,30 @@ impl Hooks {
         self
     }
 
+    /// Adds a hook to be executed when the server receives a panic reported by the client,
+    /// this requires enabling the `App::client_error_reporting`.
+    #[allow(unused_mut, unused_variables)]
+    pub fn on_client_error_report<F>(mut self, f: F) -> Self
+    where
+        F: OnClientErrorReport + Send + Sync + 'static,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            self.on_client_error_report_hooks.push(Box::new(f));
+        }
+        self
+    }
+
+    /// Adds a hook to be executed when a component reports an error to an `ErrorBoundary`,
+    /// this is ran on the server and the client.
+    pub fn on_render_error<F>(mut self, f: F) -> Self
+    where
+        F: OnRenderError + Send + Sync + 'static,
+    {
+        self.on_render_error_hooks.push(Box::new(f));
+        self
+    }
+
     pub fn extend(&mut self, hooks: Hooks) {
         self.on_handle_hooks.extend(hooks.on_handle_hooks);
 
@@ -143,8 +186,17 @@ impl Hooks {
         self.on_server_error_hooks
             .extend(hooks.on_server_error_hooks);
 
+        self.on_server_error_async_hooks
+            .extend(hooks.on_server_error_async_hooks);
+
         self.on_client_error_hooks
             .extend(hooks.on_client_error_hooks);
+
+        self.on_client_error_report_hooks
+            .extend(hooks.on_client_error_report_hooks);
+
+        self.on_render_error_hooks
+            .extend(hooks.on_render_error_hooks);
     }
 }
 
@@ -0,0 +1,16 @@
+use crate::{app::RequestContext, error::ClientPanic};
+
+/// A hook called when the server receives a panic reported by the client.
+pub trait OnClientErrorReport {
+    /// Called on each report.
+    fn call(&self, report: &ClientPanic, ctx: &RequestContext);
+}
+
+impl<F> OnClientErrorReport for F
+where
+    F: Fn(&ClientPanic, &RequestContext) + Send + Sync + 'static,
+{
+    fn call(&self, report: &ClientPanic, ctx: &RequestContext) {
+        (self)(report, ctx)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::components::BoundaryError;
+
+/// A hook called when a component reports an error to an `ErrorBoundary`,
+/// this runs on the server and the client.
+pub trait OnRenderError {
+    /// Called on each error.
+    fn call(&self, err: &BoundaryError);
+}
+
+impl<F> OnRenderError for F
+where
+    F: Fn(&BoundaryError) + Send + Sync + 'static,
+{
+    fn call(&self, err: &BoundaryError) {
+        (self)(err)
+    }
+}
@@ -1,8 +1,9 @@
-use crate::web::Response;
-
-// FIXME: Make async?
+use crate::{app::RequestContext, error::ServerError, web::Response};
+use futures::Future;
 
 /// A hook called when an response error is returned.
+///
+/// See [`OnServerErrorAsync`] for a hook that also receives the request and the error.
 pub trait OnServerError {
     fn call(&self, err: Response) -> Response;
 }
@@ -15,3 +16,138 @@ where
         (self)(err)
     }
 }
+
+/// An async hook called when an response error is returned,
+/// receives the context of the request, the error and the error response.
+#[async_trait::async_trait]
+pub trait OnServerErrorAsync {
+    /// Called on errors, returns the response to send.
+    async fn call(&self, ctx: RequestContext, err: ServerError, res: Response) -> Response;
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> OnServerErrorAsync for F
+where
+    F: Fn(RequestContext, ServerError, Response) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Response> + Send + 'static,
+{
+    async fn call(&self, ctx: RequestContext, err: ServerError, res: Response) -> Response {
+        (self)(ctx, err, res).await
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "hooks")]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use crate::{
+        app::{App, RequestContext},
+        error::ServerError,
+        events::Hooks,
+        routing::{HandlerKind, Route},
+        web::{Request, Response},
+    };
+    use http::{HeaderValue, StatusCode};
+    use std::sync::{Arc, Mutex};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("failed to load user")]
+    struct LoadUserError;
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn on_server_error_async_test() {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let hooks = Hooks::new().on_server_error_async({
+            let errors = errors.clone();
+            move |ctx: RequestContext, err: ServerError, mut res: Response| {
+                let errors = errors.clone();
+                async move {
+                    let is_load_user = err
+                        .source_error()
+                        .map(|x| x.is::<LoadUserError>())
+                        .unwrap_or_default();
+
+                    errors.lock().unwrap().push((
+                        ctx.path().to_owned(),
+                        err.status(),
+                        err.to_string(),
+                        is_load_user,
+                    ));
+
+                    res.headers_mut()
+                        .insert("x-correlation-id", HeaderValue::from_static("abc"));
+                    res
+                }
+            }
+        });
+
+        let mut page = Route::get("/users/:id", || async move {
+            let ret: crate::Result<&str> = Err(LoadUserError.into());
+            ret
+        });
+
+        page.extensions_mut().insert(HandlerKind::Page);
+
+        let service = App::<Base>::new()
+            .error_overlay(false)
+            .hooks(hooks)
+            .route(page)
+            .route(Route::get("/api/users", || async move {
+                let ret: crate::Result<&str> =
+                    Err(ServerError::new(StatusCode::CONFLICT, "user exists").into());
+                ret
+            }))
+            .build();
+
+        let res = service.handle(create_req("/users/1")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers().get("x-correlation-id").unwrap(), "abc");
+
+        let res = service.handle(create_req("/api/users")).await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert_eq!(res.headers().get("x-correlation-id").unwrap(), "abc");
+
+        let res = service.handle(create_req("/not-found")).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let errors = errors.lock().unwrap();
+        assert_eq!(
+            errors.as_slice(),
+            &[
+                (
+                    "/users/1".to_owned(),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to load user".to_owned(),
+                    true
+                ),
+                (
+                    "/api/users".to_owned(),
+                    StatusCode::CONFLICT,
+                    "user exists".to_owned(),
+                    false
+                ),
+                (
+                    "/not-found".to_owned(),
+                    StatusCode::NOT_FOUND,
+                    "404 Not Found".to_owned(),
+                    false
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+#![cfg_attr(feature = "client", allow(dead_code))]
+
+use crate::{
+    app::RequestContext,
+    context::PageDataHandle,
+    web::{cookie::Cookie, Body, CookieJar, FlashRejection, FromRequest},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    future::{ready, Ready},
+    sync::{Arc, Mutex},
+};
+use yew::{hook, use_context};
+
+/// Name of the cookie that holds the flash messages.
+pub const FLASH_COOKIE: &str = "__hashira_flash";
+
+/// The level of a flash message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlashLevel {
+    /// An informative message.
+    Info,
+
+    /// An operation completed successfully.
+    Success,
+
+    /// A warning.
+    Warning,
+
+    /// An error.
+    Error,
+}
+
+/// A message that is displayed in the next page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlashMessage {
+    /// The level of the message.
+    pub level: FlashLevel,
+
+    /// The message.
+    pub message: String,
+}
+
+/// Flash messages of the current request.
+///
+/// The messages added are stored in a cookie signed with the [`Key`] registered in the `app_data`,
+/// and are displayed by the next page rendered, which can read them with [`use_flash`].
+/// Once rendered the messages are removed.
+///
+/// # Errors
+/// Extracting the flash messages fails with a `500` [`FlashRejection`] if there is no [`Key`]
+/// in the `app_data`.
+///
+/// [`Key`]: crate::web::Key
+#[derive(Clone)]
+pub struct Flash {
+    cookies: CookieJar,
+    incoming: Arc<Vec<FlashMessage>>,
+    outgoing: Arc<Mutex<Vec<FlashMessage>>>,
+}
+
+impl Flash {
+    pub(crate) fn from_cookies(cookies: CookieJar) -> Result<Self, FlashRejection> {
+        if !cookies.has_key() {
+            return Err(FlashRejection::MissingKey);
+        }
+
+        let incoming = cookies
+            .signed()
+            .get(FLASH_COOKIE)
+            .and_then(|c| match serde_json::from_str(c.value()) {
+                Ok(messages) => Some(messages),
+                Err(err) => {
+                    log::warn!("invalid flash messages cookie: {err}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Ok(Flash {
+            cookies,
+            incoming: Arc::new(incoming),
+            outgoing: Default::default(),
+        })
+    }
+
+    /// Adds a message to display in the next page.
+    pub fn push(&self, level: FlashLevel, message: impl Into<String>) {
+        let mut outgoing = self.outgoing.lock().expect("failed to lock flash messages");
+        outgoing.push(FlashMessage {
+            level,
+            message: message.into(),
+        });
+
+        match serde_json::to_string(&*outgoing) {
+            Ok(json) => self.set_cookie(json),
+            Err(err) => log::error!("failed to serialize flash messages: {err}"),
+        }
+    }
+
+    /// Adds an info message.
+    pub fn info(&self, message: impl Into<String>) {
+        self.push(FlashLevel::Info, message)
+    }
+
+    /// Adds a success message.
+    pub fn success(&self, message: impl Into<String>) {
+        self.push(FlashLevel::Success, message)
+    }
+
+    /// Adds a warning message.
+    pub fn warning(&self, message: impl Into<String>) {
+        self.push(FlashLevel::Warning, message)
+    }
+
+    /// Adds an error message.
+    pub fn error(&self, message: impl Into<String>) {
+        self.push(FlashLevel::Error, message)
+    }
+
+    /// Returns the messages sent by the previous request.
+    pub fn messages(&self) -> &[FlashMessage] {
+        self.incoming.as_slice()
+    }
+
+    /// Returns the messages sent by the previous request and clears the cookie,
+    /// unless new messages were added in this request.
+    pub(crate) fn take(&self) -> Vec<FlashMessage> {
+        let outgoing = self.outgoing.lock().expect("failed to lock flash messages");
+
+        if !self.incoming.is_empty() && outgoing.is_empty() {
+            self.cookies
+                .remove(Cookie::build(FLASH_COOKIE, "").path("/").finish());
+        }
+
+        self.incoming.as_ref().clone()
+    }
+
+    fn set_cookie(&self, value: String) {
+        let cookie = Cookie::build(FLASH_COOKIE, value)
+            .path("/")
+            .http_only(true)
+            .same_site(cookie::SameSite::Lax)
+            .finish();
+
+        self.cookies.signed().add(cookie);
+    }
+}
+
+impl FromRequest for Flash {
+    type Error = FlashRejection;
+    type Fut = Ready<Result<Flash, FlashRejection>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        ready(ctx.flash())
+    }
+}
+
+/// Returns the flash messages for the page data.
+pub(crate) fn take_page_messages(ctx: &RequestContext) -> Vec<FlashMessage> {
+    // Avoid creating the flash if there is no cookie
+    if ctx.inner.flash.get().is_none() && ctx.cookies().get(FLASH_COOKIE).is_none() {
+        return Vec::new();
+    }
+
+    // The flash messages are not used without a key
+    ctx.flash().map(|flash| flash.take()).unwrap_or_default()
+}
+
+/// Returns the flash messages of the current page.
+#[hook]
+pub fn use_flash() -> Vec<FlashMessage> {
+    use_context::<PageDataHandle>()
+        .map(|page_data| page_data.flash.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Flash, FlashLevel, FLASH_COOKIE};
+    use crate::web::{
+        cookie::Cookie, CookieJar, FlashRejection, Key, Rejection, Request, Response,
+    };
+    use http::{header, StatusCode};
+
+    fn set_cookies(jar: &CookieJar) -> Vec<Cookie<'static>> {
+        let mut res = Response::default();
+        jar.write_to_response(&mut res);
+        res.headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|x| Cookie::parse_encoded(x.to_str().unwrap().to_owned()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn flash_roundtrip_test() {
+        let key = Key::generate();
+
+        // The request that sets the message
+        let jar = CookieJar::from_request(&Request::new(()), Some(key.clone()));
+        let flash = Flash::from_cookies(jar.clone()).unwrap();
+        flash.success("Todo saved; thanks!");
+
+        let cookie = set_cookies(&jar).pop().unwrap();
+        assert_eq!(cookie.name(), FLASH_COOKIE);
+
+        // The next request displays it
+        let req = Request::builder()
+            .header(header::COOKIE, cookie.stripped().encoded().to_string())
+            .body(())
+            .unwrap();
+
+        let jar = CookieJar::from_request(&req, Some(key));
+        let flash = Flash::from_cookies(jar.clone()).unwrap();
+        let messages = flash.take();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].level, FlashLevel::Success);
+        assert_eq!(messages[0].message, "Todo saved; thanks!");
+
+        // And is removed
+        let removal = set_cookies(&jar).pop().unwrap();
+        assert_eq!(removal.name(), FLASH_COOKIE);
+        assert!(removal.value().is_empty());
+    }
+
+    #[test]
+    fn flash_tampered_test() {
+        let req = Request::builder()
+            .header(header::COOKIE, format!("{FLASH_COOKIE}=tampered"))
+            .body(())
+            .unwrap();
+
+        let jar = CookieJar::from_request(&req, Some(Key::generate()));
+        let flash = Flash::from_cookies(jar).unwrap();
+        assert!(flash.messages().is_empty());
+    }
+
+    #[test]
+    fn flash_without_key_test() {
+        let jar = CookieJar::from_request(&Request::new(()), None);
+        let err = Flash::from_cookies(jar).err().unwrap();
+        assert!(matches!(err, FlashRejection::MissingKey));
+
+        let rejection = Rejection::from_error(err.into()).unwrap();
+        assert_eq!(rejection.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{
+    app::RequestContext,
+    error::BoxError,
+    types::BoxFuture,
+    web::{IntoResponse, Json, Response},
+};
+use futures::Future;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+
+/// The default path of the liveness endpoint.
+pub const DEFAULT_LIVENESS_PATH: &str = "/healthz";
+
+/// The default path of the readiness endpoint.
+pub const DEFAULT_READINESS_PATH: &str = "/readyz";
+
+/// The default max time a check can take.
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+type CheckFn = Arc<dyn Fn(RequestContext) -> BoxFuture<Result<(), BoxError>> + Send + Sync>;
+
+#[derive(Clone)]
+struct HealthCheck {
+    name: String,
+    liveness: bool,
+    timeout: Option<Duration>,
+    check: CheckFn,
+}
+
+/// The status of a check or the entire report.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The check succeeded.
+    Ok,
+
+    /// The check failed or timed out.
+    Error,
+}
+
+/// The result of a single check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// The name of the check.
+    pub name: String,
+
+    /// Whether the check succeeded.
+    pub status: HealthStatus,
+
+    /// The time the check took in milliseconds.
+    pub latency_ms: f64,
+
+    /// The error of a failed check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The result of running the checks of an endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// `ok` if all the checks succeeded.
+    pub status: HealthStatus,
+
+    /// The result of each check.
+    pub checks: Vec<CheckReport>,
+}
+
+impl IntoResponse for HealthReport {
+    fn into_response(self) -> Response {
+        let status = match self.status {
+            HealthStatus::Ok => StatusCode::OK,
+            HealthStatus::Error => StatusCode::SERVICE_UNAVAILABLE,
+        };
+
+        let mut res = Json(self).into_response();
+        *res.status_mut() = status;
+        res
+    }
+}
+
+/// Named checks exposed in a liveness and a readiness endpoint.
+///
+/// The readiness endpoint runs all the checks and the liveness endpoint only the checks
+/// added with [`HealthChecks::liveness_check`], both respond with a json [`HealthReport`]
+/// with a `200 OK` if all the checks succeed or a `503 Service Unavailable` otherwise.
+///
+/// The requests to these endpoints are not traced nor recorded in the metrics by default.
+///
+/// # Example
+/// ```rust,no_run
+/// use hashira::health::HealthChecks;
+///
+/// let checks = HealthChecks::new()
+///     .check("database", |_ctx| async move {
+///         // ping the database
+///         Ok::<_, std::io::Error>(())
+///     });
+/// ```
+#[derive(Clone)]
+pub struct HealthChecks {
+    checks: Vec<HealthCheck>,
+    liveness_path: String,
+    readiness_path: String,
+    timeout: Duration,
+    record_requests: bool,
+}
+
+impl HealthChecks {
+    /// Constructs an empty collection of checks using the default paths.
+    pub fn new() -> Self {
+        HealthChecks {
+            checks: vec![],
+            liveness_path: DEFAULT_LIVENESS_PATH.to_owned(),
+            readiness_path: DEFAULT_READINESS_PATH.to_owned(),
+            timeout: DEFAULT_CHECK_TIMEOUT,
+            record_requests: false,
+        }
+    }
+
+    fn add<F, Fut, E>(
+        mut self,
+        name: String,
+        liveness: bool,
+        timeout: Option<Duration>,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<BoxError>,
+    {
+        assert!(
+            self.checks.iter().all(|x| x.name != name),
+            "health check `{name}` was already registered"
+        );
+
+        let check: CheckFn = Arc::new(move |ctx| {
+            let fut = f(ctx);
+            Box::pin(async move { fut.await.map_err(Into::into) })
+        });
+
+        self.checks.push(HealthCheck {
+            name,
+            liveness,
+            timeout,
+            check,
+        });
+
+        self
+    }
+
+    /// Adds a check that runs in the readiness endpoint, like reaching a database or a cache.
+    pub fn check<F, Fut, E>(self, name: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<BoxError>,
+    {
+        self.add(name.into(), false, None, f)
+    }
+
+    /// Adds a check with its own timeout that runs in the readiness endpoint.
+    pub fn check_with_timeout<F, Fut, E>(
+        self,
+        name: impl Into<String>,
+        timeout: Duration,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<BoxError>,
+    {
+        self.add(name.into(), false, Some(timeout), f)
+    }
+
+    /// Adds a check that runs in the liveness and the readiness endpoints,
+    /// a failing liveness check usually restarts the server.
+    pub fn liveness_check<F, Fut, E>(self, name: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<BoxError>,
+    {
+        self.add(name.into(), true, None, f)
+    }
+
+    /// Sets the path of the liveness endpoint, defaults to `/healthz`.
+    pub fn liveness_endpoint(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        crate::routing::assert_valid_route(&path).expect("invalid liveness path");
+        self.liveness_path = path;
+        self
+    }
+
+    /// Sets the path of the readiness endpoint, defaults to `/readyz`.
+    pub fn readiness_endpoint(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        crate::routing::assert_valid_route(&path).expect("invalid readiness path");
+        self.readiness_path = path;
+        self
+    }
+
+    /// Sets the max time a check can take, defaults to 5 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether the requests to the endpoints are traced and recorded in the metrics,
+    /// disabled by default.
+    pub fn record_requests(mut self, record: bool) -> Self {
+        self.record_requests = record;
+        self
+    }
+
+    /// Returns the path of the liveness endpoint.
+    pub fn liveness_path(&self) -> &str {
+        &self.liveness_path
+    }
+
+    /// Returns the path of the readiness endpoint.
+    pub fn readiness_path(&self) -> &str {
+        &self.readiness_path
+    }
+
+    /// Runs the liveness checks.
+    pub async fn check_liveness(&self, ctx: RequestContext) -> HealthReport {
+        self.run(ctx, true).await
+    }
+
+    /// Runs all the checks.
+    pub async fn check_readiness(&self, ctx: RequestContext) -> HealthReport {
+        self.run(ctx, false).await
+    }
+
+    async fn run(&self, ctx: RequestContext, liveness: bool) -> HealthReport {
+        let checks = self
+            .checks
+            .iter()
+            .filter(|x| !liveness || x.liveness)
+            .map(|check| {
+                let ctx = ctx.clone();
+                let timeout = check.timeout.unwrap_or(self.timeout);
+
+                async move {
+                    let start = std::time::Instant::now();
+                    let ret = crate::app::with_timeout((check.check)(ctx), Some(timeout)).await;
+                    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+                    let error = match ret {
+                        Some(Ok(_)) => None,
+                        Some(Err(err)) => Some(err.to_string()),
+                        None => Some(format!("timed out after {timeout:?}")),
+                    };
+
+                    if let Some(err) = &error {
+                        log::warn!("health check `{}` failed: {err}", check.name);
+                    }
+
+                    CheckReport {
+                        name: check.name.clone(),
+                        status: match error {
+                            Some(_) => HealthStatus::Error,
+                            None => HealthStatus::Ok,
+                        },
+                        latency_ms,
+                        error,
+                    }
+                }
+            });
+
+        let checks = futures::future::join_all(checks).await;
+        let status = match checks.iter().all(|x| x.status == HealthStatus::Ok) {
+            true => HealthStatus::Ok,
+            false => HealthStatus::Error,
+        };
+
+        HealthReport { status, checks }
+    }
+
+    /// Returns `true` if the request to the given path should not be traced or recorded.
+    pub(crate) fn is_unrecorded(&self, path: &str) -> bool {
+        !self.record_requests && self.is_endpoint(path)
+    }
+
+    /// Returns `true` if the path is the liveness or readiness endpoint.
+    pub(crate) fn is_endpoint(&self, path: &str) -> bool {
+        let path = match path.len() > 1 {
+            true => path.trim_end_matches('/'),
+            false => path,
+        };
+
+        path == self.liveness_path || path == self.readiness_path
+    }
+
+    /// Returns the routes of the liveness and readiness endpoints.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn routes(&self) -> [crate::routing::Route; 2] {
+        use crate::routing::Route;
+
+        let liveness = Route::get(&self.liveness_path, {
+            let checks = self.clone();
+            move |ctx: RequestContext| {
+                let checks = checks.clone();
+                async move { checks.check_liveness(ctx).await }
+            }
+        });
+
+        let readiness = Route::get(&self.readiness_path, {
+            let checks = self.clone();
+            move |ctx: RequestContext| {
+                let checks = checks.clone();
+                async move { checks.check_readiness(ctx).await }
+            }
+        });
+
+        [liveness, readiness]
+    }
+}
+
+impl Default for HealthChecks {
+    fn default() -> Self {
+        HealthChecks::new()
+    }
+}
+
+impl std::fmt::Debug for HealthChecks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.checks.iter().map(|x| &x.name).collect::<Vec<_>>();
+        f.debug_struct("HealthChecks")
+            .field("checks", &names)
+            .field("liveness_path", &self.liveness_path)
+            .field("readiness_path", &self.readiness_path)
+            .field("timeout", &self.timeout)
+            .field("record_requests", &self.record_requests)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::{HealthChecks, HealthReport, HealthStatus};
+    use crate::{app::App, metrics::Metrics, routing::Route, web::Request};
+    use http::StatusCode;
+    use std::time::Duration;
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    async fn get_report(
+        service: &crate::app::AppService,
+        path: &str,
+    ) -> (StatusCode, HealthReport) {
+        let res = service.handle(create_req(path)).await;
+        let status = res.status();
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn health_checks_test() {
+        let metrics = Metrics::new();
+        let checks = HealthChecks::new()
+            .timeout(Duration::from_millis(50))
+            .liveness_check("server", |_| async move { Ok::<_, std::io::Error>(()) })
+            .check("database", |_| async move { Ok::<_, std::io::Error>(()) })
+            .check("cache", |_| async move { Err("connection refused") })
+            .check("queue", |_| async move {
+                futures_timer::Delay::new(Duration::from_secs(5)).await;
+                Ok::<_, std::io::Error>(())
+            });
+
+        let service = App::<Base>::new()
+            .metrics(metrics.clone())
+            .health_checks(checks)
+            .route(Route::get("/hello", || async move { "hello" }))
+            .build();
+
+        let (status, report) = get_report(&service, "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.checks[0].name, "server");
+
+        let (status, report) = get_report(&service, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.status, HealthStatus::Error);
+
+        let statuses = report
+            .checks
+            .iter()
+            .map(|x| (x.name.as_str(), x.status, x.error.as_deref()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("server", HealthStatus::Ok, None),
+                ("database", HealthStatus::Ok, None),
+                ("cache", HealthStatus::Error, Some("connection refused")),
+                ("queue", HealthStatus::Error, Some("timed out after 50ms")),
+            ]
+        );
+
+        // Only the other requests are recorded
+        service.handle(create_req("/hello")).await;
+        let output = metrics.render();
+        assert!(output.contains("/hello"), "metrics: {output}");
+        assert!(!output.contains("/healthz"), "metrics: {output}");
+        assert!(!output.contains("/readyz"), "metrics: {output}");
+    }
+}
@@ -2,6 +2,14 @@
 mod use_query_params;
 pub use use_query_params::*;
 
+//
+mod use_event_source;
+pub use use_event_source::*;
+
+//
+mod use_websocket;
+pub use use_websocket::*;
+
 //
 mod common;
 pub use common::*;
@@ -0,0 +1,423 @@
+use crate::error::BoxError;
+use serde::de::DeserializeOwned;
+use std::{fmt::Debug, rc::Rc, time::Duration};
+use yew::{hook, use_state, UseStateHandle};
+
+/// The state of the connection of an event source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSourceState {
+    /// The connection is being opened or was lost and is reconnecting.
+    Connecting,
+
+    /// The connection is open and receiving events.
+    Open,
+
+    /// The connection was closed.
+    Closed,
+}
+
+/// Options for [`use_event_source_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSourceOptions {
+    events: Vec<String>,
+    reconnect_delay: Duration,
+    with_credentials: bool,
+}
+
+impl EventSourceOptions {
+    /// Constructs the default options, which listen to `message` events.
+    pub fn new() -> Self {
+        EventSourceOptions {
+            events: vec![String::from("message")],
+            reconnect_delay: Duration::from_secs(3),
+            with_credentials: false,
+        }
+    }
+
+    /// Sets the names of the events to listen.
+    pub fn events<I, S>(mut self, events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.events = events.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the time to wait before reconnecting when the connection is closed by the server.
+    pub fn reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Whether if send the cookies on cross-origin requests.
+    pub fn with_credentials(mut self, with_credentials: bool) -> Self {
+        self.with_credentials = with_credentials;
+        self
+    }
+}
+
+impl Default for EventSourceOptions {
+    fn default() -> Self {
+        EventSourceOptions::new()
+    }
+}
+
+struct EventSourceInner<T> {
+    data: Option<Rc<T>>,
+    event: Option<String>,
+    last_event_id: Option<String>,
+    error: Option<Rc<BoxError>>,
+    state: EventSourceState,
+}
+
+impl<T> Clone for EventSourceInner<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            event: self.event.clone(),
+            last_event_id: self.last_event_id.clone(),
+            error: self.error.clone(),
+            state: self.state,
+        }
+    }
+}
+
+/// A handle to the events received by [`use_event_source`].
+pub struct UseEventSourceHandle<T> {
+    inner: UseStateHandle<EventSourceInner<T>>,
+}
+
+impl<T> UseEventSourceHandle<T> {
+    /// Returns the data of the last event received.
+    pub fn data(&self) -> Option<&T> {
+        self.inner.data.as_deref()
+    }
+
+    /// Returns the name of the last event received.
+    pub fn event(&self) -> Option<&str> {
+        self.inner.event.as_deref()
+    }
+
+    /// Returns the id of the last event received.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.inner.last_event_id.as_deref()
+    }
+
+    /// Returns the last error, either from the connection or when deserializing the data.
+    pub fn error(&self) -> Option<&BoxError> {
+        self.inner.error.as_deref()
+    }
+
+    /// Returns the state of the connection.
+    pub fn state(&self) -> EventSourceState {
+        self.inner.state
+    }
+}
+
+impl<T> Clone for UseEventSourceHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> PartialEq for UseEventSourceHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.state == other.inner.state
+            && self.inner.last_event_id == other.inner.last_event_id
+            && self.inner.error.is_none() == other.inner.error.is_none()
+            && match (&self.inner.data, &other.inner.data) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl<T: Debug> Debug for UseEventSourceHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UseEventSourceHandle")
+            .field("data", &self.inner.data)
+            .field("event", &self.inner.event)
+            .field("last_event_id", &self.inner.last_event_id)
+            .field("error", &self.inner.error)
+            .field("state", &self.inner.state)
+            .finish()
+    }
+}
+
+/// Connects to a stream of server-sent events and deserializes the json data of each `message` event.
+///
+/// When the connection is closed, reconnects sending the last event id in the `lastEventId` query param,
+/// which can be read in the server with the `LastEventId` extractor.
+#[hook]
+pub fn use_event_source<T>(url: &str) -> UseEventSourceHandle<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    use_event_source_with_options(url, EventSourceOptions::new())
+}
+
+/// Connects to a stream of server-sent events using the given options.
+#[hook]
+pub fn use_event_source_with_options<T>(
+    url: &str,
+    options: EventSourceOptions,
+) -> UseEventSourceHandle<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    let inner = use_state(|| EventSourceInner {
+        data: None,
+        event: None,
+        last_event_id: None,
+        error: None,
+        state: EventSourceState::Connecting,
+    });
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let inner = inner.clone();
+        yew::use_effect_with_deps(
+            move |(url, options)| {
+                let conn =
+                    wasm::EventSourceConnection::<T>::open(url.clone(), options.clone(), inner);
+                move || conn.close()
+            },
+            (url.to_owned(), options),
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (url, options);
+
+    UseEventSourceHandle { inner }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod wasm {
+    use super::{EventSourceInner, EventSourceOptions, EventSourceState};
+    use crate::{error::JsError, web::LAST_EVENT_ID_QUERY};
+    use serde::de::DeserializeOwned;
+    use std::{cell::RefCell, rc::Rc};
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::{Event, EventSource, EventSourceInit, MessageEvent};
+    use yew::UseStateHandle;
+
+    struct Connection<T> {
+        url: String,
+        options: EventSourceOptions,
+        handle: UseStateHandle<EventSourceInner<T>>,
+        current: EventSourceInner<T>,
+        source: Option<EventSource>,
+        listeners: Vec<(String, Closure<dyn FnMut(MessageEvent)>)>,
+        on_open: Option<Closure<dyn FnMut(Event)>>,
+        on_error: Option<Closure<dyn FnMut(Event)>>,
+        reconnect: Option<(i32, Closure<dyn FnMut()>)>,
+        closed: bool,
+    }
+
+    /// A connection to an event source which is closed when the component unmounts.
+    pub(super) struct EventSourceConnection<T>(Rc<RefCell<Connection<T>>>);
+
+    impl<T: DeserializeOwned + 'static> EventSourceConnection<T> {
+        pub(super) fn open(
+            url: String,
+            options: EventSourceOptions,
+            handle: UseStateHandle<EventSourceInner<T>>,
+        ) -> Self {
+            let current = (*handle).clone();
+            let conn = Rc::new(RefCell::new(Connection {
+                url,
+                options,
+                handle,
+                current,
+                source: None,
+                listeners: vec![],
+                on_open: None,
+                on_error: None,
+                reconnect: None,
+                closed: false,
+            }));
+
+            connect(&conn);
+            EventSourceConnection(conn)
+        }
+
+        pub(super) fn close(self) {
+            let mut conn = self.0.borrow_mut();
+            conn.closed = true;
+            conn.disconnect();
+            conn.listeners.clear();
+            conn.on_open = None;
+            conn.on_error = None;
+
+            if let Some((id, _)) = conn.reconnect.take() {
+                if let Some(window) = web_sys::window() {
+                    window.clear_timeout_with_handle(id);
+                }
+            }
+        }
+    }
+
+    impl<T> Connection<T> {
+        fn update(&mut self, f: impl FnOnce(&mut EventSourceInner<T>)) {
+            f(&mut self.current);
+            self.handle.set(self.current.clone());
+        }
+
+        // Closes the current event source, the closures are kept alive because
+        // this can be called from within the `onerror` callback.
+        fn disconnect(&mut self) {
+            if let Some(source) = self.source.take() {
+                for (name, listener) in self.listeners.iter() {
+                    let _ = source.remove_event_listener_with_callback(
+                        name,
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+
+                source.set_onopen(None);
+                source.set_onerror(None);
+                source.close();
+            }
+        }
+
+        fn url(&self) -> String {
+            let Some(last_event_id) = &self.current.last_event_id else {
+                return self.url.clone();
+            };
+
+            let separator = if self.url.contains('?') { '&' } else { '?' };
+            let query = serde_urlencoded::to_string([(LAST_EVENT_ID_QUERY, last_event_id)])
+                .unwrap_or_default();
+            format!("{}{separator}{query}", self.url)
+        }
+    }
+
+    fn connect<T: DeserializeOwned + 'static>(conn: &Rc<RefCell<Connection<T>>>) {
+        let mut this = conn.borrow_mut();
+        if this.closed {
+            return;
+        }
+
+        let mut init = EventSourceInit::new();
+        init.with_credentials(this.options.with_credentials);
+
+        let source = match EventSource::new_with_event_source_init_dict(&this.url(), &init) {
+            Ok(source) => source,
+            Err(err) => {
+                this.update(|x| {
+                    x.error = Some(Rc::new(JsError::new(err).into()));
+                    x.state = EventSourceState::Closed;
+                });
+                return;
+            }
+        };
+
+        this.listeners.clear();
+        for name in this.options.events.clone() {
+            let weak = Rc::downgrade(conn);
+            let listener = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let Some(conn) = weak.upgrade() else {
+                    return;
+                };
+                let mut conn = conn.borrow_mut();
+                let data = event.data().as_string().unwrap_or_default();
+                let id = event.last_event_id();
+
+                conn.update(|x| {
+                    if !id.is_empty() {
+                        x.last_event_id = Some(id);
+                    }
+
+                    match serde_json::from_str::<T>(&data) {
+                        Ok(data) => {
+                            x.data = Some(Rc::new(data));
+                            x.event = Some(event.type_());
+                            x.error = None;
+                        }
+                        Err(err) => {
+                            x.error = Some(Rc::new(err.into()));
+                        }
+                    }
+                });
+            });
+
+            let _ =
+                source.add_event_listener_with_callback(&name, listener.as_ref().unchecked_ref());
+            this.listeners.push((name, listener));
+        }
+
+        let weak = Rc::downgrade(conn);
+        let on_open = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            if let Some(conn) = weak.upgrade() {
+                conn.borrow_mut()
+                    .update(|x| x.state = EventSourceState::Open);
+            }
+        });
+
+        let weak = Rc::downgrade(conn);
+        let on_error = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            let Some(conn) = weak.upgrade() else {
+                return;
+            };
+
+            let ready_state = match &conn.borrow().source {
+                Some(source) => source.ready_state(),
+                None => return,
+            };
+
+            // The browser reconnects by itself sending the `Last-Event-ID`,
+            // we only reconnect if the connection was closed.
+            if ready_state != EventSource::CLOSED {
+                conn.borrow_mut()
+                    .update(|x| x.state = EventSourceState::Connecting);
+                return;
+            }
+
+            {
+                let mut this = conn.borrow_mut();
+                this.disconnect();
+                this.update(|x| {
+                    x.error = Some(Rc::new("event source connection was closed".into()));
+                    x.state = EventSourceState::Connecting;
+                });
+            }
+
+            schedule_reconnect(&conn);
+        });
+
+        source.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        source.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        this.on_open = Some(on_open);
+        this.on_error = Some(on_error);
+        this.source = Some(source);
+    }
+
+    fn schedule_reconnect<T: DeserializeOwned + 'static>(conn: &Rc<RefCell<Connection<T>>>) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+
+        let weak = Rc::downgrade(conn);
+        let callback = Closure::<dyn FnMut()>::new(move || {
+            if let Some(conn) = weak.upgrade() {
+                connect(&conn);
+            }
+        });
+
+        let mut this = conn.borrow_mut();
+        let timeout = this.options.reconnect_delay.as_millis() as i32;
+        match window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.as_ref().unchecked_ref(),
+            timeout,
+        ) {
+            Ok(id) => this.reconnect = Some((id, callback)),
+            Err(err) => log::error!("failed to reconnect event source: {:?}", JsError::new(err)),
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{
+    error::BoxError,
+    web::{Bytes, CloseFrame, Message},
+};
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+use yew::{hook, use_mut_ref, use_state, Callback, UseStateHandle};
+
+/// The state of a websocket connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebSocketState {
+    /// The connection is being opened.
+    Connecting,
+
+    /// The connection is open and can send and receive messages.
+    Open,
+
+    /// The connection is closing.
+    Closing,
+
+    /// The connection was closed or failed to open.
+    Closed,
+}
+
+/// Options for [`use_websocket_with_options`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WebSocketOptions {
+    protocols: Vec<String>,
+    on_message: Option<Callback<Message>>,
+}
+
+impl WebSocketOptions {
+    /// Constructs the default options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the subprotocols to request to the server.
+    pub fn protocols<I, S>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.protocols = protocols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets a callback called with each message received.
+    pub fn on_message<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Message) + 'static,
+    {
+        self.on_message = Some(Callback::from(f));
+        self
+    }
+}
+
+/// A handle to a websocket connection returned by [`use_websocket`].
+pub struct UseWebSocketHandle {
+    socket: Rc<RefCell<Option<web_sys::WebSocket>>>,
+    message: UseStateHandle<Option<Rc<Message>>>,
+    state: UseStateHandle<WebSocketState>,
+}
+
+impl UseWebSocketHandle {
+    /// Returns the last message received.
+    pub fn message(&self) -> Option<&Message> {
+        self.message.as_deref()
+    }
+
+    /// Returns the state of the connection.
+    pub fn state(&self) -> WebSocketState {
+        *self.state
+    }
+
+    /// Sends a message to the server, `Ping` and `Pong` messages are not supported in the browser
+    /// and are ignored.
+    pub fn send(&self, msg: impl Into<Message>) -> Result<(), BoxError> {
+        let socket = self.socket.borrow();
+        let Some(socket) = socket.as_ref() else {
+            return Err("websocket is not connected".into());
+        };
+
+        let msg = msg.into();
+        let is_close = msg.is_close();
+        send_message(socket, msg)?;
+
+        if is_close {
+            self.state.set(WebSocketState::Closing);
+        }
+
+        Ok(())
+    }
+
+    /// Sends a text message.
+    pub fn send_text(&self, text: impl Into<String>) -> Result<(), BoxError> {
+        self.send(Message::Text(text.into()))
+    }
+
+    /// Sends a binary message.
+    pub fn send_binary(&self, bytes: impl Into<Bytes>) -> Result<(), BoxError> {
+        self.send(Message::Binary(bytes.into()))
+    }
+
+    /// Closes the connection.
+    pub fn close(&self) -> Result<(), BoxError> {
+        self.send(Message::Close(None))
+    }
+
+    /// Closes the connection with the given code and reason.
+    pub fn close_with(&self, code: u16, reason: impl Into<String>) -> Result<(), BoxError> {
+        self.send(Message::Close(Some(CloseFrame {
+            code,
+            reason: reason.into(),
+        })))
+    }
+}
+
+impl Clone for UseWebSocketHandle {
+    fn clone(&self) -> Self {
+        Self {
+            socket: self.socket.clone(),
+            message: self.message.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl PartialEq for UseWebSocketHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state
+            && Rc::ptr_eq(&self.socket, &other.socket)
+            && match (&*self.message, &*other.message) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl Debug for UseWebSocketHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UseWebSocketHandle")
+            .field("message", &self.message)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn send_message(socket: &web_sys::WebSocket, msg: Message) -> Result<(), BoxError> {
+    use crate::error::JsError;
+
+    match msg {
+        Message::Text(text) => socket.send_with_str(&text),
+        Message::Binary(bytes) => socket.send_with_u8_array(&bytes),
+        Message::Close(None) => socket.close(),
+        Message::Close(Some(frame)) => socket.close_with_code_and_reason(frame.code, &frame.reason),
+        Message::Ping(_) | Message::Pong(_) => Ok(()),
+    }
+    .map_err(|err| JsError::new(err).into())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(unused_variables)]
+fn send_message(socket: &web_sys::WebSocket, msg: Message) -> Result<(), BoxError> {
+    unreachable!("client only function")
+}
+
+/// Opens a websocket connection to the given url, which can be relative to the current page.
+#[hook]
+pub fn use_websocket(url: &str) -> UseWebSocketHandle {
+    use_websocket_with_options(url, WebSocketOptions::new())
+}
+
+/// Opens a websocket connection to the given url using the given options.
+#[hook]
+pub fn use_websocket_with_options(url: &str, options: WebSocketOptions) -> UseWebSocketHandle {
+    let socket = use_mut_ref(|| None);
+    let message = use_state(|| None);
+    let state = use_state(|| WebSocketState::Connecting);
+
+    // Keep the latest callback without reconnecting each render
+    let on_message = use_mut_ref(|| None);
+    *on_message.borrow_mut() = options.on_message.clone();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let socket = socket.clone();
+        let message = message.clone();
+        let state = state.clone();
+
+        yew::use_effect_with_deps(
+            move |(url, protocols)| {
+                let conn = wasm::connect(url, protocols, socket, message, state, on_message);
+                move || drop(conn)
+            },
+            (url.to_owned(), options.protocols),
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (url, on_message);
+
+    UseWebSocketHandle {
+        socket,
+        message,
+        state,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod wasm {
+    use super::WebSocketState;
+    use crate::{
+        error::JsError,
+        web::{CloseFrame, Message},
+    };
+    use std::{cell::RefCell, rc::Rc};
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::{BinaryType, CloseEvent, Event, MessageEvent, WebSocket};
+    use yew::{Callback, UseStateHandle};
+
+    /// Closes the connection when dropped.
+    pub(super) struct Connection {
+        socket: Rc<RefCell<Option<WebSocket>>>,
+        _on_open: Closure<dyn FnMut(Event)>,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+        _on_close: Closure<dyn FnMut(CloseEvent)>,
+        _on_error: Closure<dyn FnMut(Event)>,
+    }
+
+    impl Drop for Connection {
+        fn drop(&mut self) {
+            if let Some(socket) = self.socket.borrow_mut().take() {
+                socket.set_onopen(None);
+                socket.set_onmessage(None);
+                socket.set_onclose(None);
+                socket.set_onerror(None);
+                let _ = socket.close();
+            }
+        }
+    }
+
+    // Resolves an url relative to the current page using the `ws` or `wss` scheme
+    fn resolve_url(url: &str) -> String {
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            return url.to_owned();
+        }
+
+        let location = web_sys::window().unwrap().location();
+        let base = location.href().unwrap_or_default();
+        let resolved = web_sys::Url::new_with_base(url, &base)
+            .map(|x| x.href())
+            .unwrap_or_else(|_| url.to_owned());
+
+        if let Some(rest) = resolved.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = resolved.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            resolved
+        }
+    }
+
+    pub(super) fn connect(
+        url: &str,
+        protocols: &[String],
+        socket_ref: Rc<RefCell<Option<WebSocket>>>,
+        message: UseStateHandle<Option<Rc<Message>>>,
+        state: UseStateHandle<WebSocketState>,
+        on_message: Rc<RefCell<Option<Callback<Message>>>>,
+    ) -> Option<Connection> {
+        let url = resolve_url(url);
+        let socket = if protocols.is_empty() {
+            WebSocket::new(&url)
+        } else {
+            let array = protocols
+                .iter()
+                .map(|x| wasm_bindgen::JsValue::from_str(x))
+                .collect::<js_sys::Array>();
+            WebSocket::new_with_str_sequence(&url, &array)
+        };
+
+        let socket = match socket {
+            Ok(socket) => socket,
+            Err(err) => {
+                log::error!("failed to open websocket: {}", JsError::new(err));
+                state.set(WebSocketState::Closed);
+                return None;
+            }
+        };
+
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        state.set(WebSocketState::Connecting);
+
+        let _on_open = {
+            let state = state.clone();
+            Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+                state.set(WebSocketState::Open);
+            })
+        };
+
+        let _on_message = {
+            let message = message.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let data = event.data();
+                let msg = match data.as_string() {
+                    Some(text) => Message::Text(text),
+                    None => match data.dyn_into::<js_sys::ArrayBuffer>() {
+                        Ok(buf) => Message::Binary(js_sys::Uint8Array::new(&buf).to_vec().into()),
+                        Err(_) => return,
+                    },
+                };
+
+                if let Some(on_message) = on_message.borrow().as_ref() {
+                    on_message.emit(msg.clone());
+                }
+
+                message.set(Some(Rc::new(msg)));
+            })
+        };
+
+        let _on_close = {
+            let state = state.clone();
+            Closure::<dyn FnMut(CloseEvent)>::new(move |event: CloseEvent| {
+                message.set(Some(Rc::new(Message::Close(Some(CloseFrame {
+                    code: event.code(),
+                    reason: event.reason(),
+                })))));
+                state.set(WebSocketState::Closed);
+            })
+        };
+
+        let _on_error = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            log::error!("websocket connection error");
+        });
+
+        socket.set_onopen(Some(_on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(_on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(_on_close.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(_on_error.as_ref().unchecked_ref()));
+
+        *socket_ref.borrow_mut() = Some(socket);
+
+        Some(Connection {
+            socket: socket_ref,
+            _on_open,
+            _on_message,
+            _on_close,
+            _on_error,
+        })
+    }
+}
@@ -0,0 +1,419 @@
+#![cfg_attr(feature = "client", allow(dead_code))]
+
+use crate::{
+    app::RequestContext,
+    context::PageDataHandle,
+    server::{LinkTag, PageLinks},
+    web::cookie::Cookie,
+};
+use http::header;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use yew::{hook, use_context};
+
+/// Name of the cookie that holds the locale selected by the user.
+pub const LOCALE_COOKIE: &str = "__hashira_locale";
+
+/// Name of the route param used as locale prefix, for example `/:lang/about`.
+pub const LOCALE_PARAM: &str = "lang";
+
+/// The translations of a locale.
+pub type Catalog = BTreeMap<String, String>;
+
+/// Configuration for the internationalization of the app.
+///
+/// The locale of a request is selected in order from:
+/// - The `lang` param of the route, nest a scope with `App::nest("/:lang", scope)` to use it.
+/// - The [`LOCALE_COOKIE`] cookie, see [`set_locale`].
+/// - The `Accept-Language` header.
+/// - The default locale.
+///
+/// The selected locale and its catalog are sent in the page data,
+/// so [`use_translation`] works the same during the server render and on the client.
+#[derive(Debug, Clone)]
+pub struct I18n {
+    default_locale: String,
+    catalogs: BTreeMap<String, Catalog>,
+}
+
+impl I18n {
+    /// Constructs a new configuration with the given default locale.
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        let default_locale = default_locale.into();
+        let mut catalogs = BTreeMap::new();
+        catalogs.insert(default_locale.clone(), Catalog::new());
+
+        I18n {
+            default_locale,
+            catalogs,
+        }
+    }
+
+    /// Adds translations for the given locale.
+    pub fn catalog<I, K, V>(mut self, locale: impl Into<String>, messages: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let catalog = self.catalogs.entry(locale.into()).or_default();
+        for (key, value) in messages {
+            catalog.insert(key.into(), value.into());
+        }
+
+        self
+    }
+
+    /// Adds the translations of the given json for the locale,
+    /// nested objects are flattened using dots: `{ "todo": { "saved": "..." } }` as `todo.saved`.
+    pub fn catalog_json(
+        self,
+        locale: impl Into<String>,
+        json: &str,
+    ) -> Result<Self, serde_json::Error> {
+        let value = serde_json::from_str::<serde_json::Value>(json)?;
+        let mut messages = Catalog::new();
+        flatten_json(&mut messages, String::new(), value);
+        Ok(self.catalog(locale, messages))
+    }
+
+    /// Loads the catalogs from `{locale}.json` files in the given directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_dir(
+        mut self,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<Self, crate::error::BoxError> {
+        for entry in std::fs::read_dir(dir.as_ref())? {
+            let path = entry?.path();
+            if path.extension().and_then(|x| x.to_str()) != Some("json") {
+                continue;
+            }
+
+            let Some(locale) = path.file_stem().and_then(|x| x.to_str()) else {
+                continue;
+            };
+
+            let json = std::fs::read_to_string(&path)?;
+            self = self.catalog_json(locale.to_owned(), &json)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Loads the catalogs from a directory in the `public` folder next to the executable,
+    /// for example `I18n::new("en").load_public_dir("locales")`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_public_dir(self, dir: &str) -> Result<Self, crate::error::BoxError> {
+        let mut public_dir = std::env::current_exe()?;
+        public_dir.pop();
+        self.load_dir(public_dir.join("public").join(dir))
+    }
+
+    /// Returns the default locale.
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Returns all the supported locales.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.catalogs.keys().map(|x| x.as_str())
+    }
+
+    /// Returns the translation of the key for the given locale.
+    pub fn translate(&self, locale: &str, key: &str) -> Option<&str> {
+        self.catalogs
+            .get(locale)
+            .and_then(|catalog| catalog.get(key))
+            .map(|x| x.as_str())
+    }
+
+    /// Returns the supported locale that matches the given language tag,
+    /// `es-MX` matches `es-MX` or `es`.
+    pub fn find_locale(&self, tag: &str) -> Option<&str> {
+        let tag = tag.trim();
+        let exact = self.locales().find(|x| x.eq_ignore_ascii_case(tag));
+
+        exact.or_else(|| {
+            let primary = tag.split('-').next()?;
+            self.locales().find(|x| x.eq_ignore_ascii_case(primary))
+        })
+    }
+
+    /// Returns the locale for the given request.
+    pub fn negotiate(&self, ctx: &RequestContext) -> String {
+        let from_param = ctx
+            .params()
+            .get(LOCALE_PARAM)
+            .and_then(|lang| self.find_locale(lang));
+
+        let from_cookie = || {
+            let cookie = ctx.cookies().get(LOCALE_COOKIE)?;
+            self.find_locale(cookie.value()).map(|x| x.to_owned())
+        };
+
+        let from_header = || {
+            let accept_language = ctx
+                .request()
+                .headers()
+                .get(header::ACCEPT_LANGUAGE)?
+                .to_str()
+                .ok()?;
+
+            parse_accept_language(accept_language)
+                .into_iter()
+                .find_map(|tag| self.find_locale(tag))
+                .map(|x| x.to_owned())
+        };
+
+        from_param
+            .map(|x| x.to_owned())
+            .or_else(from_cookie)
+            .or_else(from_header)
+            .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    /// Returns the `hreflang` alternate links for a request using a locale prefix.
+    pub(crate) fn alternate_links(&self, ctx: &RequestContext) -> PageLinks {
+        let mut links = PageLinks::new();
+        let Some(current) = ctx.params().get(LOCALE_PARAM) else {
+            return links;
+        };
+
+        // Replaces the first segment with the current locale
+        let path = ctx.path();
+        let localized = |locale: &str| {
+            let mut replaced = false;
+            path.split('/')
+                .map(|segment| {
+                    if !replaced && segment == current {
+                        replaced = true;
+                        locale
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+
+        for locale in self.locales() {
+            links = links.insert(
+                LinkTag::new()
+                    .attr("rel", "alternate")
+                    .attr("hreflang", locale)
+                    .attr("href", localized(locale)),
+            );
+        }
+
+        links.insert(
+            LinkTag::new()
+                .attr("rel", "alternate")
+                .attr("hreflang", "x-default")
+                .attr("href", localized(&self.default_locale)),
+        )
+    }
+}
+
+fn flatten_json(messages: &mut Catalog, prefix: String, value: serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+
+                flatten_json(messages, key, value);
+            }
+        }
+        serde_json::Value::String(s) => {
+            messages.insert(prefix, s);
+        }
+        other => {
+            messages.insert(prefix, other.to_string());
+        }
+    }
+}
+
+/// Returns the language tags of the `Accept-Language` header ordered by quality.
+fn parse_accept_language(value: &str) -> Vec<&str> {
+    let mut tags = value
+        .split(',')
+        .filter_map(|part| {
+            let mut iter = part.split(';');
+            let tag = iter.next()?.trim();
+            let quality = iter
+                .find_map(|x| x.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if tag.is_empty() || tag == "*" {
+                return None;
+            }
+
+            Some((tag, quality))
+        })
+        .collect::<Vec<_>>();
+
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Stores the locale selected by the user in a cookie.
+pub fn set_locale(ctx: &RequestContext, locale: impl Into<String>) {
+    let cookie = Cookie::build(LOCALE_COOKIE, locale.into())
+        .path("/")
+        .same_site(cookie::SameSite::Lax)
+        .finish();
+
+    ctx.cookies().add(cookie);
+}
+
+/// The locale of a page and its translations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocaleData {
+    /// The selected locale.
+    pub locale: String,
+
+    /// The translations of the locale.
+    pub messages: Catalog,
+}
+
+/// Returns the locale data for the page, if the i18n is enabled.
+pub(crate) fn get_locale_data(ctx: &RequestContext) -> Option<LocaleData> {
+    let i18n = ctx.app_data::<I18n>()?;
+    let locale = i18n.negotiate(ctx);
+    let messages = i18n.catalogs.get(&locale).cloned().unwrap_or_default();
+    Some(LocaleData { locale, messages })
+}
+
+/// Sets the `lang` attribute of the `<html>` element.
+pub(crate) fn set_html_lang(html: &mut String, locale: &str) {
+    let Some(start) = html.find("<html") else {
+        return;
+    };
+
+    let Some(len) = html[start..].find('>') else {
+        return;
+    };
+
+    let end = start + len;
+    let tag = &html[start..end];
+    let lang_attr = format!("lang=\"{locale}\"");
+
+    let new_tag = match tag.find("lang=\"") {
+        Some(lang_start) => {
+            let value_start = lang_start + "lang=\"".len();
+            match tag[value_start..].find('"') {
+                Some(value_len) => format!(
+                    "{}{lang_attr}{}",
+                    &tag[..lang_start],
+                    &tag[value_start + value_len + 1..]
+                ),
+                None => return,
+            }
+        }
+        None => format!("<html {lang_attr}{}", &tag["<html".len()..]),
+    };
+
+    html.replace_range(start..end, &new_tag);
+}
+
+/// Returns the locale of the current page, if the i18n is enabled.
+#[hook]
+pub fn use_locale() -> Option<String> {
+    use_context::<PageDataHandle>().and_then(|page_data| {
+        page_data
+            .i18n
+            .as_ref()
+            .map(|locale_data| locale_data.locale.clone())
+    })
+}
+
+/// Returns the translations of the current page.
+#[hook]
+pub fn use_translation() -> Translation {
+    Translation(use_context::<PageDataHandle>())
+}
+
+/// Translations of the current page.
+#[derive(Clone, PartialEq)]
+pub struct Translation(Option<PageDataHandle>);
+
+impl Translation {
+    /// Returns the current locale.
+    pub fn locale(&self) -> Option<&str> {
+        self.data().map(|x| x.locale.as_str())
+    }
+
+    /// Returns the translation for the key, or the key if not found.
+    pub fn t(&self, key: &str) -> String {
+        self.data()
+            .and_then(|x| x.messages.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_owned())
+    }
+
+    /// Returns the translation for the key replacing the `{name}` placeholders with the given values.
+    pub fn t_with(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut s = self.t(key);
+        for (name, value) in args {
+            s = s.replace(&format!("{{{name}}}"), value);
+        }
+        s
+    }
+
+    fn data(&self) -> Option<&LocaleData> {
+        self.0.as_ref().and_then(|x| x.i18n.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_accept_language, set_html_lang, I18n};
+
+    #[test]
+    fn accept_language_test() {
+        let tags = parse_accept_language("fr;q=0.5, es-MX, en;q=0.8, *;q=0.1");
+        assert_eq!(tags, vec!["es-MX", "en", "fr"]);
+    }
+
+    #[test]
+    fn find_locale_test() {
+        let i18n = I18n::new("en")
+            .catalog("es", [("hello", "Hola")])
+            .catalog("pt-BR", [("hello", "Olá")]);
+
+        assert_eq!(i18n.find_locale("es-MX"), Some("es"));
+        assert_eq!(i18n.find_locale("PT-br"), Some("pt-BR"));
+        assert_eq!(i18n.find_locale("de"), None);
+    }
+
+    #[test]
+    fn catalog_json_test() {
+        let i18n = I18n::new("en")
+            .catalog_json(
+                "en",
+                r#"{ "todo": { "saved": "Todo saved" }, "title": "Todos" }"#,
+            )
+            .unwrap();
+
+        assert_eq!(i18n.translate("en", "todo.saved"), Some("Todo saved"));
+        assert_eq!(i18n.translate("en", "title"), Some("Todos"));
+    }
+
+    #[test]
+    fn html_lang_test() {
+        let mut html = String::from("<!DOCTYPE html><html lang=\"en\"><head></head></html>");
+        set_html_lang(&mut html, "es");
+        assert_eq!(
+            html,
+            "<!DOCTYPE html><html lang=\"es\"><head></head></html>"
+        );
+
+        let mut html = String::from("<html><body></body></html>");
+        set_html_lang(&mut html, "fr");
+        assert_eq!(html, "<html lang=\"fr\"><body></body></html>");
+    }
+}
@@ -31,9 +31,40 @@ pub mod actions;
 /// Helpers for responses.
 pub mod responses;
 
+/// Cross-site request forgery protection.
+pub mod csrf;
+
+/// Cross-origin resource sharing.
+pub mod cors;
+
+/// Rate limiting of the requests.
+pub mod rate_limit;
+
+/// Authentication and authorization.
+pub mod auth;
+
+/// Messages displayed in the next page.
+pub mod flash;
+
+/// Internationalization.
+pub mod i18n;
+
+/// Metrics of the server.
+pub mod metrics;
+
+/// Health and readiness checks.
+pub mod health;
+
+/// Maintenance mode.
+pub mod maintenance;
+
 // Allow public?
 pub(crate) mod context;
 
+// Request tracing spans.
+#[cfg(feature = "tracing")]
+pub(crate) mod trace;
+
 /// Client related.
 #[cfg(feature = "client")]
 pub mod client;
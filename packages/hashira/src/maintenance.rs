@@ -0,0 +1,400 @@
+use crate::web::{RemoteAddr, Request, RequestExt};
+use std::{
+    net::IpAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Name of the default environment variable to enable the maintenance mode.
+pub const HASHIRA_MAINTENANCE: &str = "HASHIRA_MAINTENANCE";
+
+/// The default time clients should wait before retrying.
+pub const DEFAULT_MAINTENANCE_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// The default interval to check the environment variable and the flag file.
+pub const DEFAULT_MAINTENANCE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration of the maintenance mode.
+///
+/// While enabled all the requests are answered with a `503 Service Unavailable` and a `Retry-After` header,
+/// browsers receive the page registered with [`App::maintenance_page`] and other clients a problem details json.
+///
+/// The maintenance mode is enabled when any of these is true:
+/// - The environment variable, `HASHIRA_MAINTENANCE` by default, is set to `1` or `true`.
+/// - The flag file exists, if any.
+/// - Was enabled with [`AppService::set_maintenance`].
+///
+/// The environment variable and the flag file are checked at most once per second,
+/// see [`Maintenance::check_interval`].
+///
+/// The health checks endpoints keep responding during the maintenance.
+///
+/// # Example
+/// ```rust,no_run
+/// use hashira::maintenance::Maintenance;
+/// use std::net::Ipv4Addr;
+///
+/// let maintenance = Maintenance::new()
+///     .file("/var/run/app/maintenance")
+///     .allow_ip(Ipv4Addr::LOCALHOST)
+///     .bypass_cookie("maintenance_bypass", "secret");
+/// ```
+///
+/// [`App::maintenance_page`]: crate::app::App::maintenance_page
+/// [`AppService::set_maintenance`]: crate::app::AppService::set_maintenance
+#[derive(Debug, Clone)]
+pub struct Maintenance {
+    enabled: Arc<AtomicBool>,
+    env: Option<String>,
+    file: Option<PathBuf>,
+    check_interval: Duration,
+    last_check: Arc<Mutex<Option<LastCheck>>>,
+    retry_after: Duration,
+    allowed_ips: Vec<IpAddr>,
+    bypass_cookie: Option<(String, String)>,
+}
+
+impl Maintenance {
+    /// Constructs a maintenance mode that is enabled with the `HASHIRA_MAINTENANCE` environment variable.
+    pub fn new() -> Self {
+        Maintenance {
+            enabled: Arc::new(AtomicBool::new(false)),
+            env: Some(HASHIRA_MAINTENANCE.to_owned()),
+            file: None,
+            check_interval: DEFAULT_MAINTENANCE_CHECK_INTERVAL,
+            last_check: Arc::new(Mutex::new(None)),
+            retry_after: DEFAULT_MAINTENANCE_RETRY_AFTER,
+            allowed_ips: vec![],
+            bypass_cookie: None,
+        }
+    }
+
+    /// Sets the environment variable that enables the maintenance mode.
+    pub fn env(mut self, name: impl Into<String>) -> Self {
+        self.env = Some(name.into());
+        self
+    }
+
+    /// Enables the maintenance mode while the given file exists.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Sets the interval to check the environment variable and the flag file, defaults to 1 second.
+    pub fn check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Sets the time clients should wait before retrying, defaults to 5 minutes.
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Allows the requests from the given ip during the maintenance,
+    /// this requires the adapter to provide the `RemoteAddr` of the requests.
+    pub fn allow_ip(mut self, ip: impl Into<IpAddr>) -> Self {
+        self.allowed_ips.push(ip.into());
+        self
+    }
+
+    /// Allows the requests that have a cookie with the given name and value during the maintenance.
+    pub fn bypass_cookie(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.bypass_cookie = Some((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the time clients should wait before retrying.
+    pub fn get_retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    /// Returns `true` if the maintenance mode is enabled.
+    pub fn is_enabled(&self) -> bool {
+        if self.enabled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        if self.env.is_none() && self.file.is_none() {
+            return false;
+        }
+
+        // Reuse the last check until the interval elapses
+        let now = Instant::now();
+        let mut last_check = self.last_check.lock().unwrap();
+        match &*last_check {
+            Some(check) if now.duration_since(check.checked_at) < self.check_interval => {
+                check.enabled
+            }
+            _ => {
+                let enabled = self.check_env_or_file();
+                *last_check = Some(LastCheck {
+                    checked_at: now,
+                    enabled,
+                });
+                enabled
+            }
+        }
+    }
+
+    fn check_env_or_file(&self) -> bool {
+        if let Some(env) = &self.env {
+            if let Ok(value) = std::env::var(env) {
+                if value == "1" || value.eq_ignore_ascii_case("true") {
+                    return true;
+                }
+            }
+        }
+
+        match &self.file {
+            Some(file) => file.exists(),
+            None => false,
+        }
+    }
+
+    /// Enables or disables the maintenance mode at runtime.
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the request is allowed during the maintenance.
+    pub(crate) fn is_bypassed(&self, req: &Request<()>) -> bool {
+        if !self.allowed_ips.is_empty() {
+            if let Some(addr) = req.extensions().get::<RemoteAddr>() {
+                if self.allowed_ips.contains(&addr.ip()) {
+                    return true;
+                }
+            }
+        }
+
+        match &self.bypass_cookie {
+            Some((name, value)) => req
+                .cookie(name)
+                .map(|cookie| cookie.value() == value)
+                .unwrap_or_default(),
+            None => false,
+        }
+    }
+}
+
+/// The result of the last check of the environment variable and the flag file.
+#[derive(Debug)]
+struct LastCheck {
+    checked_at: Instant,
+    enabled: bool,
+}
+
+impl Default for Maintenance {
+    fn default() -> Self {
+        Maintenance::new()
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "client"))]
+mod tests {
+    use super::Maintenance;
+    use crate::{
+        app::App,
+        health::HealthChecks,
+        routing::{HandlerKind, Route},
+        web::{header, RemoteAddr, Request, Response},
+    };
+    use http::StatusCode;
+    use std::{
+        net::{Ipv4Addr, SocketAddr},
+        time::Duration,
+    };
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[yew::function_component]
+    fn MaintenancePage() -> yew::Html {
+        yew::html! {
+            "we will be back soon"
+        }
+    }
+
+    crate::impl_page_component!(MaintenancePage);
+
+    #[yew::function_component]
+    fn UnavailablePage() -> yew::Html {
+        yew::html! {
+            "service unavailable"
+        }
+    }
+
+    crate::impl_page_component!(UnavailablePage);
+
+    async fn get_text(res: Response) -> String {
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn maintenance_test() {
+        let maintenance = Maintenance::new()
+            .env("HASHIRA_MAINTENANCE_TEST")
+            .retry_after(Duration::from_secs(120))
+            .allow_ip(Ipv4Addr::new(10, 0, 0, 1))
+            .bypass_cookie("bypass", "secret");
+
+        let mut unavailable =
+            Route::get(
+                "/unavailable",
+                || async move { StatusCode::SERVICE_UNAVAILABLE },
+            );
+        unavailable.extensions_mut().insert(HandlerKind::Page);
+
+        let service = App::<Base>::new()
+            .maintenance(maintenance)
+            .maintenance_page::<MaintenancePage>()
+            .error_page::<UnavailablePage>(StatusCode::SERVICE_UNAVAILABLE)
+            .error_overlay(false)
+            .health_checks(HealthChecks::new())
+            .route(Route::get("/hello", || async move { "hello" }))
+            .route(unavailable)
+            .build();
+
+        let req = || Request::builder().uri("/hello");
+
+        assert!(!service.is_maintenance());
+        let res = service
+            .handle(req().body(Default::default()).unwrap())
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // Other `503` errors render their own error page
+        let res = service
+            .handle(
+                Request::builder()
+                    .uri("/unavailable")
+                    .header(header::ACCEPT, "text/html")
+                    .body(Default::default())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let html = get_text(res).await;
+        assert!(html.contains("service unavailable"));
+        assert!(!html.contains("we will be back soon"));
+
+        service.set_maintenance(true);
+        assert!(service.is_maintenance());
+
+        // Browsers receive the maintenance page
+        let res = service
+            .handle(
+                req()
+                    .header(header::ACCEPT, "text/html")
+                    .body(Default::default())
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "120");
+        let html = get_text(res).await;
+        assert!(html.contains("we will be back soon"));
+        assert!(!html.contains("service unavailable"));
+
+        // Other clients receive a problem details json
+        let res = service
+            .handle(req().body(Default::default()).unwrap())
+            .await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "120");
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+
+        // The health checks keep responding
+        let res = service
+            .handle(
+                Request::builder()
+                    .uri("/healthz")
+                    .body(Default::default())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // Allowed ip and bypass cookie
+        let mut allowed = req().body(Default::default()).unwrap();
+        allowed
+            .extensions_mut()
+            .insert(RemoteAddr::from(SocketAddr::from(([10, 0, 0, 1], 8000))));
+        assert_eq!(service.handle(allowed).await.status(), StatusCode::OK);
+
+        let mut not_allowed = req().body(Default::default()).unwrap();
+        not_allowed
+            .extensions_mut()
+            .insert(RemoteAddr::from(SocketAddr::from(([10, 0, 0, 2], 8000))));
+        assert_eq!(
+            service.handle(not_allowed).await.status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let res = service
+            .handle(
+                req()
+                    .header(header::COOKIE, "bypass=secret")
+                    .body(Default::default())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(get_text(res).await, "hello");
+
+        let res = service
+            .handle(
+                req()
+                    .header(header::COOKIE, "bypass=wrong")
+                    .body(Default::default())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        service.set_maintenance(false);
+        let res = service
+            .handle(req().body(Default::default()).unwrap())
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn maintenance_check_interval_test() {
+        let file = std::env::temp_dir().join(format!("hashira-maintenance-{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+
+        let maintenance = Maintenance::new()
+            .env("HASHIRA_MAINTENANCE_INTERVAL_TEST")
+            .file(&file)
+            .check_interval(Duration::from_secs(60));
+
+        assert!(!maintenance.is_enabled());
+
+        // The last check is used until the interval elapses
+        std::fs::write(&file, "").unwrap();
+        assert!(!maintenance.is_enabled());
+
+        let maintenance = maintenance.check_interval(Duration::ZERO);
+        assert!(maintenance.is_enabled());
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(!maintenance.is_enabled());
+    }
+}
@@ -0,0 +1,420 @@
+use crate::{
+    types::TryBoxStream,
+    web::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures::Stream;
+use http::{header, HeaderValue, Method, StatusCode};
+use pin_project_lite::pin_project;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// The default path of the metrics endpoint.
+pub const DEFAULT_METRICS_ENDPOINT: &str = "/_hashira/metrics";
+
+/// The default buckets of the duration histograms, in seconds.
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// The route label of the requests that didn't match any route
+const UNMATCHED_ROUTE: &str = "<unmatched>";
+
+/// Collects the metrics of the requests, renders and actions of the server.
+///
+/// The requests are keyed by the route pattern and not the path, so `/users/1` and `/users/2`
+/// are counted as `/users/:id`.
+#[derive(Clone)]
+pub struct Metrics {
+    inner: Arc<MetricsInner>,
+    endpoint: Option<String>,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    buckets: Vec<f64>,
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    request_duration: Mutex<BTreeMap<(String, String), Histogram>>,
+    render_duration: Mutex<BTreeMap<&'static str, Histogram>>,
+    stream_duration: Mutex<BTreeMap<&'static str, Histogram>>,
+    actions: Mutex<BTreeMap<(&'static str, u16), u64>>,
+}
+
+impl Metrics {
+    /// Constructs a collector using the default buckets.
+    pub fn new() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS.to_vec())
+    }
+
+    /// Constructs a collector using the given buckets for the duration histograms, in seconds.
+    pub fn with_buckets(mut buckets: Vec<f64>) -> Self {
+        buckets.retain(|x| x.is_finite());
+        buckets.sort_by(f64::total_cmp);
+        buckets.dedup();
+
+        Metrics {
+            inner: Arc::new(MetricsInner {
+                buckets,
+                ..Default::default()
+            }),
+            endpoint: None,
+        }
+    }
+
+    /// Exposes the metrics in the prometheus text format in the given path,
+    /// for example: `/_hashira/metrics`.
+    pub fn endpoint(mut self, path: impl Into<String>) -> Self {
+        self.endpoint = Some(path.into());
+        self
+    }
+
+    /// Returns the path of the metrics endpoint, if any.
+    pub fn endpoint_path(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// Records a request handled by the server.
+    ///
+    /// The `route` is the pattern of the route that matched the request, if any.
+    pub fn record_request(
+        &self,
+        method: &Method,
+        route: Option<&str>,
+        status: StatusCode,
+        duration: Duration,
+    ) {
+        let method = method.as_str().to_owned();
+        let route = route.unwrap_or(UNMATCHED_ROUTE).to_owned();
+
+        {
+            let mut requests = lock(&self.inner.requests);
+            *requests
+                .entry((method.clone(), route.clone(), status.as_u16()))
+                .or_default() += 1;
+        }
+
+        let mut histograms = lock(&self.inner.request_duration);
+        histograms
+            .entry((method, route))
+            .or_insert_with(|| Histogram::new(&self.inner.buckets))
+            .observe(&self.inner.buckets, duration);
+    }
+
+    /// Records the time taken to prepare the render of a page component.
+    pub fn record_render(&self, component: &'static str, duration: Duration) {
+        let mut histograms = lock(&self.inner.render_duration);
+        histograms
+            .entry(component)
+            .or_insert_with(|| Histogram::new(&self.inner.buckets))
+            .observe(&self.inner.buckets, duration);
+    }
+
+    /// Records the time taken to stream a rendered page component.
+    pub fn record_stream(&self, component: &'static str, duration: Duration) {
+        let mut histograms = lock(&self.inner.stream_duration);
+        histograms
+            .entry(component)
+            .or_insert_with(|| Histogram::new(&self.inner.buckets))
+            .observe(&self.inner.buckets, duration);
+    }
+
+    /// Records the invocation of an action.
+    pub fn record_action(&self, action: &'static str, status: StatusCode) {
+        let mut actions = lock(&self.inner.actions);
+        *actions.entry((action, status.as_u16())).or_default() += 1;
+    }
+
+    /// Returns the metrics in the prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buf = String::new();
+
+        write_header(
+            &mut buf,
+            "hashira_http_requests_total",
+            "Total number of HTTP requests.",
+            "counter",
+        );
+        for ((method, route, status), count) in lock(&self.inner.requests).iter() {
+            let labels = labels(&[
+                ("method", method),
+                ("route", route),
+                ("status", &status.to_string()),
+            ]);
+            let _ = writeln!(buf, "hashira_http_requests_total{{{labels}}} {count}");
+        }
+
+        write_header(
+            &mut buf,
+            "hashira_http_request_duration_seconds",
+            "Time to respond the HTTP requests in seconds.",
+            "histogram",
+        );
+        for ((method, route), histogram) in lock(&self.inner.request_duration).iter() {
+            let labels = labels(&[("method", method), ("route", route)]);
+            histogram.write_to(
+                &mut buf,
+                "hashira_http_request_duration_seconds",
+                &labels,
+                &self.inner.buckets,
+            );
+        }
+
+        write_header(
+            &mut buf,
+            "hashira_render_duration_seconds",
+            "Time to prepare the render of the pages in seconds.",
+            "histogram",
+        );
+        for (component, histogram) in lock(&self.inner.render_duration).iter() {
+            let labels = labels(&[("component", component)]);
+            histogram.write_to(
+                &mut buf,
+                "hashira_render_duration_seconds",
+                &labels,
+                &self.inner.buckets,
+            );
+        }
+
+        write_header(
+            &mut buf,
+            "hashira_stream_duration_seconds",
+            "Time to stream the rendered pages in seconds.",
+            "histogram",
+        );
+        for (component, histogram) in lock(&self.inner.stream_duration).iter() {
+            let labels = labels(&[("component", component)]);
+            histogram.write_to(
+                &mut buf,
+                "hashira_stream_duration_seconds",
+                &labels,
+                &self.inner.buckets,
+            );
+        }
+
+        write_header(
+            &mut buf,
+            "hashira_actions_total",
+            "Total number of action invocations.",
+            "counter",
+        );
+        for ((action, status), count) in lock(&self.inner.actions).iter() {
+            let labels = labels(&[("action", action), ("status", &status.to_string())]);
+            let _ = writeln!(buf, "hashira_actions_total{{{labels}}} {count}");
+        }
+
+        buf
+    }
+
+    /// Returns a response with the metrics in the prometheus text format.
+    pub fn to_response(&self) -> Response {
+        let mut res = self.render().into_response();
+        res.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+        );
+        res
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics::new()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // The metrics are still valid if a thread panicked while holding the lock
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn write_header(buf: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(buf, "# HELP {name} {help}");
+    let _ = writeln!(buf, "# TYPE {name} {kind}");
+}
+
+fn labels(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct Histogram {
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: &[f64]) -> Self {
+        Histogram {
+            counts: vec![0; buckets.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, buckets: &[f64], duration: Duration) {
+        let secs = duration.as_secs_f64();
+        self.sum += secs;
+        self.count += 1;
+
+        // Values greater than the last bucket are only counted in `+Inf`
+        if let Some(idx) = buckets.iter().position(|le| secs <= *le) {
+            self.counts[idx] += 1;
+        }
+    }
+
+    fn write_to(&self, buf: &mut String, name: &str, labels: &str, buckets: &[f64]) {
+        let mut cumulative = 0;
+
+        for (count, le) in self.counts.iter().zip(buckets) {
+            cumulative += count;
+            let _ = writeln!(buf, "{name}_bucket{{{labels},le=\"{le}\"}} {cumulative}");
+        }
+
+        let _ = writeln!(buf, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(buf, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(buf, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+/// The route that matched a request, this is inserted in the extensions
+/// of the response to be recorded after the request is handled.
+pub(crate) struct MatchedRoute {
+    pub path: String,
+    pub action: Option<&'static str>,
+}
+
+/// The name of the action handled by a route.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ActionName(pub &'static str);
+
+pin_project! {
+    /// Records the time to consume the stream of a rendered page.
+    pub(crate) struct TimedStream {
+        #[pin]
+        stream: TryBoxStream<Bytes>,
+        metrics: Metrics,
+        component: &'static str,
+        start: Option<Instant>,
+    }
+}
+
+#[cfg_attr(feature = "client", allow(dead_code))]
+impl TimedStream {
+    pub fn new(stream: TryBoxStream<Bytes>, metrics: Metrics, component: &'static str) -> Self {
+        TimedStream {
+            stream,
+            metrics,
+            component,
+            start: None,
+        }
+    }
+}
+
+impl Stream for TimedStream {
+    type Item = Result<Bytes, crate::error::BoxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let start = *this.start.get_or_insert_with(Instant::now);
+        let ret = this.stream.poll_next(cx);
+
+        if let Poll::Ready(None) = ret {
+            this.metrics.record_stream(this.component, start.elapsed());
+        }
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Metrics, DEFAULT_METRICS_ENDPOINT};
+    use crate::{app::App, routing::Route, web::Request};
+    use http::{header, StatusCode};
+    use std::time::Duration;
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(path: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_test() {
+        let service = App::<Base>::new()
+            .metrics(Metrics::with_buckets(vec![1.0, 60.0]).endpoint(DEFAULT_METRICS_ENDPOINT))
+            .route(Route::get("/users/:id", || async { "user" }))
+            .build();
+
+        for path in ["/users/1", "/users/2", "/not_found"] {
+            service.handle(create_req(path)).await;
+        }
+
+        let res = service.handle(create_req(DEFAULT_METRICS_ENDPOINT)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; version=0.0.4; charset=utf-8"
+        );
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+        assert!(text.contains(
+            "hashira_http_requests_total{method=\"GET\",route=\"/users/:id\",status=\"200\"} 2\n"
+        ));
+        assert!(text.contains(
+            "hashira_http_requests_total{method=\"GET\",route=\"<unmatched>\",status=\"404\"} 1\n"
+        ));
+        assert!(text.contains(
+            "hashira_http_request_duration_seconds_count{method=\"GET\",route=\"/users/:id\"} 2\n"
+        ));
+    }
+
+    #[test]
+    fn histogram_test() {
+        let metrics = Metrics::with_buckets(vec![0.5, 0.1, 1.0]);
+        metrics.record_render("Page", Duration::from_millis(50));
+        metrics.record_render("Page", Duration::from_millis(300));
+        metrics.record_render("Page", Duration::from_secs(2));
+        metrics.record_action("CreateUser", StatusCode::OK);
+
+        let text = metrics.render();
+        let expected = "\
+hashira_render_duration_seconds_bucket{component=\"Page\",le=\"0.1\"} 1
+hashira_render_duration_seconds_bucket{component=\"Page\",le=\"0.5\"} 2
+hashira_render_duration_seconds_bucket{component=\"Page\",le=\"1\"} 2
+hashira_render_duration_seconds_bucket{component=\"Page\",le=\"+Inf\"} 3
+hashira_render_duration_seconds_sum{component=\"Page\"} 2.35
+hashira_render_duration_seconds_count{component=\"Page\"} 3
+";
+        assert!(text.contains(expected), "{text}");
+        assert!(text.contains("hashira_actions_total{action=\"CreateUser\",status=\"200\"} 1\n"));
+    }
+}
@@ -0,0 +1,638 @@
+use crate::{
+    error::{BoxError, ServerError},
+    web::{RemoteAddr, Request, Response},
+};
+use http::{header, HeaderName, HeaderValue, StatusCode};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// The number of checks after which the expired entries of the [`MemoryStore`] are removed.
+const CLEANUP_INTERVAL: u64 = 1024;
+
+/// The algorithm used to limit the requests.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RateLimitAlgorithm {
+    /// Allows bursts of up to `capacity` requests, one request is added back every `refill`.
+    TokenBucket {
+        /// Max number of tokens in the bucket.
+        capacity: u64,
+
+        /// The time to add back a token.
+        refill: Duration,
+    },
+
+    /// Allows `limit` requests per `window`, weighting the requests of the previous window
+    /// to avoid bursts when the window resets.
+    SlidingWindow {
+        /// Max number of requests per window.
+        limit: u64,
+
+        /// Duration of the window.
+        window: Duration,
+    },
+}
+
+impl RateLimitAlgorithm {
+    /// Returns the max number of requests allowed.
+    pub fn limit(&self) -> u64 {
+        match self {
+            RateLimitAlgorithm::TokenBucket { capacity, .. } => *capacity,
+            RateLimitAlgorithm::SlidingWindow { limit, .. } => *limit,
+        }
+    }
+}
+
+/// The result of checking a request against a rate limit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    allowed: bool,
+    limit: u64,
+    remaining: u64,
+    reset: Duration,
+    retry_after: Duration,
+}
+
+impl RateLimitDecision {
+    /// Constructs a decision that allows the request.
+    pub fn allow(limit: u64, remaining: u64, reset: Duration) -> Self {
+        RateLimitDecision {
+            allowed: true,
+            limit,
+            remaining,
+            reset,
+            retry_after: Duration::ZERO,
+        }
+    }
+
+    /// Constructs a decision that rejects the request.
+    pub fn deny(limit: u64, reset: Duration, retry_after: Duration) -> Self {
+        RateLimitDecision {
+            allowed: false,
+            limit,
+            remaining: 0,
+            reset,
+            retry_after,
+        }
+    }
+
+    /// Returns `true` if the request is allowed.
+    pub fn is_allowed(&self) -> bool {
+        self.allowed
+    }
+
+    /// Returns the max number of requests allowed.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns the number of requests left.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Returns the time until the limit is fully reset.
+    pub fn reset(&self) -> Duration {
+        self.reset
+    }
+
+    /// Returns the time to wait before retrying a rejected request.
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    /// Adds the `RateLimit-*` headers and the `Retry-After` header if was rejected.
+    pub(crate) fn apply(&self, res: &mut Response) {
+        let headers = res.headers_mut();
+        headers.insert(RATE_LIMIT_LIMIT, HeaderValue::from(self.limit));
+        headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from(self.remaining));
+        headers.insert(RATE_LIMIT_RESET, HeaderValue::from(as_secs(self.reset)));
+
+        if !self.allowed {
+            headers.insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(as_secs(self.retry_after)),
+            );
+        }
+    }
+
+    /// Returns the response for a rejected request.
+    pub(crate) fn error_response(&self) -> Response {
+        use crate::web::IntoResponse;
+
+        ServerError::new(StatusCode::TOO_MANY_REQUESTS, "too many requests").into_response()
+    }
+}
+
+const RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+// Seconds rounded up, a client should not retry before the time
+fn as_secs(duration: Duration) -> u64 {
+    let secs = duration.as_secs();
+    if duration.subsec_nanos() > 0 {
+        secs + 1
+    } else {
+        secs
+    }
+}
+
+/// Stores the state of the rate limits.
+///
+/// Implement this to share the limits between multiple servers, like using a Redis database.
+#[async_trait::async_trait]
+pub trait RateLimitStore {
+    /// Records a request with the given key and returns whether is allowed.
+    async fn check(
+        &self,
+        key: &str,
+        algorithm: &RateLimitAlgorithm,
+    ) -> Result<RateLimitDecision, BoxError>;
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Entry {
+    Bucket {
+        tokens: f64,
+        last: Instant,
+    },
+    Window {
+        prev_count: u64,
+        count: u64,
+        start: Instant,
+    },
+}
+
+/// A store that keeps the rate limits in memory, the limits are not shared between servers.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, Entry>>,
+    checks: AtomicU64,
+}
+
+impl MemoryStore {
+    /// Constructs an empty store.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub(crate) fn check_at(
+        &self,
+        key: &str,
+        algorithm: &RateLimitAlgorithm,
+        now: Instant,
+    ) -> RateLimitDecision {
+        let mut entries = self.entries.lock().unwrap();
+
+        let checks = self.checks.fetch_add(1, Ordering::Relaxed) + 1;
+        if checks >= CLEANUP_INTERVAL {
+            self.checks.store(0, Ordering::Relaxed);
+            entries.retain(|_, entry| !is_expired(entry, algorithm, now));
+        }
+
+        match *algorithm {
+            RateLimitAlgorithm::TokenBucket { capacity, refill } => {
+                let new_entry = Entry::Bucket {
+                    tokens: capacity as f64,
+                    last: now,
+                };
+
+                // The key may have been used with other algorithm
+                let entry = entries.entry(key.to_owned()).or_insert(new_entry);
+                if !matches!(entry, Entry::Bucket { .. }) {
+                    *entry = new_entry;
+                }
+
+                let Entry::Bucket { tokens, last } = entry else {
+                    unreachable!("rate limit entry is not a token bucket")
+                };
+
+                let refill_secs = refill.as_secs_f64();
+                let elapsed = now.saturating_duration_since(*last).as_secs_f64();
+                *tokens = (*tokens + elapsed / refill_secs).min(capacity as f64);
+                *last = now;
+
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    let reset = Duration::from_secs_f64((capacity as f64 - *tokens) * refill_secs);
+                    RateLimitDecision::allow(capacity, *tokens as u64, reset)
+                } else {
+                    let reset = Duration::from_secs_f64((capacity as f64 - *tokens) * refill_secs);
+                    let retry_after = Duration::from_secs_f64((1.0 - *tokens) * refill_secs);
+                    RateLimitDecision::deny(capacity, reset, retry_after)
+                }
+            }
+            RateLimitAlgorithm::SlidingWindow { limit, window } => {
+                let new_entry = Entry::Window {
+                    prev_count: 0,
+                    count: 0,
+                    start: now,
+                };
+
+                // The key may have been used with other algorithm
+                let entry = entries.entry(key.to_owned()).or_insert(new_entry);
+                if !matches!(entry, Entry::Window { .. }) {
+                    *entry = new_entry;
+                }
+
+                let Entry::Window {
+                    prev_count,
+                    count,
+                    start,
+                } = entry
+                else {
+                    unreachable!("rate limit entry is not a sliding window")
+                };
+
+                // Move to the window of the current time
+                let elapsed = now.saturating_duration_since(*start);
+                if elapsed >= window * 2 {
+                    *prev_count = 0;
+                    *count = 0;
+                    *start = now;
+                } else if elapsed >= window {
+                    *prev_count = *count;
+                    *count = 0;
+                    *start += window;
+                }
+
+                let elapsed = now.saturating_duration_since(*start);
+                let window_secs = window.as_secs_f64();
+                let weight = 1.0 - (elapsed.as_secs_f64() / window_secs);
+                let estimated = (*prev_count as f64 * weight) + *count as f64;
+                let reset = window - elapsed;
+
+                if estimated + 1.0 <= limit as f64 {
+                    *count += 1;
+                    let remaining = (limit as f64 - estimated - 1.0).floor() as u64;
+                    RateLimitDecision::allow(limit, remaining, reset)
+                } else {
+                    // Time until the weighted requests of the previous window leave room for one more
+                    let retry_after = if *count + 1 > limit || *prev_count == 0 {
+                        reset
+                    } else {
+                        let available = (limit - *count - 1) as f64;
+                        let weight_needed = available / *prev_count as f64;
+                        let wait = (1.0 - weight_needed) * window_secs - elapsed.as_secs_f64();
+                        Duration::from_secs_f64(wait.max(0.0)).min(reset)
+                    };
+
+                    RateLimitDecision::deny(limit, reset, retry_after)
+                }
+            }
+        }
+    }
+}
+
+fn is_expired(entry: &Entry, algorithm: &RateLimitAlgorithm, now: Instant) -> bool {
+    match (entry, algorithm) {
+        (Entry::Bucket { last, .. }, RateLimitAlgorithm::TokenBucket { capacity, refill }) => {
+            now.saturating_duration_since(*last) >= refill.mul_f64(*capacity as f64)
+        }
+        (Entry::Window { start, .. }, RateLimitAlgorithm::SlidingWindow { window, .. }) => {
+            now.saturating_duration_since(*start) >= *window * 2
+        }
+        _ => true,
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn check(
+        &self,
+        key: &str,
+        algorithm: &RateLimitAlgorithm,
+    ) -> Result<RateLimitDecision, BoxError> {
+        Ok(self.check_at(key, algorithm, Instant::now()))
+    }
+}
+
+type KeyFn = Arc<dyn Fn(&Request<()>) -> Option<String> + Send + Sync>;
+
+/// Determines the key used to count the requests.
+#[derive(Clone)]
+pub enum RateLimitKey {
+    /// The ip of the `RemoteAddr` of the request.
+    RemoteAddr,
+
+    /// The value of a header.
+    Header(HeaderName),
+
+    /// A function that returns the key of a request.
+    Custom(KeyFn),
+}
+
+impl RateLimitKey {
+    /// Constructs a key using the value of the given header.
+    pub fn header(name: impl TryInto<HeaderName>) -> Self {
+        match name.try_into() {
+            Ok(name) => RateLimitKey::Header(name),
+            Err(_) => panic!("invalid rate limit header name"),
+        }
+    }
+
+    /// Constructs a key using the given function.
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&Request<()>) -> Option<String> + Send + Sync + 'static,
+    {
+        RateLimitKey::Custom(Arc::new(f))
+    }
+
+    /// Returns the key for the given request, requests without key are not limited.
+    pub fn get(&self, req: &Request<()>) -> Option<String> {
+        match self {
+            RateLimitKey::RemoteAddr => req
+                .extensions()
+                .get::<RemoteAddr>()
+                .map(|addr| addr.ip().to_string()),
+            RateLimitKey::Header(name) => req
+                .headers()
+                .get(name)
+                .and_then(|x| x.to_str().ok())
+                .map(|x| x.to_owned()),
+            RateLimitKey::Custom(f) => f(req),
+        }
+    }
+}
+
+impl std::fmt::Debug for RateLimitKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateLimitKey::RemoteAddr => write!(f, "RemoteAddr"),
+            RateLimitKey::Header(name) => f.debug_tuple("Header").field(name).finish(),
+            RateLimitKey::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Limits the number of requests a client can make.
+///
+/// It can be registered for all the routes with [`App::rate_limit`], for the routes of a scope
+/// with [`AppNested::rate_limit`] or for a single action with
+/// `#[action("/path", rate_limit = "path::to::fn")]`, the most specific configuration is used.
+///
+/// Rejected requests receive a `429 Too Many Requests` with a `Retry-After` header,
+/// and all the responses include the `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers.
+///
+/// # Example
+/// ```rust,no_run
+/// use hashira::rate_limit::{RateLimit, RateLimitKey};
+/// use std::time::Duration;
+///
+/// // 5 requests per minute for each `x-api-key`
+/// let rate_limit = RateLimit::sliding_window(5, Duration::from_secs(60))
+///     .key(RateLimitKey::header("x-api-key"));
+/// ```
+///
+/// [`App::rate_limit`]: crate::app::App::rate_limit
+/// [`AppNested::rate_limit`]: crate::app::AppNested::rate_limit
+#[derive(Clone)]
+pub struct RateLimit {
+    algorithm: RateLimitAlgorithm,
+    key: RateLimitKey,
+    name: Option<String>,
+    store: Arc<dyn RateLimitStore + Send + Sync>,
+}
+
+impl RateLimit {
+    /// Constructs a rate limit with the given algorithm,
+    /// by default the requests are counted by `RemoteAddr` in a [`MemoryStore`].
+    pub fn new(algorithm: RateLimitAlgorithm) -> Self {
+        match algorithm {
+            RateLimitAlgorithm::TokenBucket { capacity, refill } => {
+                assert!(
+                    capacity > 0,
+                    "rate limit capacity should be greater than zero"
+                );
+                assert!(
+                    !refill.is_zero(),
+                    "rate limit refill should be greater than zero"
+                );
+            }
+            RateLimitAlgorithm::SlidingWindow { limit, window } => {
+                assert!(limit > 0, "rate limit should be greater than zero");
+                assert!(
+                    !window.is_zero(),
+                    "rate limit window should be greater than zero"
+                );
+            }
+        }
+
+        RateLimit {
+            algorithm,
+            key: RateLimitKey::RemoteAddr,
+            name: None,
+            store: Arc::new(MemoryStore::new()),
+        }
+    }
+
+    /// Constructs a rate limit that allows bursts of `capacity` requests
+    /// and adds back a request every `refill`.
+    pub fn token_bucket(capacity: u64, refill: Duration) -> Self {
+        RateLimit::new(RateLimitAlgorithm::TokenBucket { capacity, refill })
+    }
+
+    /// Constructs a rate limit that allows `limit` requests per `window`.
+    pub fn sliding_window(limit: u64, window: Duration) -> Self {
+        RateLimit::new(RateLimitAlgorithm::SlidingWindow { limit, window })
+    }
+
+    /// Sets how the requests are grouped.
+    pub fn key(mut self, key: RateLimitKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Sets the name used to prefix the keys, use it to keep separate counters
+    /// when multiple rate limits use the same store.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the store where the counters are kept.
+    pub fn store(mut self, store: impl RateLimitStore + Send + Sync + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Returns the algorithm used.
+    pub fn algorithm(&self) -> &RateLimitAlgorithm {
+        &self.algorithm
+    }
+
+    /// Records the request and returns the decision,
+    /// returns `None` if the request has no key or the store failed.
+    pub(crate) async fn check(&self, req: &Request<()>) -> Option<RateLimitDecision> {
+        let key = self.key.get(req)?;
+        let key = match &self.name {
+            Some(name) => format!("{name}:{key}"),
+            None => key,
+        };
+
+        match self.store.check(&key, &self.algorithm).await {
+            Ok(decision) => Some(decision),
+            Err(err) => {
+                // We let the request pass if the store is not available
+                log::error!("failed to check the rate limit: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for RateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("algorithm", &self.algorithm)
+            .field("key", &self.key)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryStore, RateLimitAlgorithm, RateLimitDecision};
+    use std::time::{Duration, Instant};
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    #[cfg(not(feature = "client"))]
+    #[yew::function_component]
+    fn Base(props: &yew::html::ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[cfg(not(feature = "client"))]
+    fn create_req(path: &str, key: &str) -> crate::web::Request {
+        crate::web::Request::builder()
+            .uri(path)
+            .header("x-api-key", key)
+            .body(Default::default())
+            .unwrap()
+    }
+
+    #[cfg(not(feature = "client"))]
+    #[tokio::test]
+    async fn rate_limit_response_test() {
+        use super::{RateLimit, RateLimitKey};
+        use crate::{app::App, error::PROBLEM_JSON, routing::Route};
+        use http::{header, StatusCode};
+
+        let service = App::<Base>::new()
+            .rate_limit(RateLimit::token_bucket(1, SEC * 60).key(RateLimitKey::header("x-api-key")))
+            .route(Route::get("/api/hello", || async move { "hello" }))
+            .route(
+                Route::get("/api/signup", || async move { "signup" }).rate_limit(
+                    RateLimit::sliding_window(2, SEC * 60).key(RateLimitKey::header("x-api-key")),
+                ),
+            )
+            .build();
+
+        let res = service.handle(create_req("/api/hello", "a")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get("ratelimit-limit").unwrap(), "1");
+        assert_eq!(res.headers().get("ratelimit-remaining").unwrap(), "0");
+        assert_eq!(res.headers().get("ratelimit-reset").unwrap(), "60");
+        assert!(res.headers().get(header::RETRY_AFTER).is_none());
+
+        let res = service.handle(create_req("/api/hello", "a")).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "60");
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON
+        );
+
+        // Other keys have its own limit
+        let res = service.handle(create_req("/api/hello", "b")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // The route limit overrides the app limit
+        for _ in 0..2 {
+            let res = service.handle(create_req("/api/signup", "a")).await;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        let res = service.handle(create_req("/api/signup", "a")).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get("ratelimit-limit").unwrap(), "2");
+
+        // Requests without key are not limited
+        let req = crate::web::Request::builder()
+            .uri("/api/hello")
+            .body(Default::default())
+            .unwrap();
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get("ratelimit-limit").is_none());
+    }
+
+    #[test]
+    fn token_bucket_test() {
+        let store = MemoryStore::new();
+        let algorithm = RateLimitAlgorithm::TokenBucket {
+            capacity: 2,
+            refill: SEC,
+        };
+
+        let now = Instant::now();
+        assert_eq!(
+            store.check_at("a", &algorithm, now),
+            RateLimitDecision::allow(2, 1, SEC)
+        );
+        assert_eq!(
+            store.check_at("a", &algorithm, now),
+            RateLimitDecision::allow(2, 0, SEC * 2)
+        );
+        assert_eq!(
+            store.check_at("a", &algorithm, now),
+            RateLimitDecision::deny(2, SEC * 2, SEC)
+        );
+
+        // Other keys are not affected
+        assert!(store.check_at("b", &algorithm, now).is_allowed());
+
+        // A token is added back after the refill
+        let decision = store.check_at("a", &algorithm, now + SEC);
+        assert_eq!(decision, RateLimitDecision::allow(2, 0, SEC * 2));
+    }
+
+    #[test]
+    fn sliding_window_test() {
+        let store = MemoryStore::new();
+        let algorithm = RateLimitAlgorithm::SlidingWindow {
+            limit: 2,
+            window: SEC * 10,
+        };
+
+        let now = Instant::now();
+        assert!(store.check_at("a", &algorithm, now).is_allowed());
+        assert!(store.check_at("a", &algorithm, now).is_allowed());
+
+        let decision = store.check_at("a", &algorithm, now + SEC);
+        assert!(!decision.is_allowed());
+        assert_eq!(decision.retry_after(), SEC * 9);
+
+        // Half of the previous window still counts
+        let decision = store.check_at("a", &algorithm, now + SEC * 15);
+        assert_eq!(decision, RateLimitDecision::allow(2, 0, SEC * 5));
+
+        let decision = store.check_at("a", &algorithm, now + SEC * 15);
+        assert!(!decision.is_allowed());
+
+        // Both windows have passed
+        let decision = store.check_at("a", &algorithm, now + SEC * 30);
+        assert_eq!(decision, RateLimitDecision::allow(2, 1, SEC * 10));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{components::AnyComponent, app::ErrorPageHandler};
+use crate::{
+    app::ErrorPageHandler,
+    components::{id::PageId, AnyComponent},
+};
 use http::StatusCode;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -12,6 +15,7 @@ pub struct ErrorPageConflictError(StatusCode);
 pub struct ErrorRouter {
     routes: HashMap<StatusCode, AnyComponent<serde_json::Value>>,
     fallback: Option<AnyComponent<serde_json::Value>>,
+    maintenance: Option<(PageId, AnyComponent<serde_json::Value>)>,
 }
 
 impl ErrorRouter {
@@ -20,6 +24,7 @@ impl ErrorRouter {
         ErrorRouter {
             routes: HashMap::new(),
             fallback: None,
+            maintenance: None,
         }
     }
 
@@ -41,10 +46,23 @@ impl ErrorRouter {
         self.fallback = Some(component);
     }
 
+    /// Sets the component rendered during the maintenance.
+    pub(crate) fn maintenance(&mut self, id: PageId, component: AnyComponent<serde_json::Value>) {
+        self.maintenance = Some((id, component));
+    }
+
     /// Returns the component to render for the given `StatusCode`.
     pub fn find(&self, status: &StatusCode) -> Option<&AnyComponent<serde_json::Value>> {
         self.routes.get(status).or(self.fallback.as_ref())
     }
+
+    /// Returns the maintenance component if was registered with the given id.
+    pub(crate) fn find_maintenance(&self, id: &PageId) -> Option<&AnyComponent<serde_json::Value>> {
+        self.maintenance
+            .as_ref()
+            .filter(|(maintenance_id, _)| maintenance_id == id)
+            .map(|(_, component)| component)
+    }
 }
 
 /// Contains the error routes for the client.
@@ -52,6 +70,7 @@ impl ErrorRouter {
 pub struct ServerErrorRouter {
     routes: HashMap<StatusCode, ErrorPageHandler>,
     fallback: Option<ErrorPageHandler>,
+    maintenance: Option<ErrorPageHandler>,
 }
 
 impl ServerErrorRouter {
@@ -60,6 +79,7 @@ impl ServerErrorRouter {
         ServerErrorRouter {
             routes: HashMap::new(),
             fallback: None,
+            maintenance: None,
         }
     }
 
@@ -81,6 +101,12 @@ impl ServerErrorRouter {
         self.fallback = Some(handler);
     }
 
+    /// Sets the handler that renders the page during the maintenance.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn maintenance(&mut self, handler: ErrorPageHandler) {
+        self.maintenance = Some(handler);
+    }
+
     /// Returns the handler for the given `StatusCode`.
     pub fn find(&self, status: &StatusCode) -> Option<&ErrorPageHandler> {
         match self.routes.get(status) {
@@ -88,4 +114,12 @@ impl ServerErrorRouter {
             None => self.fallback.as_ref(),
         }
     }
+
+    /// Returns the handler for the maintenance page,
+    /// or the handler for `503 Service Unavailable` if there is no maintenance page.
+    pub(crate) fn find_maintenance(&self) -> Option<&ErrorPageHandler> {
+        self.maintenance
+            .as_ref()
+            .or_else(|| self.find(&StatusCode::SERVICE_UNAVAILABLE))
+    }
 }
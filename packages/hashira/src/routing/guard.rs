@@ -0,0 +1,29 @@
+use crate::{app::RequestContext, web::Response};
+use std::sync::Arc;
+
+/// A check that runs before the handler of a route.
+///
+/// Guards can be added to a [`Route`], to all the routes of an `AppNested`
+/// or to pages and actions using `guard = "path::to::fn"` in its attribute.
+///
+/// [`Route`]: crate::routing::Route
+#[async_trait::async_trait]
+pub trait Guard: Send + Sync + 'static {
+    /// Returns `Ok` if the request can continue, otherwise the response to return.
+    async fn check(&self, ctx: &RequestContext) -> Result<(), Response>;
+}
+
+/// The guards of a route, stored in the route extensions.
+#[derive(Default, Clone)]
+pub(crate) struct RouteGuards(pub(crate) Vec<Arc<dyn Guard>>);
+
+impl RouteGuards {
+    /// Runs the guards in order and returns the first rejection.
+    pub(crate) async fn check(&self, ctx: &RequestContext) -> Result<(), Response> {
+        for guard in self.0.iter() {
+            guard.check(ctx).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -20,4 +20,7 @@ mod page_route;
 pub use page_route::*;
 
 mod error_router;
-pub use error_router::*;
\ No newline at end of file
+pub use error_router::*;
+
+mod guard;
+pub use guard::*;
@@ -1,11 +1,11 @@
 use core::fmt;
 use http::Extensions;
-use std::future::Future;
+use std::{future::Future, sync::Arc};
 
-use super::RouteMethod;
+use super::{Guard, RouteGuards, RouteMethod};
 use crate::{
-    app::{Handler, PageHandler},
-    web::{FromRequest, IntoResponse},
+    app::{Handler, PageHandler, RequestTimeout},
+    web::{BodyLimit, FromRequest, IntoResponse},
 };
 
 /// Type of the handler in a route
@@ -183,6 +183,39 @@ impl Route {
     pub fn extensions_mut(&mut self) -> &mut Extensions {
         &mut self.extensions
     }
+
+    /// Adds a guard to this route, guards run in order before the handler.
+    pub fn guard(mut self, guard: impl Guard) -> Self {
+        self.add_guard(Arc::new(guard));
+        self
+    }
+
+    /// Sets the max size of the request body of this route.
+    pub fn body_limit(mut self, limit: impl Into<BodyLimit>) -> Self {
+        self.extensions.insert(limit.into());
+        self
+    }
+
+    /// Sets the max time to process a request of this route.
+    pub fn timeout(mut self, timeout: impl Into<RequestTimeout>) -> Self {
+        self.extensions.insert(timeout.into());
+        self
+    }
+
+    /// Sets the rate limit of this route.
+    pub fn rate_limit(mut self, rate_limit: crate::rate_limit::RateLimit) -> Self {
+        self.extensions.insert(rate_limit);
+        self
+    }
+
+    pub(crate) fn add_guard(&mut self, guard: Arc<dyn Guard>) {
+        match self.extensions.get_mut::<RouteGuards>() {
+            Some(guards) => guards.0.push(guard),
+            None => {
+                self.extensions.insert(RouteGuards(vec![guard]));
+            }
+        }
+    }
 }
 
 impl fmt::Debug for Route {
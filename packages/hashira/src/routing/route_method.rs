@@ -47,6 +47,24 @@ impl RouteMethod {
     pub fn all() -> RouteMethod {
         RouteMethod(0b1111_1111)
     }
+
+    /// Returns the http methods this `RouteMethod` matches.
+    pub fn to_methods(&self) -> Vec<Method> {
+        [
+            (RouteMethod::GET, Method::GET),
+            (RouteMethod::POST, Method::POST),
+            (RouteMethod::PUT, Method::PUT),
+            (RouteMethod::PATCH, Method::PATCH),
+            (RouteMethod::DELETE, Method::DELETE),
+            (RouteMethod::HEAD, Method::HEAD),
+            (RouteMethod::OPTIONS, Method::OPTIONS),
+            (RouteMethod::TRACE, Method::TRACE),
+        ]
+        .into_iter()
+        .filter(|(route_method, _)| self.matches(route_method))
+        .map(|(_, method)| method)
+        .collect()
+    }
 }
 
 #[derive(Debug, Error)]
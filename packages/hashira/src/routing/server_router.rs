@@ -38,6 +38,10 @@ impl MethodRouter {
         self.0.iter().find(|route| route.method.matches(&m))
     }
 
+    pub(crate) fn methods(&self) -> Option<RouteMethod> {
+        self.0.iter().map(|route| route.method).reduce(|a, b| a | b)
+    }
+
     pub(crate) fn push(&mut self, route: Route) -> Result<(), RouteMethodConflict> {
         let method = route.method();
 
@@ -126,6 +130,12 @@ impl ServerRouter {
         }
     }
 
+    /// Returns all the methods registered for the given path.
+    pub fn methods_at(&self, path: &str) -> Option<RouteMethod> {
+        let mtch = self.route_to_id.find(path).ok()?;
+        self.id_to_route.get(mtch.value)?.methods()
+    }
+
     pub fn insert(&mut self, route: Route) -> Result<(), InsertServerRouteError> {
         let path = route.path().to_owned();
         match self.path_to_id.get(&path) {
@@ -282,4 +292,19 @@ mod tests {
         assert!(router.at("/path", RouteMethod::PATCH).is_ok());
         assert!(router.at("/path", RouteMethod::DELETE).is_ok());
     }
+
+    #[test]
+    fn methods_at_test() {
+        let mut router = ServerRouter::new();
+
+        let route1 = Route::new("/path/:id", RouteMethod::GET, noop);
+        let route2 = Route::new("/path/:id", RouteMethod::POST | RouteMethod::PUT, noop);
+
+        assert!(router.insert(route1).is_ok());
+        assert!(router.insert(route2).is_ok());
+
+        let methods = router.methods_at("/path/1").unwrap();
+        assert_eq!(methods, RouteMethod::GET | RouteMethod::POST | RouteMethod::PUT);
+        assert!(router.methods_at("/other").is_none());
+    }
 }
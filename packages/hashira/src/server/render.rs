@@ -9,17 +9,19 @@ use crate::components::{
     HASHIRA_SCRIPTS_MARKER, HASHIRA_TITLE_MARKER,
 };
 use crate::context::ServerContext;
-use crate::error::BoxError;
+use crate::error::{BoxError, ServerPanic};
+use crate::metrics::TimedStream;
 use crate::routing::ErrorRouter;
 use crate::types::TryBoxStream;
 use bytes::Bytes;
-use futures::{stream, StreamExt, TryStreamExt};
+use futures::{channel::mpsc::UnboundedReceiver, stream, StreamExt, TryStreamExt};
 use serde::Serialize;
-use std::sync::Arc;
+use std::{panic::AssertUnwindSafe, sync::Arc};
 use yew::{
     function_component,
     html::{ChildrenProps, ChildrenRenderer},
-    BaseComponent, Html, ServerRenderer,
+    platform::{LocalHandle, Runtime},
+    BaseComponent, Html, LocalServerRenderer, ServerRenderer,
 };
 
 pub(crate) struct RenderPageOptions {
@@ -50,6 +52,14 @@ struct AfterContentElements {
 }
 
 /// Renders the given component inside the given root as a stream of bytes.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "render",
+        skip_all,
+        fields(component = std::any::type_name::<COMP>())
+    )
+)]
 pub(crate) async fn render_page_to_stream<COMP, ROOT>(
     props: COMP::Properties,
     options: RenderPageOptions,
@@ -59,6 +69,8 @@ where
     COMP::Properties: Serialize + Send,
     ROOT: BaseComponent<Properties = ChildrenProps>,
 {
+    let start = std::time::Instant::now();
+
     let RenderPageOptions {
         head,
         index_html,
@@ -67,6 +79,11 @@ where
         request_context,
     } = options;
 
+    let component = std::any::type_name::<COMP>();
+    let metrics = request_context
+        .app_data::<crate::metrics::Metrics>()
+        .cloned();
+
     // The base layout
     #[allow(unused_mut)]
     let mut result_html = index_html;
@@ -85,10 +102,15 @@ where
             .expect("hooks where no registered in AppData");
 
         for before_render in hooks.on_before_render_hooks.iter() {
-            result_html = before_render
-                .call(result_html, request_context.clone())
-                .await
-                .map_err(RenderError::ChunkError)?
+            let fut = before_render.call(result_html, request_context.clone());
+
+            #[cfg(feature = "tracing")]
+            let fut = tracing::Instrument::instrument(
+                fut,
+                tracing::info_span!("hook", name = "on_before_render"),
+            );
+
+            result_html = fut.await.map_err(RenderError::ChunkError)?
         }
     }
 
@@ -111,8 +133,22 @@ where
         uri: request_context.request().uri().clone(),
         error: page_error,
         params: request_context.params().clone(),
+        csrf: crate::csrf::get_or_create_token(&request_context),
+        user: crate::auth::get_page_user(&request_context).await,
+        flash: crate::flash::take_page_messages(&request_context),
+        i18n: crate::i18n::get_locale_data(&request_context),
     };
 
+    // Sets the `<html lang>` and the alternate links of the locale
+    let mut locale_links = PageLinks::new();
+    if let Some(locale_data) = &page_data.i18n {
+        crate::i18n::set_html_lang(&mut result_html, &locale_data.locale);
+
+        if let Some(i18n) = request_context.app_data::<crate::i18n::I18n>() {
+            locale_links = i18n.alternate_links(&request_context);
+        }
+    }
+
     // The props passed to the container page
     let page_props = PageProps {
         page_data: page_data.clone(),
@@ -128,7 +164,9 @@ where
         server_context: ServerContext::new(Some(request_context)),
     };
 
-    let (title, metadata, links, scripts) = head.into_parts();
+    let (title, metadata, mut links, scripts) = head.into_parts();
+    links.extend(locale_links);
+
     let before_content = BeforeContentElements {
         title,
         metadata,
@@ -142,9 +180,21 @@ where
         .map(|(a, b)| (a.to_owned(), b.to_owned()))
         .unwrap();
 
-    // Render the page as a stream
-    let renderer = ServerRenderer::<Page<ROOT>>::with_props(move || page_props);
-    let page_html = renderer.render_stream().map(Result::<_, BoxError>::Ok);
+    // A panic before the first chunk responds with the error page, after that it ends the stream
+    let mut page_html = render_page_content::<ROOT>(page_props);
+    let first_chunk = match page_html.next().await {
+        Some(Ok(chunk)) => chunk,
+        Some(Err(panic)) => std::panic::resume_unwind(Box::new(panic)),
+        None => String::new(),
+    };
+
+    let page_html =
+        stream::once(async move { Ok::<_, BoxError>(first_chunk) }).chain(page_html.map(|chunk| {
+            chunk.map_err(|panic| {
+                panic.log();
+                BoxError::from(panic)
+            })
+        }));
 
     // We chain all the produced streams together
     let html_stream = stream::once(async move {
@@ -171,6 +221,10 @@ where
                         .expect("hooks where no registered in AppData");
 
                     for on_chunk in hooks.on_chunk_render_hooks.iter() {
+                        #[cfg(feature = "tracing")]
+                        let _enter =
+                            tracing::trace_span!("hook", name = "on_chunk_render").entered();
+
                         s = on_chunk.call(s, request_context.clone())?
                     }
 
@@ -185,9 +239,56 @@ where
     })
     .map_ok(Bytes::from);
 
+    let html_stream: TryBoxStream<Bytes> = match metrics {
+        Some(metrics) => {
+            metrics.record_render(component, start.elapsed());
+            Box::pin(TimedStream::new(Box::pin(html_stream), metrics, component))
+        }
+        None => Box::pin(html_stream),
+    };
+
+    // The time to stream the page is recorded in its own span
+    #[cfg(feature = "tracing")]
+    let html_stream = crate::trace::TracedStream::new(
+        html_stream,
+        tracing::info_span!("stream", duration_ms = tracing::field::Empty),
+    );
+
     Ok(Box::pin(html_stream))
 }
 
+/// Renders the page in the yew runtime as a stream, a panic in a component is sent as the last item.
+///
+/// yew renders the components in its own runtime, where a panic only ends the rendering.
+fn render_page_content<ROOT>(
+    page_props: PageProps,
+) -> UnboundedReceiver<Result<String, ServerPanic>>
+where
+    ROOT: BaseComponent<Properties = ChildrenProps>,
+{
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    let create_task = move || async move {
+        let renderer = LocalServerRenderer::<Page<ROOT>>::with_props(page_props);
+        let mut page_html = Box::pin(AssertUnwindSafe(renderer.render_stream()).catch_unwind());
+
+        while let Some(chunk) = page_html.next().await {
+            // The location of the panic is captured in this thread
+            let chunk = chunk.map_err(|payload| ServerPanic::from_payload(&*payload, None));
+
+            if tx.unbounded_send(chunk).is_err() {
+                break;
+            }
+        }
+    };
+
+    match LocalHandle::try_current() {
+        Some(handle) => handle.spawn_local(create_task()),
+        None => Runtime::default().spawn_pinned(create_task),
+    }
+
+    rx
+}
+
 /// Renders the given component inside the given root as a html string.
 pub(crate) async fn render_page_to_html<COMP, ROOT>(
     props: COMP::Properties,
@@ -0,0 +1,76 @@
+use crate::web::{Request, RequestId, RequestStart, Response};
+use futures::Stream;
+use pin_project_lite::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tracing::{field::Empty, Span};
+
+/// Creates the span of a request, the `route`, `status` and `latency_ms` are recorded later.
+pub(crate) fn request_span<B>(req: &Request<B>, request_id: &RequestId) -> Span {
+    tracing::info_span!(
+        "request",
+        method = %req.method(),
+        path = %req.uri().path(),
+        route = Empty,
+        status = Empty,
+        request_id = %request_id,
+        latency_ms = Empty,
+    )
+}
+
+/// Records the route pattern that matched the request, in the span of the request.
+pub(crate) fn record_route(req: &Request<()>, route: &str) {
+    if let Some(span) = req.extensions().get::<Span>() {
+        span.record("route", route);
+    }
+}
+
+/// Records the status of the response and the time to process the request.
+pub(crate) fn record_response(span: &Span, res: &Response, start: RequestStart) {
+    span.record("status", res.status().as_u16());
+    span.record("latency_ms", elapsed_ms(start.instant()));
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+pin_project! {
+    /// A stream that polls inside a span and records the `duration_ms` when completes.
+    pub(crate) struct TracedStream<S> {
+        #[pin]
+        stream: S,
+        span: Span,
+        start: Option<Instant>,
+    }
+}
+
+impl<S> TracedStream<S> {
+    pub fn new(stream: S, span: Span) -> Self {
+        TracedStream {
+            stream,
+            span,
+            start: None,
+        }
+    }
+}
+
+impl<S: Stream> Stream for TracedStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let _enter = this.span.enter();
+        let start = *this.start.get_or_insert_with(Instant::now);
+
+        let ret = this.stream.poll_next(cx);
+        if let Poll::Ready(None) = ret {
+            this.span.record("duration_ms", elapsed_ms(start));
+        }
+
+        ret
+    }
+}
@@ -32,6 +32,7 @@ pub async fn fetch_json<S: serde::de::DeserializeOwned>(
 
 #[cfg(target_arch = "wasm32")]
 async fn get_response_error(resp: web_sys::Response) -> crate::error::BoxError {
+    use crate::web::ValidationErrors;
     use wasm_bindgen_futures::JsFuture;
     debug_assert!(!resp.ok());
 
@@ -42,11 +43,37 @@ async fn get_response_error(resp: web_sys::Response) -> crate::error::BoxError {
         .flatten()
         .unwrap_or_default();
 
+    // Problem details are returned as is, so the error can be deserialized
+    if content_type.starts_with(crate::error::PROBLEM_JSON) {
+        let text = resp.text().unwrap();
+        let text = JsFuture::from(text).await.unwrap();
+        let text = text.as_string().unwrap_or_default();
+
+        return match serde_json::from_str::<crate::error::ProblemDetails>(&text) {
+            Ok(problem) => problem.into(),
+            Err(err) => err.to_string().into(),
+        };
+    }
+
     let error_message = match content_type.as_str() {
         "application/json" => {
             let json = resp.json().unwrap();
             let json = JsFuture::from(json).await.unwrap();
-            serde_wasm_bindgen::from_value::<String>(json).unwrap()
+
+            // Validation errors are returned as is, so the fields can display them
+            if resp.status() == 422 {
+                if let Ok(errors) =
+                    serde_wasm_bindgen::from_value::<ValidationErrors>(json.clone())
+                {
+                    return errors.into();
+                }
+            }
+
+            match serde_wasm_bindgen::from_value::<serde_json::Value>(json) {
+                Ok(serde_json::Value::String(s)) => s,
+                Ok(value) => value.to_string(),
+                Err(err) => err.to_string(),
+            }
         }
         _ => {
             let text = resp.text().unwrap();
@@ -1,6 +1,6 @@
 use super::{Body, Response, ResponseExt};
 use crate::{
-    error::{BoxError, ServerError},
+    error::{BoxError, ErrorSource, ProblemDetails, ServerError},
     types::TryBoxStream,
 };
 use bytes::Bytes;
@@ -164,11 +164,25 @@ impl IntoResponse for BoxError {
             return err.into_response();
         }
 
-        Response::builder()
+        let mut res = Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
             .header(header::CONTENT_TYPE, mime::TEXT_PLAIN_UTF_8.essence_str())
             .body(Body::from(self.to_string()))
-            .unwrap()
+            .unwrap();
+
+        let problem = ProblemDetails::new(StatusCode::INTERNAL_SERVER_ERROR).with_detail(self.to_string());
+        res.extensions_mut().insert(problem);
+
+        // The error overlay displays the sources of the error
+        if cfg!(debug_assertions) {
+            let chain = crate::app::error_overlay::ErrorChain::new(self.as_ref());
+            res.extensions_mut().insert(chain);
+        }
+
+        let source = ErrorSource(std::sync::Arc::from(self));
+        res.extensions_mut().insert(source);
+
+        res
     }
 }
 
@@ -5,6 +5,7 @@ mod body;
 mod from_request;
 mod into_json;
 mod into_response;
+mod rejection;
 mod request_ext;
 mod response_ext;
 mod types;
@@ -12,6 +13,7 @@ mod types;
 pub use from_request::*;
 pub use into_json::*;
 pub use into_response::*;
+pub use rejection::*;
 pub use request_ext::*;
 pub use response_ext::*;
 pub use types::*;
@@ -0,0 +1,674 @@
+use super::{
+    types::utils::ContentTypeError, DataNotFoundError, IntoResponse, LengthLimitError, Response,
+};
+use crate::{
+    app::RequestContext,
+    error::{BoxError, ProblemDetails, ServerError},
+};
+use http::StatusCode;
+use serde::{Deserialize, Deserializer};
+use std::{fmt::Display, string::FromUtf8Error, sync::Arc};
+use thiserror::Error;
+
+pub use super::types::utils::InvalidContentType;
+
+/// The status code and field path of a typed rejection.
+trait RejectionInfo: std::error::Error {
+    fn status(&self) -> StatusCode;
+
+    fn path(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// The rejection of the [`Bytes`] extractor.
+///
+/// [`Bytes`]: crate::web::Bytes
+#[derive(Debug, Error)]
+pub enum BytesRejection {
+    /// Failed to read the request body.
+    #[error("failed to read body: {0}")]
+    FailedToReadBody(BoxError),
+
+    /// The body exceeded the [`BodyLimit`].
+    ///
+    /// [`BodyLimit`]: crate::web::BodyLimit
+    #[error("body exceeded the limit of {limit} bytes")]
+    PayloadTooLarge {
+        /// The limit in bytes.
+        limit: usize,
+    },
+}
+
+impl BytesRejection {
+    /// Constructs a rejection from an error returned while reading the body.
+    pub(crate) fn from_body_error(err: BoxError) -> Self {
+        match err.downcast::<LengthLimitError>() {
+            Ok(err) => BytesRejection::PayloadTooLarge { limit: err.limit },
+            Err(err) => BytesRejection::FailedToReadBody(err),
+        }
+    }
+}
+
+impl From<LengthLimitError> for BytesRejection {
+    fn from(err: LengthLimitError) -> Self {
+        BytesRejection::PayloadTooLarge { limit: err.limit }
+    }
+}
+
+impl RejectionInfo for BytesRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            BytesRejection::FailedToReadBody(_) => StatusCode::BAD_REQUEST,
+            BytesRejection::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
+/// The rejection of the [`String`] extractor.
+#[derive(Debug, Error)]
+pub enum StringRejection {
+    /// Failed to read the request body.
+    #[error(transparent)]
+    Body(#[from] BytesRejection),
+
+    /// The body is not valid UTF-8.
+    #[error("body is not valid utf-8: {0}")]
+    InvalidUtf8(#[from] FromUtf8Error),
+}
+
+impl RejectionInfo for StringRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            StringRejection::Body(err) => err.status(),
+            StringRejection::InvalidUtf8(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// The rejection of the [`Json`] extractor.
+///
+/// [`Json`]: crate::web::Json
+#[derive(Debug, Error)]
+pub enum JsonRejection {
+    /// The request don't have a `Content-Type` header.
+    #[error("expected content type `application/json`")]
+    MissingContentType,
+
+    /// The content type is not `application/json`.
+    #[error(transparent)]
+    InvalidContentType(Box<InvalidContentType>),
+
+    /// Failed to read the request body.
+    #[error(transparent)]
+    Body(#[from] BytesRejection),
+
+    /// The body is not valid json.
+    #[error("failed to parse json: {0}")]
+    Syntax(serde_json::Error),
+
+    /// The json don't match the expected type.
+    #[error("failed to deserialize json: {source}")]
+    Data {
+        /// The path to the field that failed.
+        path: Option<String>,
+
+        /// The deserialization error.
+        source: serde_json::Error,
+    },
+}
+
+impl From<ContentTypeError> for JsonRejection {
+    fn from(err: ContentTypeError) -> Self {
+        match err {
+            ContentTypeError::NoContentType => JsonRejection::MissingContentType,
+            ContentTypeError::InvalidContentType(err) => JsonRejection::InvalidContentType(err),
+        }
+    }
+}
+
+impl RejectionInfo for JsonRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            JsonRejection::MissingContentType | JsonRejection::InvalidContentType(_) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            JsonRejection::Body(err) => err.status(),
+            JsonRejection::Syntax(_) => StatusCode::BAD_REQUEST,
+            JsonRejection::Data { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn path(&self) -> Option<&str> {
+        match self {
+            JsonRejection::Data { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// The rejection of the [`Form`] extractor.
+///
+/// [`Form`]: crate::web::Form
+#[derive(Debug, Error)]
+pub enum FormRejection {
+    /// The request don't have a `Content-Type` header.
+    #[error("expected content type `application/x-www-form-urlencoded`")]
+    MissingContentType,
+
+    /// The content type is not `application/x-www-form-urlencoded`.
+    #[error(transparent)]
+    InvalidContentType(Box<InvalidContentType>),
+
+    /// The uri don't have a query string, for `GET` and `HEAD` requests.
+    #[error("uri query not found")]
+    MissingQuery,
+
+    /// Failed to read the request body.
+    #[error(transparent)]
+    Body(#[from] BytesRejection),
+
+    /// The form don't match the expected type.
+    #[error("failed to deserialize form: {source}")]
+    Deserialize {
+        /// The path to the field that failed.
+        path: Option<String>,
+
+        /// The deserialization error.
+        source: serde_urlencoded::de::Error,
+    },
+}
+
+impl From<ContentTypeError> for FormRejection {
+    fn from(err: ContentTypeError) -> Self {
+        match err {
+            ContentTypeError::NoContentType => FormRejection::MissingContentType,
+            ContentTypeError::InvalidContentType(err) => FormRejection::InvalidContentType(err),
+        }
+    }
+}
+
+impl RejectionInfo for FormRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            FormRejection::MissingContentType | FormRejection::InvalidContentType(_) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            FormRejection::MissingQuery => StatusCode::BAD_REQUEST,
+            FormRejection::Body(err) => err.status(),
+            FormRejection::Deserialize { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn path(&self) -> Option<&str> {
+        match self {
+            FormRejection::Deserialize { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// The rejection of the [`Query`] extractor.
+///
+/// [`Query`]: crate::web::Query
+#[derive(Debug, Error)]
+pub enum QueryRejection {
+    /// The uri don't have a query string.
+    #[error("url does not contain a query string")]
+    MissingQuery,
+
+    /// The query don't match the expected type.
+    #[error("failed to deserialize query: {source}")]
+    Deserialize {
+        /// The path to the field that failed.
+        path: Option<String>,
+
+        /// The deserialization error.
+        source: serde_qs::Error,
+    },
+}
+
+impl RejectionInfo for QueryRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            QueryRejection::MissingQuery => StatusCode::BAD_REQUEST,
+            QueryRejection::Deserialize { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn path(&self) -> Option<&str> {
+        match self {
+            QueryRejection::Deserialize { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// The rejection of the [`Multipart`] extractor.
+///
+/// [`Multipart`]: crate::web::Multipart
+#[derive(Debug, Error)]
+pub enum MultipartRejection {
+    /// The request don't have a `Content-Type` header.
+    #[error("content type was not specified")]
+    MissingContentType,
+
+    /// The content type is not a valid multipart content type.
+    #[error("invalid multipart content type: {0}")]
+    InvalidBoundary(BoxError),
+
+    /// Failed to read the request body.
+    #[error(transparent)]
+    Body(#[from] BytesRejection),
+
+    /// The body is not a valid multipart.
+    #[error("failed to parse multipart: {0}")]
+    Parse(BoxError),
+
+    /// The multipart don't match the expected type.
+    #[error("failed to deserialize multipart: {0}")]
+    Deserialize(multer_derive::Error),
+
+    /// The body exceeded the size limit.
+    #[error("multipart body exceeded the limit of {limit} bytes")]
+    PayloadTooLarge {
+        /// The limit in bytes.
+        limit: u64,
+    },
+
+    /// A field exceeded the size limit.
+    #[error("multipart field exceeded the limit of {limit} bytes")]
+    FieldTooLarge {
+        /// The limit in bytes.
+        limit: u64,
+
+        /// The name of the field.
+        field_name: Option<String>,
+    },
+
+    /// Failed to write a file to disk.
+    #[error("failed to write multipart file: {0}")]
+    Io(std::io::Error),
+}
+
+impl RejectionInfo for MultipartRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            MultipartRejection::MissingContentType | MultipartRejection::InvalidBoundary(_) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            MultipartRejection::Body(err) => err.status(),
+            MultipartRejection::Parse(_) => StatusCode::BAD_REQUEST,
+            MultipartRejection::Deserialize(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            MultipartRejection::PayloadTooLarge { .. }
+            | MultipartRejection::FieldTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            MultipartRejection::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn path(&self) -> Option<&str> {
+        match self {
+            MultipartRejection::FieldTooLarge { field_name, .. } => field_name.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// The rejection of the [`WebSocketUpgrade`] extractor.
+///
+/// [`WebSocketUpgrade`]: crate::web::WebSocketUpgrade
+#[derive(Debug, Error)]
+pub enum WebSocketUpgradeRejection {
+    /// The request method is not `GET`.
+    #[error("websocket upgrade request method must be `GET`")]
+    MethodNotGet,
+
+    /// The `Connection` header don't contains `upgrade`.
+    #[error("`Connection` header must contain `upgrade`")]
+    InvalidConnectionHeader,
+
+    /// The `Upgrade` header is not `websocket`.
+    #[error("`Upgrade` header must be `websocket`")]
+    InvalidUpgradeHeader,
+
+    /// The `Sec-WebSocket-Version` header is not `13`.
+    #[error("`Sec-WebSocket-Version` header must be `13`")]
+    InvalidWebSocketVersion,
+
+    /// The request don't have a `Sec-WebSocket-Key` header.
+    #[error("`Sec-WebSocket-Key` header is missing")]
+    MissingWebSocketKey,
+}
+
+impl RejectionInfo for WebSocketUpgradeRejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            WebSocketUpgradeRejection::MethodNotGet => StatusCode::METHOD_NOT_ALLOWED,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// The rejection of the [`Flash`] extractor.
+///
+/// [`Flash`]: crate::flash::Flash
+#[derive(Debug, Error)]
+pub enum FlashRejection {
+    /// There is no [`Key`] in the `app_data` to sign the flash messages cookie.
+    ///
+    /// [`Key`]: crate::web::Key
+    #[error("flash messages require a `cookie::Key` in the app data to sign the cookie")]
+    MissingKey,
+}
+
+impl RejectionInfo for FlashRejection {
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl RejectionInfo for DataNotFoundError {
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// An error returned by one of the built-in extractors.
+///
+/// By default is returned as `application/problem+json` in the form
+/// `{ "type": "about:blank", "title": "Unprocessable Entity", "status": 422, "detail": "...", "path": "field" }`,
+/// use [`App::rejection_handler`] to customize how is rendered.
+///
+/// The problem details replace the `RejectionBody` json returned before, which was removed,
+/// the path of the field is now the `path` extension of [`Rejection::problem_details`].
+///
+/// [`App::rejection_handler`]: crate::app::App::rejection_handler
+#[derive(Debug)]
+pub struct Rejection {
+    status: StatusCode,
+    path: Option<String>,
+    error: BoxError,
+}
+
+impl Rejection {
+    /// Attempts to convert the error returned by an extractor into a rejection.
+    pub fn from_error(error: BoxError) -> Result<Self, BoxError> {
+        macro_rules! try_downcast {
+            ($($rejection:ty),*) => {
+                $(
+                    if let Some(rejection) = error.downcast_ref::<$rejection>() {
+                        return Ok(Rejection {
+                            status: rejection.status(),
+                            path: rejection.path().map(ToOwned::to_owned),
+                            error,
+                        });
+                    }
+                )*
+            };
+        }
+
+        try_downcast!(
+            BytesRejection,
+            StringRejection,
+            JsonRejection,
+            FormRejection,
+            QueryRejection,
+            MultipartRejection,
+            WebSocketUpgradeRejection,
+            FlashRejection,
+            DataNotFoundError
+        );
+
+        Err(error)
+    }
+
+    /// Returns the status code of the rejection.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the path of the field that failed to deserialize, if any.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> String {
+        self.error.to_string()
+    }
+
+    /// Returns the rejection error, which can be downcasted to the typed rejection
+    /// of the extractor, for example a [`JsonRejection`].
+    pub fn error(&self) -> &BoxError {
+        &self.error
+    }
+
+    /// Returns the problem details of the rejection, the path of the field
+    /// that failed to deserialize is added as the `path` extension.
+    pub fn problem_details(&self) -> ProblemDetails {
+        let problem = ProblemDetails::new(self.status).with_detail(self.message());
+
+        match &self.path {
+            Some(path) => problem.with_extension("path", path),
+            None => problem,
+        }
+    }
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        self.problem_details().into_response()
+    }
+}
+
+type RejectionHandlerFn = dyn Fn(Rejection, &RequestContext) -> Response + Send + Sync;
+
+/// Renders the rejections of the extractors, stored in the `AppData`.
+#[derive(Clone)]
+pub(crate) struct RejectionHandler(pub(crate) Arc<RejectionHandlerFn>);
+
+/// Returns the response for an error returned by the extractors of a handler.
+pub(crate) fn rejection_response(ctx: &RequestContext, error: BoxError) -> Response {
+    match Rejection::from_error(error) {
+        Ok(rejection) => match ctx.app_data::<RejectionHandler>() {
+            Some(handler) => (handler.0)(rejection, ctx),
+            None => rejection.into_response(),
+        },
+        Err(error) => ServerError::from_error(error).into_response(),
+    }
+}
+
+/// Deserializes a value returning the path of the field that failed, if any.
+pub(crate) fn deserialize_with_path<'de, T, D>(
+    deserializer: D,
+) -> Result<T, (D::Error, Option<String>)>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        // The root path is displayed as `.`
+        let path = Some(err.path().to_string()).filter(|path| path != ".");
+        (err.into_inner(), path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize_with_path, Rejection};
+    use crate::{
+        app::App,
+        error::{ProblemDetails, PROBLEM_JSON},
+        routing::Route,
+        web::{Body, Inject, IntoResponse, Json, Query, Request, Response},
+    };
+    use http::{header, Method, StatusCode};
+    use serde::Deserialize;
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct User {
+        name: String,
+        address: Address,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Address {
+        zip: u32,
+    }
+
+    #[test]
+    fn deserialize_with_path_test() {
+        let json = r#"{ "name": "Ferris", "address": { "zip": "none" } }"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let (_, path) = deserialize_with_path::<User, _>(&mut de).unwrap_err();
+        assert_eq!(path.as_deref(), Some("address.zip"));
+
+        let mut de = serde_json::Deserializer::from_str("[]");
+        let (_, path) = deserialize_with_path::<User, _>(&mut de).unwrap_err();
+        assert_eq!(path, None);
+    }
+
+    async fn into_problem(res: Response) -> ProblemDetails {
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON
+        );
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn json_req(content_type: &str, body: &'static str) -> Request {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/users")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejection_status_test() {
+        let service = App::<Base>::new()
+            .route(Route::post("/users", |_: Json<User>| async { "ok" }))
+            .route(Route::get("/search", |_: Query<Address>| async { "ok" }))
+            .route(Route::get("/data", |_: Inject<u64>| async { "ok" }))
+            .build();
+
+        let res = service.handle(json_req("text/plain", "{}")).await;
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let res = service.handle(json_req("application/json", "{")).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = service
+            .handle(json_req(
+                "application/json",
+                r#"{ "name": "Ferris", "address": { "zip": true } }"#,
+            ))
+            .await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let problem = into_problem(res).await;
+        assert_eq!(problem.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.extension("path").unwrap(), "address.zip");
+
+        let req = Request::builder()
+            .uri("/search")
+            .body(Body::empty())
+            .unwrap();
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = Request::builder()
+            .uri("/search?zip=abc")
+            .body(Body::empty())
+            .unwrap();
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(into_problem(res).await.extension("path").unwrap(), "zip");
+
+        let req = Request::builder().uri("/data").body(Body::empty()).unwrap();
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[cfg(not(feature = "client"))]
+    #[tokio::test]
+    async fn nested_action_rejection_test() {
+        use crate::{
+            actions::{call_action, Action},
+            app::{nested, RequestContext},
+            types::BoxFuture,
+        };
+
+        struct CreateUser;
+
+        impl Action for CreateUser {
+            type Response = String;
+
+            fn route() -> &'static str {
+                "/api/users"
+            }
+
+            fn call(ctx: RequestContext, body: Body) -> BoxFuture<crate::Result<String>> {
+                Box::pin(call_action(ctx, body, |user: Json<User>| async move {
+                    user.into_inner().name
+                }))
+            }
+        }
+
+        let service = App::<Base>::new()
+            .nest("/api", nested().action::<CreateUser>())
+            .build();
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/api/users")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(r#"{ "name": "Ferris", "address": { "zip": true } }"#.into())
+            .unwrap();
+
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let problem = into_problem(res).await;
+        assert_eq!(problem.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.extension("path").unwrap(), "address.zip");
+    }
+
+    #[tokio::test]
+    async fn rejection_handler_test() {
+        let service = App::<Base>::new()
+            .rejection_handler(|rejection: Rejection, _| {
+                (
+                    rejection.status(),
+                    format!("custom: {}", rejection.status()),
+                )
+                    .into_response()
+            })
+            .route(Route::post("/users", |_: Json<User>| async { "ok" }))
+            .build();
+
+        let res = service.handle(json_req("text/plain", "{}")).await;
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "custom: 415 Unsupported Media Type");
+    }
+}
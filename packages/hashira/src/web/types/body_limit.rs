@@ -0,0 +1,110 @@
+use crate::{app::RequestContext, types::TryBoxStream, web::Payload};
+use bytes::Bytes;
+use futures::StreamExt;
+use http::header;
+use thiserror::Error;
+
+/// Default max size of a request body: 2 MiB.
+const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// The max size in bytes of a request body read by the extractors, like `Bytes`, `String`,
+/// `Json`, `Form` and [`BodyStream`], defaults to 2 MiB.
+///
+/// It can be set for all the routes with [`App::body_limit`], for a single route with
+/// [`Route::body_limit`] or for an action with `#[action("/path", body_limit = "1024 * 1024")]`.
+///
+/// The `Multipart` extractor uses the limits of the `MultipartConfig` instead.
+///
+/// [`BodyStream`]: crate::web::BodyStream
+/// [`App::body_limit`]: crate::app::App::body_limit
+/// [`Route::body_limit`]: crate::routing::Route::body_limit
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BodyLimit(Option<usize>);
+
+impl BodyLimit {
+    /// Constructs a limit of the given bytes.
+    pub fn new(limit: usize) -> Self {
+        BodyLimit(Some(limit))
+    }
+
+    /// Constructs a limit that allow a body of any size.
+    pub fn unlimited() -> Self {
+        BodyLimit(None)
+    }
+
+    /// Returns the limit in bytes, if any.
+    pub fn get(&self) -> Option<usize> {
+        self.0
+    }
+
+    /// Returns the limit for the current request.
+    pub(crate) fn from_request(ctx: &RequestContext) -> Self {
+        ctx.request()
+            .extensions()
+            .get::<BodyLimit>()
+            .or_else(|| ctx.app_data::<BodyLimit>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns an error if the `Content-Length` of the request exceeds the limit.
+    pub(crate) fn check_content_length(
+        &self,
+        ctx: &RequestContext,
+    ) -> Result<(), LengthLimitError> {
+        let Some(limit) = self.0 else {
+            return Ok(());
+        };
+
+        let content_length = ctx
+            .request()
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.parse::<usize>().ok());
+
+        match content_length {
+            Some(len) if len > limit => Err(LengthLimitError { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a stream of the payload which fails with a [`LengthLimitError`] if exceeds the limit.
+    pub(crate) fn limit_payload(&self, payload: Payload) -> TryBoxStream<Bytes> {
+        let Some(limit) = self.0 else {
+            return payload.into_stream();
+        };
+
+        let mut read = 0;
+        Box::pin(payload.into_stream().map(move |chunk| {
+            let chunk = chunk?;
+            read += chunk.len();
+
+            if read > limit {
+                return Err(LengthLimitError { limit }.into());
+            }
+
+            Ok(chunk)
+        }))
+    }
+}
+
+impl Default for BodyLimit {
+    fn default() -> Self {
+        BodyLimit::new(DEFAULT_BODY_LIMIT)
+    }
+}
+
+impl From<usize> for BodyLimit {
+    fn from(limit: usize) -> Self {
+        BodyLimit::new(limit)
+    }
+}
+
+/// The request body exceeded the [`BodyLimit`].
+#[derive(Debug, Error)]
+#[error("body exceeded the limit of {limit} bytes")]
+pub struct LengthLimitError {
+    /// The limit in bytes.
+    pub limit: usize,
+}
@@ -0,0 +1,168 @@
+use crate::{
+    app::RequestContext,
+    error::BoxError,
+    types::TryBoxStream,
+    web::{Body, BodyLimit, BytesRejection, FromRequest},
+};
+use bytes::Bytes;
+use futures::Stream;
+use std::{
+    future::{ready, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A stream of the chunks of the request body.
+///
+/// The stream fails with a [`LengthLimitError`] if the body exceeds the [`BodyLimit`].
+///
+/// [`LengthLimitError`]: crate::web::LengthLimitError
+pub struct BodyStream(TryBoxStream<Bytes>);
+
+impl BodyStream {
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> TryBoxStream<Bytes> {
+        self.0
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx)
+    }
+}
+
+impl FromRequest for BodyStream {
+    type Error = BytesRejection;
+    type Fut = Ready<Result<BodyStream, BytesRejection>>;
+
+    fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
+        let limit = BodyLimit::from_request(ctx);
+
+        if let Err(err) = limit.check_content_length(ctx) {
+            return ready(Err(err.into()));
+        }
+
+        let stream = match body.take() {
+            Some(payload) => limit.limit_payload(payload),
+            None => Box::pin(futures::stream::empty()),
+        };
+
+        ready(Ok(BodyStream(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BodyStream;
+    use crate::{
+        app::App,
+        routing::Route,
+        web::{Body, BodyLimit, Bytes, LengthLimitError, Request},
+    };
+    use futures::StreamExt;
+    use http::{header, Method, StatusCode};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn create_req(path: &str, body: Body, content_length: Option<usize>) -> Request {
+        let mut builder = Request::builder().method(Method::POST).uri(path);
+        if let Some(len) = content_length {
+            builder = builder.header(header::CONTENT_LENGTH, len);
+        }
+
+        builder.body(body).unwrap()
+    }
+
+    fn stream_body(chunks: usize) -> Body {
+        let (tx, body) = Body::channel();
+        for _ in 0..chunks {
+            tx.send(Ok(Bytes::from_static(b"0123456789"))).unwrap();
+        }
+
+        body
+    }
+
+    #[tokio::test]
+    async fn body_limit_test() {
+        let service = App::<Base>::new()
+            .body_limit(16)
+            .route(Route::post("/bytes", |bytes: Bytes| async move {
+                bytes.len().to_string()
+            }))
+            .route(
+                Route::post(
+                    "/large",
+                    |bytes: Bytes| async move { bytes.len().to_string() },
+                )
+                .body_limit(BodyLimit::unlimited()),
+            )
+            .build();
+
+        let res = service
+            .handle(create_req("/bytes", Body::from("small"), None))
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // Rejected by the `Content-Length`
+        let res = service
+            .handle(create_req("/bytes", Body::empty(), Some(1024)))
+            .await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Rejected while reading
+        let res = service
+            .handle(create_req("/bytes", stream_body(3), None))
+            .await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let res = service
+            .handle(create_req("/large", Body::from("a".repeat(1024)), None))
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn body_stream_test() {
+        let service = App::<Base>::new()
+            .body_limit(25)
+            .route(Route::post(
+                "/stream",
+                |mut stream: BodyStream| async move {
+                    let mut len = 0;
+                    while let Some(chunk) = stream.next().await {
+                        match chunk {
+                            Ok(chunk) => len += chunk.len(),
+                            Err(err) => {
+                                assert!(err.is::<LengthLimitError>());
+                                return "too large".to_owned();
+                            }
+                        }
+                    }
+
+                    len.to_string()
+                },
+            ))
+            .build();
+
+        let res = service
+            .handle(create_req("/stream", stream_body(2), None))
+            .await;
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "20");
+
+        let res = service
+            .handle(create_req("/stream", stream_body(3), None))
+            .await;
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "too large");
+    }
+}
@@ -1,21 +1,46 @@
 use crate::{
     app::RequestContext,
-    error::BoxError,
     types::BoxFuture,
-    web::{Body, FromRequest, IntoResponse, Response},
+    web::{Body, BodyLimit, BytesRejection, FromRequest, IntoResponse, Payload, Response},
 };
-use bytes::{Bytes, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::StreamExt;
 use http::header;
 
 impl FromRequest for Bytes {
-    type Error = BoxError;
+    type Error = BytesRejection;
     type Fut = BoxFuture<Result<Bytes, Self::Error>>;
 
-    fn from_request(_ctx: &RequestContext, body: &mut Body) -> Self::Fut {
-        let body = std::mem::take(body);
+    fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
+        let limit = BodyLimit::from_request(ctx);
+        let content_length = limit.check_content_length(ctx);
+        let mut body = std::mem::take(body);
+
         Box::pin(async move {
-            let bytes = body.into_bytes().await?;
-            Ok(bytes)
+            content_length?;
+
+            let Some(payload) = body.take() else {
+                return Ok(Bytes::new());
+            };
+
+            match payload {
+                Payload::Bytes(bytes) => match limit.get() {
+                    Some(limit) if bytes.len() > limit => {
+                        Err(BytesRejection::PayloadTooLarge { limit })
+                    }
+                    _ => Ok(bytes),
+                },
+                Payload::Stream(_) => {
+                    let mut stream = limit.limit_payload(payload);
+                    let mut collector = BytesMut::new();
+
+                    while let Some(chunk) = stream.next().await {
+                        collector.put(chunk.map_err(BytesRejection::from_body_error)?);
+                    }
+
+                    Ok(collector.freeze())
+                }
+            }
         })
     }
 }
@@ -0,0 +1,253 @@
+use crate::{
+    app::RequestContext,
+    web::{Body, FromRequest, Request, RequestExt, Response},
+};
+use cookie::Cookie;
+use http::{header, HeaderValue};
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+pub use cookie::Key;
+
+/// The cookies of the current request.
+///
+/// Any cookie added or removed from this jar, including the `signed` and `private` views,
+/// will be written to the response as a `Set-Cookie` header.
+///
+/// The signed and private views use the [`Key`] registered in the `app_data`.
+#[derive(Clone)]
+pub struct CookieJar {
+    jar: Arc<Mutex<cookie::CookieJar>>,
+    key: Option<Key>,
+}
+
+impl CookieJar {
+    /// Constructs a jar containing the cookies of the given request.
+    pub(crate) fn from_request<B>(req: &Request<B>, key: Option<Key>) -> Self {
+        let mut jar = cookie::CookieJar::new();
+
+        match req.cookies() {
+            Ok(cookies) => {
+                for cookie in cookies {
+                    jar.add_original(cookie);
+                }
+            }
+            Err(err) => {
+                log::warn!("failed to parse request cookies: {err}");
+            }
+        }
+
+        CookieJar {
+            jar: Arc::new(Mutex::new(jar)),
+            key,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, cookie::CookieJar> {
+        self.jar.lock().expect("failed to lock cookie jar")
+    }
+
+    /// Returns the cookie with the given name.
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        self.lock().get(name).cloned()
+    }
+
+    /// Adds a cookie, this will be sent in the response.
+    pub fn add(&self, cookie: Cookie<'static>) {
+        self.lock().add(cookie);
+    }
+
+    /// Removes a cookie, a removal cookie will be sent in the response.
+    pub fn remove(&self, cookie: Cookie<'static>) {
+        self.lock().remove(cookie);
+    }
+
+    /// Returns all the cookies in the jar.
+    pub fn cookies(&self) -> Vec<Cookie<'static>> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Returns a view of this jar which sign and verify the cookies.
+    ///
+    /// # Panics
+    /// If there is no [`Key`] in the `app_data`.
+    pub fn signed(&self) -> SignedCookieJar {
+        SignedCookieJar {
+            jar: self.clone(),
+            key: self.expect_key(),
+        }
+    }
+
+    /// Returns a view of this jar which encrypt and decrypt the cookies.
+    ///
+    /// # Panics
+    /// If there is no [`Key`] in the `app_data`.
+    pub fn private(&self) -> PrivateCookieJar {
+        PrivateCookieJar {
+            jar: self.clone(),
+            key: self.expect_key(),
+        }
+    }
+
+    /// Returns `true` if the jar have a [`Key`] for the signed and private views.
+    pub(crate) fn has_key(&self) -> bool {
+        self.key.is_some()
+    }
+
+    fn expect_key(&self) -> Key {
+        self.key
+            .clone()
+            .expect("`cookie::Key` was not found in app data")
+    }
+
+    /// Appends the changes made to this jar to the response.
+    pub(crate) fn write_to_response(&self, res: &mut Response) {
+        let jar = self.lock();
+
+        // The request cookies are decoded, so we also encode them
+        for cookie in jar.delta() {
+            match HeaderValue::from_str(&cookie.encoded().to_string()) {
+                Ok(value) => {
+                    res.headers_mut().append(header::SET_COOKIE, value);
+                }
+                Err(err) => {
+                    log::error!("failed to set cookie `{}`: {err}", cookie.name());
+                }
+            }
+        }
+    }
+}
+
+/// A view of a [`CookieJar`] where the cookies are signed to prevent tampering.
+#[derive(Clone)]
+pub struct SignedCookieJar {
+    jar: CookieJar,
+    key: Key,
+}
+
+impl SignedCookieJar {
+    /// Returns the cookie with the given name if its signature is valid.
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        self.jar.lock().signed(&self.key).get(name)
+    }
+
+    /// Signs and adds a cookie, this will be sent in the response.
+    pub fn add(&self, cookie: Cookie<'static>) {
+        self.jar.lock().signed_mut(&self.key).add(cookie);
+    }
+
+    /// Removes a cookie, a removal cookie will be sent in the response.
+    pub fn remove(&self, cookie: Cookie<'static>) {
+        self.jar.lock().signed_mut(&self.key).remove(cookie);
+    }
+}
+
+/// A view of a [`CookieJar`] where the cookies are encrypted and authenticated.
+#[derive(Clone)]
+pub struct PrivateCookieJar {
+    jar: CookieJar,
+    key: Key,
+}
+
+impl PrivateCookieJar {
+    /// Returns the decrypted cookie with the given name if it is valid.
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        self.jar.lock().private(&self.key).get(name)
+    }
+
+    /// Encrypts and adds a cookie, this will be sent in the response.
+    pub fn add(&self, cookie: Cookie<'static>) {
+        self.jar.lock().private_mut(&self.key).add(cookie);
+    }
+
+    /// Removes a cookie, a removal cookie will be sent in the response.
+    pub fn remove(&self, cookie: Cookie<'static>) {
+        self.jar.lock().private_mut(&self.key).remove(cookie);
+    }
+}
+
+impl FromRequest for CookieJar {
+    type Error = Infallible;
+    type Fut = Ready<Result<CookieJar, Infallible>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        ready(Ok(ctx.cookies()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CookieJar, Key};
+    use crate::web::{Request, Response};
+    use cookie::Cookie;
+    use http::header;
+
+    #[test]
+    fn cookie_jar_delta_test() {
+        let req = Request::builder()
+            .header(header::COOKIE, "color=red; fruit=apple")
+            .body(())
+            .unwrap();
+
+        let jar = CookieJar::from_request(&req, None);
+        assert_eq!(jar.get("color").unwrap().value(), "red");
+        assert_eq!(jar.get("fruit").unwrap().value(), "apple");
+
+        jar.add(Cookie::new("number", "12"));
+        jar.remove(Cookie::named("fruit"));
+
+        let mut res = Response::default();
+        jar.write_to_response(&mut res);
+
+        let set_cookies = res
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|x| Cookie::parse(x.to_str().unwrap().to_owned()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(set_cookies.len(), 2);
+        assert!(set_cookies
+            .iter()
+            .any(|c| c.name() == "number" && c.value() == "12"));
+        assert!(set_cookies
+            .iter()
+            .any(|c| c.name() == "fruit" && c.value().is_empty()));
+    }
+
+    #[test]
+    fn signed_and_private_cookie_test() {
+        let key = Key::generate();
+        let jar = CookieJar::from_request(&Request::new(()), Some(key.clone()));
+
+        jar.signed().add(Cookie::new("signed", "hello"));
+        jar.private().add(Cookie::new("private", "world"));
+
+        assert_eq!(jar.signed().get("signed").unwrap().value(), "hello");
+        assert_eq!(jar.private().get("private").unwrap().value(), "world");
+
+        // The raw values are not the original
+        assert_ne!(jar.get("signed").unwrap().value(), "hello");
+        assert_ne!(jar.get("private").unwrap().value(), "world");
+
+        // Send the cookies back with a tampered value
+        let tampered = format!("signed={}x", jar.get("signed").unwrap().value());
+        let req = Request::builder()
+            .header(header::COOKIE, tampered)
+            .body(())
+            .unwrap();
+
+        let other = CookieJar::from_request(&req, Some(key));
+        assert!(other.signed().get("signed").is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn signed_without_key_test() {
+        let jar = CookieJar::from_request(&Request::new(()), None);
+        let _ = jar.signed();
+    }
+}
@@ -94,7 +94,7 @@ where
                                 buf: bytes,
                             });
                         }
-                        Err(err) => break Err(err),
+                        Err(err) => break Err(err.into()),
                     }
                 }
                 EitherExtractProj::Left { fut, buf } => {
@@ -3,13 +3,19 @@ use futures::{ready, Future, FutureExt};
 use http::{header, HeaderValue, Method};
 use pin_project_lite::pin_project;
 use serde::{de::DeserializeOwned, Serialize};
-use std::{marker::PhantomData, task::Poll};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    task::Poll,
+};
 
 use crate::{
     app::RequestContext,
-    error::{BoxError, ServerError},
-    responses,
-    web::{Body, FromRequest, IntoResponse, Request, Response},
+    error::ServerError,
+    web::{
+        rejection::deserialize_with_path, Body, FormRejection, FromRequest, IntoResponse, Request,
+        Response,
+    },
 };
 
 use super::utils::is_content_type;
@@ -25,6 +31,20 @@ impl<T> Form<T> {
     }
 }
 
+impl<T> Deref for Form<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Form<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<T> IntoResponse for Form<T>
 where
     T: Serialize,
@@ -48,7 +68,7 @@ impl<T> FromRequest for Form<T>
 where
     T: DeserializeOwned,
 {
-    type Error = BoxError;
+    type Error = FormRejection;
     type Fut = FromRequestFormFuture<T>;
 
     fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
@@ -74,7 +94,7 @@ impl<T> Future for FromRequestFormFuture<T>
 where
     T: DeserializeOwned,
 {
-    type Output = Result<Form<T>, BoxError>;
+    type Output = Result<Form<T>, FormRejection>;
 
     fn poll(
         mut self: std::pin::Pin<&mut Self>,
@@ -82,7 +102,7 @@ where
     ) -> std::task::Poll<Self::Output> {
         let req = self.ctx.request();
         if let Err(err) = is_content_type(req, mime::APPLICATION_WWW_FORM_URLENCODED) {
-            return Poll::Ready(Err(responses::unprocessable_entity(err)));
+            return Poll::Ready(Err(err.into()));
         }
 
         let request = self.ctx.request();
@@ -93,29 +113,27 @@ where
         }
 
         let mut this = self.as_mut();
-        let ret = ready!(this.fut.poll_unpin(cx));
-        
-        match ret {
-            Ok(bytes) => match serde_urlencoded::from_bytes::<T>(&bytes) {
-                Ok(x) => Poll::Ready(Ok(Form(x))),
-                Err(err) => Poll::Ready(Err(responses::unprocessable_entity(format!(
-                    "failed to deserialize form: {err}"
-                )))),
-            },
-            Err(err) => Poll::Ready(Err(responses::unprocessable_entity(err))),
+        let bytes = ready!(this.fut.poll_unpin(cx))?;
+
+        let de = serde_urlencoded::Deserializer::new(form_urlencoded::parse(&bytes));
+        match deserialize_with_path(de) {
+            Ok(x) => Poll::Ready(Ok(Form(x))),
+            Err((source, path)) => Poll::Ready(Err(FormRejection::Deserialize { path, source })),
         }
     }
 }
 
-fn parse_form_from_uri<T: DeserializeOwned>(request: &Request<()>) -> Result<Form<T>, BoxError> {
-    match request.uri().query() {
-        Some(query) => match serde_urlencoded::from_str::<T>(query) {
-            Ok(x) => Ok(Form(x)),
-            Err(err) => Err(responses::unprocessable_entity(format!(
-                "failed to deserialize uri query: {err}"
-            ))),
-        },
-        None => Err(responses::unprocessable_entity("uri query not found")),
+fn parse_form_from_uri<T: DeserializeOwned>(
+    request: &Request<()>,
+) -> Result<Form<T>, FormRejection> {
+    let Some(query) = request.uri().query() else {
+        return Err(FormRejection::MissingQuery);
+    };
+
+    let de = serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+    match deserialize_with_path(de) {
+        Ok(x) => Ok(Form(x)),
+        Err((source, path)) => Err(FormRejection::Deserialize { path, source }),
     }
 }
 
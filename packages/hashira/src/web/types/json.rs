@@ -1,15 +1,21 @@
 use crate::{
     app::RequestContext,
-    error::{BoxError, ServerError},
-    responses,
-    web::{Body, FromRequest, IntoResponse, Response},
+    error::ServerError,
+    web::{
+        rejection::deserialize_with_path, Body, FromRequest, IntoResponse, JsonRejection, Response,
+    },
 };
 use bytes::Bytes;
 use futures::{ready, Future, FutureExt};
 use http::header;
 use pin_project_lite::pin_project;
 use serde::{de::DeserializeOwned, Serialize};
-use std::{marker::PhantomData, task::Poll};
+use serde_json::error::Category;
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    task::Poll,
+};
 
 use super::utils::is_content_type;
 
@@ -23,6 +29,20 @@ impl<T> Json<T> {
     }
 }
 
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<T: Serialize> IntoResponse for Json<T> {
     fn into_response(self) -> Response {
         let json = match serde_json::to_string(&self.0) {
@@ -46,7 +66,7 @@ impl<T> FromRequest for Json<T>
 where
     T: DeserializeOwned,
 {
-    type Error = BoxError;
+    type Error = JsonRejection;
     type Fut = FromRequestJsonFuture<T>;
 
     fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
@@ -72,7 +92,7 @@ impl<T> Future for FromRequestJsonFuture<T>
 where
     T: DeserializeOwned,
 {
-    type Output = Result<Json<T>, BoxError>;
+    type Output = Result<Json<T>, JsonRejection>;
 
     fn poll(
         mut self: std::pin::Pin<&mut Self>,
@@ -80,20 +100,22 @@ where
     ) -> std::task::Poll<Self::Output> {
         let req = self.ctx.request();
         if let Err(err) = is_content_type(req, mime::APPLICATION_JSON) {
-            return Poll::Ready(Err(responses::unprocessable_entity(err)));
+            return Poll::Ready(Err(err.into()));
         }
 
         let mut this = self.as_mut();
-        let ret = ready!(this.fut.poll_unpin(cx));
-
-        match ret {
-            Ok(bytes) => match serde_json::from_slice::<T>(&bytes) {
-                Ok(x) => Poll::Ready(Ok(Json(x))),
-                Err(err) => Poll::Ready(Err(responses::unprocessable_entity(format!(
-                    "failed to deserialize json: {err}"
-                )))),
+        let bytes = ready!(this.fut.poll_unpin(cx))?;
+
+        let mut de = serde_json::Deserializer::from_slice(&bytes);
+        let result = deserialize_with_path(&mut de)
+            .and_then(|x| de.end().map(|_| x).map_err(|err| (err, None)));
+
+        match result {
+            Ok(x) => Poll::Ready(Ok(Json(x))),
+            Err((err, path)) => match err.classify() {
+                Category::Data => Poll::Ready(Err(JsonRejection::Data { path, source: err })),
+                _ => Poll::Ready(Err(JsonRejection::Syntax(err))),
             },
-            Err(err) => Poll::Ready(Err(responses::unprocessable_entity(err))),
         }
     }
 }
@@ -25,6 +25,12 @@ pub use form::*;
 mod bytes_;
 pub use bytes_::*;
 
+mod body_limit;
+pub use body_limit::*;
+
+mod body_stream;
+pub use body_stream::*;
+
 mod option;
 pub use option::*;
 
@@ -44,4 +50,19 @@ mod either_;
 pub use either_::*;
 
 mod addr;
-pub use addr::*;
\ No newline at end of file
+pub use addr::*;
+
+mod request_id;
+pub use request_id::*;
+
+mod cookie_jar;
+pub use cookie_jar::*;
+
+mod valid;
+pub use valid::*;
+
+mod sse;
+pub use sse::*;
+
+mod websocket;
+pub use websocket::*;
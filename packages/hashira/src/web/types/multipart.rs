@@ -1,13 +1,34 @@
 use crate::{
     app::RequestContext,
     types::BoxFuture,
-    web::{Body, FromRequest},
+    web::{Body, BytesRejection, FromRequest, MultipartRejection},
 };
-use http::header;
-use multer_derive::{Error, FromMultipart, MultipartForm};
-use std::convert::Infallible;
+use bytes::{BufMut, Bytes, BytesMut};
+use http::{header, HeaderMap};
+use mime::Mime;
+use multer_derive::{
+    multer::{self, Constraints, SizeLimit},
+    FromMultipart, MultipartForm,
+};
+use std::{
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::{fs, io::AsyncWriteExt};
+
+/// Default max size of a multipart body: 32 MiB.
+const DEFAULT_MAX_TOTAL_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Default size after which the files are written to disk: 1 MiB.
+const DEFAULT_SPILL_THRESHOLD: usize = 1024 * 1024;
 
 /// Represents a multipart form.
+///
+/// The body is streamed and the size limits of the [`MultipartConfig`] in the `app_data` are enforced,
+/// returning `413 Payload Too Large` when exceeded. All the fields are kept in memory,
+/// use [`MultipartStream`] to write large files to disk.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Multipart<T>(T);
 
@@ -18,11 +39,25 @@ impl<T> Multipart<T> {
     }
 }
 
+impl<T> Deref for Multipart<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Multipart<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<T> FromRequest for Multipart<T>
 where
     T: FromMultipart,
 {
-    type Error = Error;
+    type Error = MultipartRejection;
     type Fut = BoxFuture<Result<Multipart<T>, Self::Error>>;
 
     fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
@@ -30,36 +65,630 @@ where
         let body = std::mem::take(body);
 
         Box::pin(async move {
-            let Some(header_value) = ctx.request().headers().get(header::CONTENT_TYPE) else {
-                return Err(Error::new("content type was not specified"));
-            };
-
-            log::debug!("Preparing multipart...");
-
-            let content_type = header_value.to_str().map_err(Error::new)?;
-            let boundary =
-                multer_derive::multer::parse_boundary(content_type).map_err(Error::new)?;
-
-            // TODO: We should be able to take the entire body
             log::debug!("Reading request multipart body");
-            let bytes = body.into_bytes().await.map_err(Error::new)?.to_vec();
-
-            let multer = multer_derive::multer::Multipart::new(
-                futures::stream::once(async move { Ok::<_, Infallible>(bytes) }),
-                boundary,
-            );
-
-            log::debug!("Parsing multipart...");
-            let multipart = MultipartForm::with_multipart(multer)
+            let (multer, _) = create_multer(&ctx, body)?;
+            let form = MultipartForm::with_multipart(multer)
                 .await
-                .map_err(Error::new)?;
+                .map_err(multer_rejection)?;
 
             log::debug!(
                 "Converting multipart to: {}",
                 std::any::type_name::<Multipart<T>>()
             );
-            let value = T::from_multipart(&multipart, Default::default()).map_err(Error::new)?;
+
+            let value = T::from_multipart(&form, Default::default())
+                .map_err(MultipartRejection::Deserialize)?;
             Ok(Multipart(value))
         })
     }
 }
+
+/// A multipart form that is read field by field.
+///
+/// The size limits of the [`MultipartConfig`] in the `app_data` are enforced,
+/// the fields can be read in memory or written to a [`TempFile`].
+///
+/// # Example
+/// ```rust,no_run
+/// use hashira::web::MultipartStream;
+///
+/// async fn upload(mut form: MultipartStream) -> hashira::Result<String> {
+///     while let Some(field) = form.next_field().await? {
+///         if field.name() == Some("video") {
+///             let file = field.into_temp_file().await?;
+///             file.persist("uploads/video.mp4")?;
+///         }
+///     }
+///
+///     Ok(String::from("uploaded"))
+/// }
+/// ```
+pub struct MultipartStream {
+    multer: multer::Multipart<'static>,
+    config: MultipartConfig,
+}
+
+impl MultipartStream {
+    /// Returns the next field of the form, if any.
+    ///
+    /// The previous field must be dropped before reading the next one.
+    pub async fn next_field(&mut self) -> Result<Option<StreamField>, MultipartRejection> {
+        let field = self.multer.next_field().await.map_err(multer_rejection)?;
+
+        Ok(field.map(|field| StreamField {
+            field,
+            config: self.config.clone(),
+        }))
+    }
+}
+
+impl std::fmt::Debug for MultipartStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultipartStream")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl FromRequest for MultipartStream {
+    type Error = MultipartRejection;
+    type Fut = BoxFuture<Result<MultipartStream, Self::Error>>;
+
+    fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
+        let ctx = ctx.clone();
+        let body = std::mem::take(body);
+
+        Box::pin(async move {
+            let (multer, config) = create_multer(&ctx, body)?;
+            Ok(MultipartStream { multer, config })
+        })
+    }
+}
+
+/// A field of a [`MultipartStream`].
+pub struct StreamField {
+    field: multer::Field<'static>,
+    config: MultipartConfig,
+}
+
+impl StreamField {
+    /// Returns the name of the field.
+    pub fn name(&self) -> Option<&str> {
+        self.field.name()
+    }
+
+    /// Returns the name of the file sent by the client, if is a file field.
+    pub fn file_name(&self) -> Option<&str> {
+        self.field.file_name()
+    }
+
+    /// Returns the content type of the field.
+    pub fn content_type(&self) -> Option<&Mime> {
+        self.field.content_type()
+    }
+
+    /// Returns the headers of the field.
+    pub fn headers(&self) -> &HeaderMap {
+        self.field.headers()
+    }
+
+    /// Returns the next chunk of the field, if any.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, MultipartRejection> {
+        self.field.chunk().await.map_err(multer_rejection)
+    }
+
+    /// Reads the entire field in memory.
+    pub async fn bytes(self) -> Result<Bytes, MultipartRejection> {
+        self.field.bytes().await.map_err(multer_rejection)
+    }
+
+    /// Reads the entire field in memory as text.
+    pub async fn text(self) -> Result<String, MultipartRejection> {
+        self.field.text().await.map_err(multer_rejection)
+    }
+
+    /// Reads the field into a [`TempFile`], which is written to disk
+    /// if is larger than the [`MultipartConfig::spill_threshold`].
+    pub async fn into_temp_file(mut self) -> Result<TempFile, MultipartRejection> {
+        let mut file = TempFile {
+            name: self.field.name().map(ToOwned::to_owned),
+            file_name: self.field.file_name().map(ToOwned::to_owned),
+            content_type: self.field.content_type().cloned(),
+            size: 0,
+            data: TempFileData::Memory(Bytes::new()),
+        };
+
+        let mut buf = BytesMut::new();
+        let mut disk_file = None;
+
+        while let Some(chunk) = self.chunk().await? {
+            file.size += chunk.len() as u64;
+
+            match &mut disk_file {
+                Some(disk_file) => write_chunk(disk_file, &chunk).await?,
+                None if buf.len() + chunk.len() > self.config.spill_threshold => {
+                    let path = self.config.temp_file_path();
+                    log::debug!("Writing multipart file to {}", path.display());
+
+                    // Set before writing, so the file is removed on failure
+                    let mut new_file = fs::File::create(&path)
+                        .await
+                        .map_err(MultipartRejection::Io)?;
+                    file.data = TempFileData::Disk(path);
+
+                    write_chunk(&mut new_file, &buf).await?;
+                    write_chunk(&mut new_file, &chunk).await?;
+                    buf.clear();
+                    disk_file = Some(new_file);
+                }
+                None => buf.put(chunk),
+            }
+        }
+
+        match disk_file {
+            Some(mut disk_file) => disk_file.flush().await.map_err(MultipartRejection::Io)?,
+            None => file.data = TempFileData::Memory(buf.freeze()),
+        }
+
+        Ok(file)
+    }
+}
+
+impl std::fmt::Debug for StreamField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamField")
+            .field("name", &self.name())
+            .field("file_name", &self.file_name())
+            .field("content_type", &self.content_type())
+            .finish()
+    }
+}
+
+/// The limits used to read a [`Multipart`] form, register it with [`App::multipart_config`].
+///
+/// [`App::multipart_config`]: crate::app::App::multipart_config
+#[derive(Debug, Clone)]
+pub struct MultipartConfig {
+    max_field_size: Option<u64>,
+    max_total_size: Option<u64>,
+    spill_threshold: usize,
+    temp_dir: Option<PathBuf>,
+}
+
+impl MultipartConfig {
+    /// Constructs a default config, which limits the body to 32 MiB and
+    /// write the temporary files larger than 1 MiB to disk.
+    pub fn new() -> Self {
+        MultipartConfig {
+            max_field_size: None,
+            max_total_size: Some(DEFAULT_MAX_TOTAL_SIZE),
+            spill_threshold: DEFAULT_SPILL_THRESHOLD,
+            temp_dir: None,
+        }
+    }
+
+    /// Sets the max size in bytes of each field.
+    pub fn max_field_size(mut self, size: u64) -> Self {
+        self.max_field_size = Some(size);
+        self
+    }
+
+    /// Sets the max size in bytes of the entire body.
+    pub fn max_total_size(mut self, size: u64) -> Self {
+        self.max_total_size = Some(size);
+        self
+    }
+
+    /// Removes the limit of the entire body.
+    pub fn unlimited(mut self) -> Self {
+        self.max_total_size = None;
+        self
+    }
+
+    /// Sets the size in bytes after which a [`TempFile`] is written to disk.
+    pub fn spill_threshold(mut self, size: usize) -> Self {
+        self.spill_threshold = size;
+        self
+    }
+
+    /// Sets the directory where the temporary files are written, defaults to [`std::env::temp_dir`].
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(dir.into());
+        self
+    }
+
+    fn constraints(&self) -> Constraints {
+        let mut size_limit = SizeLimit::new();
+
+        if let Some(size) = self.max_total_size {
+            size_limit = size_limit.whole_stream(size);
+        }
+
+        if let Some(size) = self.max_field_size {
+            size_limit = size_limit.per_field(size);
+        }
+
+        Constraints::new().size_limit(size_limit)
+    }
+
+    fn temp_file_path(&self) -> PathBuf {
+        use rand::{distributions::Alphanumeric, Rng};
+
+        let name: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(16)
+            .map(char::from)
+            .collect();
+
+        let dir = self.temp_dir.clone().unwrap_or_else(std::env::temp_dir);
+        dir.join(format!("hashira-upload-{name}"))
+    }
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        MultipartConfig::new()
+    }
+}
+
+enum TempFileData {
+    Memory(Bytes),
+    Disk(PathBuf),
+}
+
+/// A field of a [`MultipartStream`], which is written to a temporary file if is larger
+/// than the [`MultipartConfig::spill_threshold`].
+///
+/// The temporary file is removed when dropped, use [`TempFile::persist`] to keep it.
+pub struct TempFile {
+    name: Option<String>,
+    file_name: Option<String>,
+    content_type: Option<Mime>,
+    size: u64,
+    data: TempFileData,
+}
+
+impl TempFile {
+    /// Returns the name of the field.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the name of the file sent by the client.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Returns the content type of the file.
+    pub fn content_type(&self) -> Option<&Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// Returns the size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns `true` if the file is in memory.
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self.data, TempFileData::Memory(_))
+    }
+
+    /// Returns the path of the temporary file, if was written to disk.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.data {
+            TempFileData::Memory(_) => None,
+            TempFileData::Disk(path) => Some(path.as_path()),
+        }
+    }
+
+    /// Returns the contents of the file, reading it from disk if necessary.
+    pub fn bytes(&self) -> std::io::Result<Bytes> {
+        match &self.data {
+            TempFileData::Memory(bytes) => Ok(bytes.clone()),
+            TempFileData::Disk(path) => std::fs::read(path).map(Bytes::from),
+        }
+    }
+
+    /// Moves the file to the given path.
+    pub fn persist(mut self, dest: impl AsRef<Path>) -> std::io::Result<()> {
+        let dest = dest.as_ref();
+        let data = std::mem::replace(&mut self.data, TempFileData::Memory(Bytes::new()));
+
+        match data {
+            TempFileData::Memory(bytes) => std::fs::write(dest, bytes),
+            TempFileData::Disk(path) => {
+                // Rename fails if the destination is in other file system
+                if std::fs::rename(&path, dest).is_err() {
+                    std::fs::copy(&path, dest)?;
+                    let _ = std::fs::remove_file(&path);
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if let TempFileData::Disk(path) = &self.data {
+            if let Err(err) = std::fs::remove_file(path) {
+                log::warn!("failed to remove temporary file {}: {err}", path.display());
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for TempFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TempFile")
+            .field("name", &self.name)
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .field("size", &self.size)
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+fn create_multer(
+    ctx: &RequestContext,
+    body: Body,
+) -> Result<(multer::Multipart<'static>, MultipartConfig), MultipartRejection> {
+    let Some(header_value) = ctx.request().headers().get(header::CONTENT_TYPE) else {
+        return Err(MultipartRejection::MissingContentType);
+    };
+
+    let content_type = header_value
+        .to_str()
+        .map_err(|err| MultipartRejection::InvalidBoundary(err.into()))?;
+    let boundary = multer::parse_boundary(content_type)
+        .map_err(|err| MultipartRejection::InvalidBoundary(err.into()))?;
+
+    let config = ctx
+        .app_data::<MultipartConfig>()
+        .cloned()
+        .unwrap_or_default();
+
+    let multer =
+        multer::Multipart::with_constraints(body.into_stream(), boundary, config.constraints());
+    Ok((multer, config))
+}
+
+fn multer_rejection(err: multer::Error) -> MultipartRejection {
+    match err {
+        multer::Error::StreamSizeExceeded { limit } => {
+            MultipartRejection::PayloadTooLarge { limit }
+        }
+        multer::Error::FieldSizeExceeded { limit, field_name } => {
+            MultipartRejection::FieldTooLarge { limit, field_name }
+        }
+        multer::Error::StreamReadFailed(err) => {
+            MultipartRejection::Body(BytesRejection::FailedToReadBody(err))
+        }
+        err => MultipartRejection::Parse(err.into()),
+    }
+}
+
+async fn write_chunk(file: &mut fs::File, chunk: &[u8]) -> Result<(), MultipartRejection> {
+    file.write_all(chunk).await.map_err(MultipartRejection::Io)
+}
+
+// `tokio::fs` is not available in wasm, where the files can't be written to disk
+#[cfg(target_arch = "wasm32")]
+mod fs {
+    use std::{io, path::Path};
+
+    fn unsupported() -> io::Error {
+        io::Error::new(io::ErrorKind::Unsupported, "files can't be written in wasm")
+    }
+
+    pub struct File;
+
+    impl File {
+        pub async fn create(_: impl AsRef<Path>) -> io::Result<File> {
+            Err(unsupported())
+        }
+
+        pub async fn write_all(&mut self, _: &[u8]) -> io::Result<()> {
+            Err(unsupported())
+        }
+
+        pub async fn flush(&mut self) -> io::Result<()> {
+            Err(unsupported())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Multipart, MultipartConfig, MultipartStream};
+    use crate::{
+        app::App,
+        routing::Route,
+        web::{Body, Request, Response},
+    };
+    use http::{header, Method, StatusCode};
+    use multer_derive::{Error, FormContext, FormFile, FromMultipart, MultipartForm};
+    use std::path::{Path, PathBuf};
+    use yew::html::ChildrenProps;
+
+    const BOUNDARY: &str = "hashira-boundary";
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    struct Empty;
+
+    impl FromMultipart for Empty {
+        fn from_multipart(_: &MultipartForm, _: FormContext<'_>) -> Result<Self, Error> {
+            Ok(Empty)
+        }
+    }
+
+    struct WithFormFile {
+        file: FormFile,
+    }
+
+    impl FromMultipart for WithFormFile {
+        fn from_multipart(multipart: &MultipartForm, _: FormContext<'_>) -> Result<Self, Error> {
+            let ctx = FormContext {
+                field_name: Some("file"),
+            };
+
+            Ok(WithFormFile {
+                file: FormFile::from_multipart(multipart, ctx)?,
+            })
+        }
+    }
+
+    fn create_body(file_contents: &str) -> String {
+        format!(
+            "--{BOUNDARY}\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+            Ferris\r\n\
+            --{BOUNDARY}\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"ferris.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            {file_contents}\r\n\
+            --{BOUNDARY}--\r\n"
+        )
+    }
+
+    fn create_req(file_contents: &str) -> Request {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/upload")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(create_body(file_contents)))
+            .unwrap()
+    }
+
+    fn create_temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hashira-multipart-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn count_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    async fn get_text(res: Response) -> String {
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn multipart_form_test() {
+        let service = App::<Base>::new()
+            .route(Route::post(
+                "/upload",
+                |form: Multipart<WithFormFile>| async move {
+                    let file = form.into_inner().file;
+                    String::from_utf8(file.bytes().to_vec()).unwrap()
+                },
+            ))
+            .build();
+
+        let res = service.handle(create_req("hello")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(get_text(res).await, "hello");
+    }
+
+    #[tokio::test]
+    async fn temp_file_test() {
+        let dir = create_temp_dir("temp-file");
+        let service = App::<Base>::new()
+            .multipart_config(MultipartConfig::new().spill_threshold(16).temp_dir(&dir))
+            .route(Route::post(
+                "/upload",
+                |mut form: MultipartStream| async move {
+                    let title = form.next_field().await.unwrap().unwrap();
+                    assert_eq!(title.name(), Some("title"));
+                    let title = title.text().await.unwrap();
+
+                    let field = form.next_field().await.unwrap().unwrap();
+                    let file = field.into_temp_file().await.unwrap();
+                    assert!(form.next_field().await.unwrap().is_none());
+
+                    assert_eq!(file.name(), Some("file"));
+                    assert_eq!(file.file_name(), Some("ferris.txt"));
+                    assert_eq!(file.content_type(), Some(&mime::TEXT_PLAIN));
+                    let on_disk = file.path().map(|path| path.exists()).unwrap_or_default();
+                    let bytes = file.bytes().unwrap();
+
+                    format!("{title}:{on_disk}:{}:{}", file.size(), bytes.len())
+                },
+            ))
+            .build();
+
+        // Written to disk
+        let res = service.handle(create_req(&"a".repeat(64))).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(get_text(res).await, "Ferris:true:64:64");
+
+        // Removed after the `TempFile` is dropped
+        assert_eq!(count_files(&dir), 0);
+
+        // Kept in memory
+        let res = service.handle(create_req("small")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(get_text(res).await, "Ferris:false:5:5");
+        assert_eq!(count_files(&dir), 0);
+    }
+
+    #[tokio::test]
+    async fn temp_file_persist_test() {
+        let dir = create_temp_dir("persist");
+        let dest = dir.join("ferris.txt");
+        let service = App::<Base>::new()
+            .multipart_config(MultipartConfig::new().spill_threshold(16).temp_dir(&dir))
+            .route(Route::post("/upload", {
+                let dest = dest.clone();
+                move |mut form: MultipartStream| {
+                    let dest = dest.clone();
+                    async move {
+                        while let Some(field) = form.next_field().await.unwrap() {
+                            if field.file_name().is_some() {
+                                let file = field.into_temp_file().await.unwrap();
+                                file.persist(&dest).unwrap();
+                            }
+                        }
+
+                        "ok"
+                    }
+                }
+            }))
+            .build();
+
+        let contents = "a".repeat(64);
+        let res = service.handle(create_req(&contents)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), contents);
+        assert_eq!(count_files(&dir), 1);
+    }
+
+    #[tokio::test]
+    async fn size_limit_test() {
+        let service = App::<Base>::new()
+            .multipart_config(MultipartConfig::new().max_field_size(32))
+            .route(Route::post("/upload", |_: Multipart<Empty>| async { "ok" }))
+            .build();
+
+        let res = service.handle(create_req("small file")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.handle(create_req(&"a".repeat(64))).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
     app::RequestContext,
-    web::{Body, FromRequest},
+    web::{rejection::deserialize_with_path, Body, FromRequest, QueryRejection},
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use std::{
+    future::{ready, Ready},
+    ops::{Deref, DerefMut},
 };
-use serde::de::DeserializeOwned;
-use std::future::{ready, Ready};
 
 /// Represents an url search params.
 pub struct Query<Q>(Q);
@@ -15,20 +18,58 @@ impl<Q> Query<Q> {
     }
 }
 
+impl<Q> Deref for Query<Q> {
+    type Target = Q;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<Q> DerefMut for Query<Q> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<Q: DeserializeOwned> FromRequest for Query<Q> {
-    type Error = serde_qs::Error;
+    type Error = QueryRejection;
     type Fut = Ready<Result<Query<Q>, Self::Error>>;
 
     fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
-        match ctx.request().uri().query() {
-            Some(s) => ready(serde_qs::from_str(s).map(Query)),
-            None => ready(Err(serde_qs::Error::Custom(
-                "url does not contain a query string".to_owned(),
-            ))),
+        let Some(s) = ctx.request().uri().query() else {
+            return ready(Err(QueryRejection::MissingQuery));
+        };
+
+        match serde_qs::from_str::<TrackPath<Q>>(s) {
+            Ok(TrackPath(Ok(x))) => ready(Ok(Query(x))),
+            Ok(TrackPath(Err((message, path)))) => {
+                let source = serde_qs::Error::Custom(message);
+                ready(Err(QueryRejection::Deserialize { path, source }))
+            }
+            Err(source) => ready(Err(QueryRejection::Deserialize { path: None, source })),
         }
     }
 }
 
+// `serde_qs` don't expose its deserializer,
+// so the error and path of the field that failed are returned in the value
+struct TrackPath<T>(Result<T, (String, Option<String>)>);
+
+impl<'de, T> Deserialize<'de> for TrackPath<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let result =
+            deserialize_with_path(deserializer).map_err(|(err, path)| (err.to_string(), path));
+        Ok(TrackPath(result))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -0,0 +1,164 @@
+use crate::{
+    app::RequestContext,
+    web::{Body, FromRequest},
+};
+use http::HeaderValue;
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    future::{ready, Ready},
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The header used to propagate the id of a request.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Max length of a request id taken from the request headers
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// The id of a request, taken from the `X-Request-Id` header or generated if missing.
+///
+/// The id is also sent back in the `X-Request-Id` header of the response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(Arc<str>);
+
+impl RequestId {
+    /// Generates a new random request id.
+    pub fn new() -> Self {
+        let id = format!("{:032x}", rand::random::<u128>());
+        RequestId(id.into())
+    }
+
+    /// Returns the request id in the given headers if is valid, otherwise generates a new one.
+    pub fn from_headers(headers: &http::HeaderMap) -> Self {
+        headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|id| is_valid_request_id(id))
+            .map(|id| RequestId(id.into()))
+            .unwrap_or_default()
+    }
+
+    /// Returns the id as a `str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the id as a header value.
+    pub fn to_header_value(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.0).expect("request id should be a valid header value")
+    }
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LENGTH && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        RequestId::new()
+    }
+}
+
+impl Deref for RequestId {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromRequest for RequestId {
+    type Error = Infallible;
+    type Fut = Ready<Result<RequestId, Infallible>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        let id = ctx
+            .request_id()
+            .cloned()
+            .unwrap_or_else(|| RequestId::from_headers(ctx.request().headers()));
+
+        ready(Ok(id))
+    }
+}
+
+/// The time when the server started processing a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RequestStart(Instant);
+
+impl RequestStart {
+    /// Constructs a `RequestStart` from the current time.
+    pub fn now() -> Self {
+        RequestStart(Instant::now())
+    }
+
+    /// Returns the instant the request started.
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// Returns the time elapsed since the request started.
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RequestId, REQUEST_ID_HEADER};
+    use crate::{app::App, routing::Route, web::Request};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[tokio::test]
+    async fn request_id_test() {
+        let service = App::<Base>::new()
+            .route(Route::get(
+                "/id",
+                |id: RequestId| async move { id.to_string() },
+            ))
+            .build();
+
+        // Propagated from the request
+        let req = Request::builder()
+            .uri("/id")
+            .header(REQUEST_ID_HEADER, "abc-123")
+            .body(Default::default())
+            .unwrap();
+
+        let res = service.handle(req).await;
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "abc-123");
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "abc-123");
+
+        // Generated
+        let req = Request::builder()
+            .uri("/id")
+            .header(REQUEST_ID_HEADER, "invalid id")
+            .body(Default::default())
+            .unwrap();
+
+        let res = service.handle(req).await;
+        let header = res.headers().get(REQUEST_ID_HEADER).cloned().unwrap();
+        assert_ne!(header, "invalid id");
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, header.as_bytes());
+        assert_eq!(bytes.len(), 32);
+    }
+}
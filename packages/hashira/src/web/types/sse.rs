@@ -0,0 +1,480 @@
+use crate::{
+    app::RequestContext,
+    error::BoxError,
+    types::TryBoxStream,
+    web::{Body, FromRequest, IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures::{Future, Stream};
+use futures_timer::Delay;
+use http::{header, HeaderValue};
+use pin_project_lite::pin_project;
+use serde::Serialize;
+use std::{
+    convert::Infallible,
+    fmt::Write,
+    future::{ready, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// The header sent by the browser when reconnecting to an event stream.
+pub const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// The query param used by `use_event_source` to send the last event id when
+/// the connection is opened again.
+pub const LAST_EVENT_ID_QUERY: &str = "lastEventId";
+
+/// A server-sent event.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Duration>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// Constructs an empty event.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the id of the event, the client sends it back when reconnecting.
+    ///
+    /// # Panic
+    /// If the id contains a new line or carriage return.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        let id = id.into();
+        assert_single_line("id", &id);
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the name of the event, if not set the client receives it as a `message`.
+    ///
+    /// # Panic
+    /// If the name contains a new line or carriage return.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        let event = event.into();
+        assert_single_line("event", &event);
+        self.event = Some(event);
+        self
+    }
+
+    /// Sets the text data of the event.
+    pub fn data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Sets the data of the event serialized as json.
+    pub fn json_data<T: Serialize>(mut self, data: &T) -> Result<Self, serde_json::Error> {
+        self.data = Some(serde_json::to_string(data)?);
+        Ok(self)
+    }
+
+    /// Sets the time the client should wait before reconnecting.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets a comment, which is ignored by the client.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Returns the bytes of this event as sent in the stream.
+    pub fn to_bytes(&self) -> Bytes {
+        let mut buf = String::new();
+
+        if let Some(comment) = &self.comment {
+            write_field(&mut buf, "", comment);
+        }
+
+        if let Some(event) = &self.event {
+            write_field(&mut buf, "event", event);
+        }
+
+        if let Some(data) = &self.data {
+            write_field(&mut buf, "data", data);
+        }
+
+        if let Some(id) = &self.id {
+            write_field(&mut buf, "id", id);
+        }
+
+        if let Some(retry) = self.retry {
+            let _ = writeln!(buf, "retry: {}", retry.as_millis());
+        }
+
+        buf.push('\n');
+        Bytes::from(buf)
+    }
+}
+
+fn assert_single_line(field: &str, value: &str) {
+    assert!(
+        !value.contains(['\n', '\r']),
+        "sse event `{field}` cannot contain new lines"
+    );
+}
+
+// Writes each line of the value as a field, so multiline data is received as a single value
+fn write_field(buf: &mut String, name: &str, value: &str) {
+    for line in value.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let _ = writeln!(buf, "{name}: {line}");
+    }
+}
+
+/// Configuration for sending comments to keep alive the connection of a [`Sse`] stream
+/// when there is no events to send.
+#[derive(Debug, Clone)]
+pub struct KeepAlive {
+    interval: Duration,
+    text: String,
+}
+
+impl KeepAlive {
+    /// Constructs a keep alive which sends a comment every 15 seconds.
+    pub fn new() -> Self {
+        KeepAlive {
+            interval: Duration::from_secs(15),
+            text: String::new(),
+        }
+    }
+
+    /// Sets the time to wait since the last event to send a keep alive comment.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the text of the keep alive comment.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        KeepAlive::new()
+    }
+}
+
+/// A response that sends a stream of server-sent events.
+///
+/// The events are received in the client using the `EventSource` api,
+/// or the `use_event_source` hook.
+///
+/// The event streams are long-lived, so these are not ended by the stream timeout
+/// of [`App::timeout`] and are not waited for when the server is shutting down.
+///
+/// [`App::timeout`]: crate::app::App::timeout
+pub struct Sse<S> {
+    stream: S,
+    keep_alive: Option<KeepAlive>,
+}
+
+impl<S> Sse<S> {
+    /// Constructs a response from a stream of events.
+    pub fn new(stream: S) -> Self {
+        Sse {
+            stream,
+            keep_alive: None,
+        }
+    }
+
+    /// Sends comments to keep alive the connection.
+    pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+}
+
+impl<S, E> IntoResponse for Sse<S>
+where
+    S: Stream<Item = Result<Event, E>> + Send + Sync + 'static,
+    E: Into<BoxError>,
+{
+    fn into_response(self) -> Response {
+        let keep_alive = self.keep_alive.map(|keep_alive| {
+            let bytes = Event::new().comment(keep_alive.text).to_bytes();
+            let delay = Delay::new(keep_alive.interval);
+            (keep_alive.interval, bytes, delay)
+        });
+
+        let stream = SseStream {
+            stream: self.stream,
+            keep_alive,
+        };
+
+        let stream: TryBoxStream<Bytes> = Box::pin(stream);
+        let mut res = Response::new(Body::from(stream));
+
+        let headers = res.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(mime::TEXT_EVENT_STREAM.as_ref()),
+        );
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        res
+    }
+}
+
+pin_project! {
+    struct SseStream<S> {
+        #[pin]
+        stream: S,
+        keep_alive: Option<(Duration, Bytes, Delay)>,
+    }
+}
+
+impl<S, E> Stream for SseStream<S>
+where
+    S: Stream<Item = Result<Event, E>>,
+    E: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(Ok(event))) => {
+                if let Some((interval, _, delay)) = this.keep_alive {
+                    delay.reset(*interval);
+                }
+
+                Poll::Ready(Some(Ok(event.to_bytes())))
+            }
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err.into()))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => {
+                let Some((interval, bytes, delay)) = this.keep_alive else {
+                    return Poll::Pending;
+                };
+
+                match Pin::new(&mut *delay).poll(cx) {
+                    Poll::Ready(_) => {
+                        delay.reset(*interval);
+                        Poll::Ready(Some(Ok(bytes.clone())))
+                    }
+                    Poll::Pending => Poll::Pending,
+                }
+            }
+        }
+    }
+}
+
+/// The id of the last event received by the client, sent when reconnecting to a [`Sse`] stream.
+///
+/// It's taken from the `Last-Event-ID` header or the `lastEventId` query param.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LastEventId(pub Option<String>);
+
+impl LastEventId {
+    /// Returns the last event id, if any.
+    pub fn into_inner(self) -> Option<String> {
+        self.0
+    }
+}
+
+impl FromRequest for LastEventId {
+    type Error = Infallible;
+    type Fut = Ready<Result<LastEventId, Infallible>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        let req = ctx.request();
+        let from_header = req
+            .headers()
+            .get(LAST_EVENT_ID_HEADER)
+            .and_then(|x| x.to_str().ok())
+            .map(|x| x.to_owned());
+
+        let id = from_header.or_else(|| {
+            let query = req.uri().query()?;
+            form_urlencoded_value(query, LAST_EVENT_ID_QUERY)
+        });
+
+        ready(Ok(LastEventId(id)))
+    }
+}
+
+fn form_urlencoded_value(query: &str, key: &str) -> Option<String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+        .ok()?
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v)
+}
+
+/// Returns `true` for the responses that keep the connection open,
+/// the event streams and the protocol upgrades.
+pub(crate) fn is_long_lived(res: &Response) -> bool {
+    if res.status() == http::StatusCode::SWITCHING_PROTOCOLS {
+        return true;
+    }
+
+    res.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with(mime::TEXT_EVENT_STREAM.as_ref()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, KeepAlive, LastEventId, Sse};
+    use crate::{
+        app::{App, RequestTimeout},
+        routing::Route,
+        web::Request,
+    };
+    use futures::StreamExt;
+    use http::{header, StatusCode};
+    use serde::Serialize;
+    use std::{convert::Infallible, time::Duration};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Message {
+        text: String,
+    }
+
+    #[test]
+    fn event_format_test() {
+        let event = Event::new()
+            .id("1")
+            .event("greet")
+            .json_data(&Message {
+                text: "hello".to_owned(),
+            })
+            .unwrap()
+            .retry(Duration::from_secs(3));
+
+        assert_eq!(
+            event.to_bytes(),
+            "event: greet\ndata: {\"text\":\"hello\"}\nid: 1\nretry: 3000\n\n"
+        );
+
+        let event = Event::new().comment("ping").data("line 1\nline 2");
+        assert_eq!(event.to_bytes(), ": ping\ndata: line 1\ndata: line 2\n\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn event_id_with_new_line_test() {
+        let _ = Event::new().id("1\n2");
+    }
+
+    #[tokio::test]
+    async fn sse_response_test() {
+        let service = App::<Base>::new()
+            .route(Route::get("/events", |last_id: LastEventId| async move {
+                let start = last_id
+                    .into_inner()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .map(|x| x + 1)
+                    .unwrap_or(0);
+
+                let events = (start..3).map(|i| {
+                    Ok::<_, Infallible>(Event::new().id(i.to_string()).data(i.to_string()))
+                });
+
+                Sse::new(futures::stream::iter(events))
+            }))
+            .build();
+
+        let req = Request::builder()
+            .uri("/events")
+            .body(Default::default())
+            .unwrap();
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(
+            bytes,
+            "data: 0\nid: 0\n\ndata: 1\nid: 1\n\ndata: 2\nid: 2\n\n"
+        );
+
+        let req = Request::builder()
+            .uri("/events?lastEventId=1")
+            .body(Default::default())
+            .unwrap();
+        let res = service.handle(req).await;
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "data: 2\nid: 2\n\n");
+    }
+
+    #[tokio::test]
+    async fn sse_keep_alive_test() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<Event, Infallible>>();
+        let sse = Sse::new(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)).keep_alive(
+            KeepAlive::new()
+                .interval(Duration::from_millis(10))
+                .text("ping"),
+        );
+
+        let mut stream = crate::web::IntoResponse::into_response(sse)
+            .into_body()
+            .into_stream();
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), ": ping\n\n");
+
+        tx.send(Ok(Event::new().data("done"))).unwrap();
+        drop(tx);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "data: done\n\n");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn sse_long_lived_test() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<Event, Infallible>>();
+        let rx = std::sync::Arc::new(std::sync::Mutex::new(Some(rx)));
+
+        let service = App::<Base>::new()
+            .timeout(RequestTimeout::none().stream(Duration::from_millis(10)))
+            .route(Route::get("/events", move || {
+                let rx = rx.lock().unwrap().take().unwrap();
+                async move { Sse::new(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)) }
+            }))
+            .build();
+
+        let req = Request::builder()
+            .uri("/events")
+            .body(Default::default())
+            .unwrap();
+        let res = service.handle(req).await;
+
+        // The event stream is not waited for when shutting down
+        assert_eq!(service.in_flight_requests(), 0);
+
+        // And is not ended by the stream timeout
+        futures_timer::Delay::new(Duration::from_millis(50)).await;
+        tx.send(Ok(Event::new().data("done"))).unwrap();
+        drop(tx);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "data: done\n\n");
+    }
+}
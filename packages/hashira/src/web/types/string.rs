@@ -4,10 +4,13 @@ use bytes::Bytes;
 use futures::{ready, Future, FutureExt};
 use pin_project_lite::pin_project;
 
-use crate::{app::RequestContext, error::BoxError, responses, web::{FromRequest, Body}};
+use crate::{
+    app::RequestContext,
+    web::{Body, FromRequest, StringRejection},
+};
 
 impl FromRequest for String {
-    type Error = BoxError;
+    type Error = StringRejection;
     type Fut = StringFromRequestFuture;
 
     fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
@@ -26,7 +29,7 @@ pin_project! {
 }
 
 impl Future for StringFromRequestFuture {
-    type Output = Result<String, BoxError>;
+    type Output = Result<String, StringRejection>;
 
     fn poll(
         mut self: std::pin::Pin<&mut Self>,
@@ -38,12 +41,9 @@ impl Future for StringFromRequestFuture {
         match ret {
             Ok(bytes) => match String::from_utf8(bytes.to_vec()) {
                 Ok(s) => Poll::Ready(Ok(s)),
-                Err(err) => {
-                    let err = responses::unprocessable_entity(err);
-                    Poll::Ready(Err(err))
-                }
+                Err(err) => Poll::Ready(Err(StringRejection::InvalidUtf8(err))),
             },
-            Err(err) => Poll::Ready(Err(responses::unprocessable_entity(err))),
+            Err(err) => Poll::Ready(Err(StringRejection::Body(err))),
         }
     }
 }
@@ -0,0 +1,227 @@
+use crate::{
+    app::RequestContext,
+    error::{BoxError, ServerError},
+    types::BoxFuture,
+    web::{Body, FromRequest, IntoResponse, Json, Response},
+};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display, ops::Deref};
+
+/// Validates a value after being extracted from a request.
+pub trait Validate {
+    /// Returns the errors of each invalid field, if any.
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// The validation errors of each field.
+///
+/// When returned as a response is sent as a `422 Unprocessable Entity` with a json body
+/// in the form: `{ "errors": { "field": ["message"] } }`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationErrors {
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    /// Constructs an empty instance.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an error message for the given field.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors
+            .entry(field.into())
+            .or_default()
+            .push(message.into());
+    }
+
+    /// Adds an error message for the given field and returns this instance.
+    pub fn with(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.add(field, message);
+        self
+    }
+
+    /// Returns the error messages of the given field.
+    pub fn field(&self, field: &str) -> &[String] {
+        self.errors
+            .get(field)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the field have errors.
+    pub fn has_errors(&self, field: &str) -> bool {
+        !self.field(field).is_empty()
+    }
+
+    /// Returns an iterator over the fields and its errors.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.errors.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
+    }
+
+    /// Returns `true` if there is no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns `Ok` if there is no errors, otherwise returns this instance as error.
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+        for (field, messages) in self.errors.iter() {
+            for msg in messages {
+                if !first {
+                    write!(f, ", ")?;
+                }
+
+                first = false;
+                write!(f, "{field}: {msg}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response {
+        let mut res = Json(self).into_response();
+        *res.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+        res
+    }
+}
+
+/// Extracts a value and validates it, returning a `422 Unprocessable Entity`
+/// with the [`ValidationErrors`] if the value is invalid.
+///
+/// It can wrap any extractor that dereferences to a [`Validate`] type, like
+/// `Json<T>`, `Form<T>`, `Query<T>` or `Multipart<T>`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Valid<E>(pub E);
+
+impl<E> Valid<E> {
+    /// Returns the inner extractor.
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<E> Deref for Valid<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> FromRequest for Valid<E>
+where
+    E: FromRequest + Deref + Send + 'static,
+    E::Target: Validate,
+    E::Fut: Send + 'static,
+{
+    type Error = BoxError;
+    type Fut = BoxFuture<Result<Valid<E>, BoxError>>;
+
+    fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
+        let fut = E::from_request(ctx, body);
+
+        Box::pin(async move {
+            let value = fut.await.map_err(Into::into)?;
+
+            match value.validate() {
+                Ok(_) => Ok(Valid(value)),
+                Err(errors) => Err(ServerError::from_response_and_status(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    errors,
+                )
+                .into()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Valid, Validate, ValidationErrors};
+    use crate::{
+        app::App,
+        routing::Route,
+        web::{Form, Request},
+    };
+    use http::{header, Method, StatusCode};
+    use serde::Deserialize;
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct NewUser {
+        name: String,
+        age: u32,
+    }
+
+    impl Validate for NewUser {
+        fn validate(&self) -> Result<(), ValidationErrors> {
+            let mut errors = ValidationErrors::new();
+
+            if self.name.is_empty() {
+                errors.add("name", "name is required");
+            }
+
+            if self.age < 18 {
+                errors.add("age", "must be an adult");
+            }
+
+            errors.into_result()
+        }
+    }
+
+    fn create_req(body: &'static str) -> Request {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/users")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn valid_extractor_test() {
+        let service = App::<Base>::new()
+            .route(Route::post(
+                "/users",
+                |Valid(form): Valid<Form<NewUser>>| async move { form.into_inner().name },
+            ))
+            .build();
+
+        let res = service.handle(create_req("name=Ferris&age=20")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.handle(create_req("name=&age=10")).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let errors = serde_json::from_slice::<ValidationErrors>(&bytes).unwrap();
+        assert_eq!(errors.field("name"), ["name is required"]);
+        assert_eq!(errors.field("age"), ["must be an adult"]);
+    }
+}
@@ -0,0 +1,509 @@
+use crate::{
+    app::RequestContext,
+    error::BoxError,
+    types::BoxFuture,
+    web::{Body, Bytes, FromRequest, Response, WebSocketUpgradeRejection},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::{Future, Sink, SinkExt, Stream, StreamExt};
+use http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use sha1::{Digest, Sha1};
+use std::{
+    fmt::Debug,
+    future::{ready, Ready},
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+// https://datatracker.ietf.org/doc/html/rfc6455#section-1.3
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// A message sent or received through a [`WebSocket`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// A text message.
+    Text(String),
+
+    /// A binary message.
+    Binary(Bytes),
+
+    /// A ping message.
+    Ping(Bytes),
+
+    /// A pong message.
+    Pong(Bytes),
+
+    /// A close message, with an optional close frame.
+    Close(Option<CloseFrame>),
+}
+
+impl Message {
+    /// Constructs a text message.
+    pub fn text(text: impl Into<String>) -> Self {
+        Message::Text(text.into())
+    }
+
+    /// Constructs a binary message.
+    pub fn binary(bytes: impl Into<Bytes>) -> Self {
+        Message::Binary(bytes.into())
+    }
+
+    /// Returns the text of this message if is a text message.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Message::Text(text) => Some(text.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is a close message.
+    pub fn is_close(&self) -> bool {
+        matches!(self, Message::Close(_))
+    }
+
+    /// Returns the bytes of this message.
+    pub fn into_bytes(self) -> Bytes {
+        match self {
+            Message::Text(text) => Bytes::from(text),
+            Message::Binary(bytes) | Message::Ping(bytes) | Message::Pong(bytes) => bytes,
+            Message::Close(frame) => frame.map(|x| Bytes::from(x.reason)).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<String> for Message {
+    fn from(value: String) -> Self {
+        Message::Text(value)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(value: &str) -> Self {
+        Message::Text(value.to_owned())
+    }
+}
+
+impl From<Bytes> for Message {
+    fn from(value: Bytes) -> Self {
+        Message::Binary(value)
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    fn from(value: Vec<u8>) -> Self {
+        Message::Binary(value.into())
+    }
+}
+
+/// The code and reason sent when closing a [`WebSocket`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseFrame {
+    /// The close code.
+    pub code: u16,
+
+    /// The reason of the close.
+    pub reason: String,
+}
+
+type BoxSink = Pin<Box<dyn Sink<Message, Error = BoxError> + Send>>;
+type BoxStream = Pin<Box<dyn Stream<Item = Result<Message, BoxError>> + Send>>;
+
+/// A websocket connection.
+///
+/// The connection is created by the server adapter after a [`WebSocketUpgrade`],
+/// so the same code works with any of the adapters.
+pub struct WebSocket {
+    sink: BoxSink,
+    stream: BoxStream,
+    protocol: Option<String>,
+}
+
+impl WebSocket {
+    /// Constructs a websocket from a stream of incoming messages which is also a sink of outgoing messages.
+    pub fn new<S>(socket: S) -> Self
+    where
+        S: Stream<Item = Result<Message, BoxError>>
+            + Sink<Message, Error = BoxError>
+            + Send
+            + 'static,
+    {
+        let (sink, stream) = socket.split();
+        WebSocket::from_split(sink, stream)
+    }
+
+    /// Constructs a websocket from a sink of outgoing messages and a stream of incoming messages.
+    pub fn from_split<Si, St>(sink: Si, stream: St) -> Self
+    where
+        Si: Sink<Message, Error = BoxError> + Send + 'static,
+        St: Stream<Item = Result<Message, BoxError>> + Send + 'static,
+    {
+        WebSocket {
+            sink: Box::pin(sink),
+            stream: Box::pin(stream),
+            protocol: None,
+        }
+    }
+
+    /// Returns the subprotocol selected in the handshake, if any.
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+
+    /// Receives the next message, returns `None` if the connection was closed.
+    pub async fn recv(&mut self) -> Option<Result<Message, BoxError>> {
+        self.stream.next().await
+    }
+
+    /// Sends a message.
+    pub async fn send(&mut self, msg: impl Into<Message>) -> Result<(), BoxError> {
+        self.sink.send(msg.into()).await
+    }
+
+    /// Sends a text message.
+    pub async fn send_text(&mut self, text: impl Into<String>) -> Result<(), BoxError> {
+        self.send(Message::Text(text.into())).await
+    }
+
+    /// Sends a binary message.
+    pub async fn send_binary(&mut self, bytes: impl Into<Bytes>) -> Result<(), BoxError> {
+        self.send(Message::Binary(bytes.into())).await
+    }
+
+    /// Closes the connection.
+    pub async fn close(mut self) -> Result<(), BoxError> {
+        self.sink.send(Message::Close(None)).await?;
+        self.sink.close().await
+    }
+
+    /// Closes the connection with the given code and reason.
+    pub async fn close_with(
+        mut self,
+        code: u16,
+        reason: impl Into<String>,
+    ) -> Result<(), BoxError> {
+        let frame = CloseFrame {
+            code,
+            reason: reason.into(),
+        };
+
+        self.sink.send(Message::Close(Some(frame))).await?;
+        self.sink.close().await
+    }
+}
+
+impl Debug for WebSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocket")
+            .field("protocol", &self.protocol)
+            .finish()
+    }
+}
+
+impl Stream for WebSocket {
+    type Item = Result<Message, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+impl Sink<Message> for WebSocket {
+    type Error = BoxError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.sink.as_mut().poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Message) -> Result<(), BoxError> {
+        self.sink.as_mut().start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.sink.as_mut().poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.sink.as_mut().poll_close(cx)
+    }
+}
+
+type UpgradeCallback = Box<dyn FnOnce(WebSocket) -> BoxFuture<()> + Send>;
+
+/// The callback to run after a [`WebSocketUpgrade`], is added to the extensions of
+/// the `101 Switching Protocols` response.
+///
+/// Server adapters take this from the response, complete the upgrade and call it with the connection.
+pub struct WebSocketHandler {
+    callback: Mutex<Option<UpgradeCallback>>,
+    protocol: Option<String>,
+}
+
+impl WebSocketHandler {
+    /// Returns the subprotocol selected in the handshake, if any.
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+
+    /// Returns a future that runs the callback with the given connection.
+    pub fn call(self, mut socket: WebSocket) -> BoxFuture<()> {
+        let callback = self
+            .callback
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+            .expect("websocket handler was already called");
+
+        socket.protocol = self.protocol;
+        callback(socket)
+    }
+}
+
+impl Debug for WebSocketHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocketHandler")
+            .field("protocol", &self.protocol)
+            .finish()
+    }
+}
+
+/// Extractor to upgrade the connection to a [`WebSocket`].
+///
+/// Returns the response of [`WebSocketUpgrade::on_upgrade`] from the handler to
+/// accept the connection.
+#[derive(Debug, Clone)]
+pub struct WebSocketUpgrade {
+    key: HeaderValue,
+    requested_protocols: Vec<String>,
+    protocol: Option<String>,
+}
+
+impl WebSocketUpgrade {
+    /// Sets the subprotocols supported by the server, the first of the protocols requested
+    /// by the client that is supported is selected.
+    pub fn protocols<I, S>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let supported = protocols.into_iter().map(Into::into).collect::<Vec<_>>();
+        self.protocol = self
+            .requested_protocols
+            .iter()
+            .find(|p| supported.contains(p))
+            .cloned();
+        self
+    }
+
+    /// Returns the subprotocols requested by the client.
+    pub fn requested_protocols(&self) -> &[String] {
+        &self.requested_protocols
+    }
+
+    /// Returns a `101 Switching Protocols` response which calls the given function with the connection
+    /// after the upgrade.
+    pub fn on_upgrade<F, Fut>(self, f: F) -> Response
+    where
+        F: FnOnce(WebSocket) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut res = Response::new(Body::empty());
+        *res.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+
+        let headers = res.headers_mut();
+        headers.insert(header::CONNECTION, HeaderValue::from_static("upgrade"));
+        headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+        headers.insert(
+            header::SEC_WEBSOCKET_ACCEPT,
+            HeaderValue::from_str(&accept_key(self.key.as_bytes()))
+                .expect("accept key should be a valid header"),
+        );
+
+        if let Some(protocol) = &self.protocol {
+            if let Ok(value) = HeaderValue::from_str(protocol) {
+                headers.insert(header::SEC_WEBSOCKET_PROTOCOL, value);
+            }
+        }
+
+        let callback: UpgradeCallback = Box::new(move |socket| Box::pin(f(socket)));
+        res.extensions_mut().insert(WebSocketHandler {
+            callback: Mutex::new(Some(callback)),
+            protocol: self.protocol,
+        });
+
+        res
+    }
+}
+
+impl FromRequest for WebSocketUpgrade {
+    type Error = WebSocketUpgradeRejection;
+    type Fut = Ready<Result<WebSocketUpgrade, WebSocketUpgradeRejection>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        ready(from_headers(
+            ctx.request().method(),
+            ctx.request().headers(),
+        ))
+    }
+}
+
+fn from_headers(
+    method: &Method,
+    headers: &HeaderMap,
+) -> Result<WebSocketUpgrade, WebSocketUpgradeRejection> {
+    if method != Method::GET {
+        return Err(WebSocketUpgradeRejection::MethodNotGet);
+    }
+
+    if !header_contains(headers, header::CONNECTION, "upgrade") {
+        return Err(WebSocketUpgradeRejection::InvalidConnectionHeader);
+    }
+
+    if !header_contains(headers, header::UPGRADE, "websocket") {
+        return Err(WebSocketUpgradeRejection::InvalidUpgradeHeader);
+    }
+
+    if !header_contains(headers, header::SEC_WEBSOCKET_VERSION, "13") {
+        return Err(WebSocketUpgradeRejection::InvalidWebSocketVersion);
+    }
+
+    let key = headers
+        .get(header::SEC_WEBSOCKET_KEY)
+        .cloned()
+        .ok_or(WebSocketUpgradeRejection::MissingWebSocketKey)?;
+
+    let requested_protocols = headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|x| x.to_str().ok())
+        .flat_map(|x| x.split(','))
+        .map(|x| x.trim().to_owned())
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    Ok(WebSocketUpgrade {
+        key,
+        requested_protocols,
+        protocol: None,
+    })
+}
+
+fn header_contains(headers: &HeaderMap, name: header::HeaderName, token: &str) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|x| x.to_str().ok())
+        .flat_map(|x| x.split(','))
+        .any(|x| x.trim().eq_ignore_ascii_case(token))
+}
+
+/// Returns the value of the `Sec-WebSocket-Accept` header for the given `Sec-WebSocket-Key`.
+pub fn accept_key(key: &[u8]) -> String {
+    let mut sha1 = Sha1::new();
+    sha1.update(key);
+    sha1.update(WEBSOCKET_GUID.as_bytes());
+    STANDARD.encode(sha1.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{accept_key, Message, WebSocket, WebSocketHandler, WebSocketUpgrade};
+    use crate::{
+        app::App,
+        error::BoxError,
+        routing::Route,
+        web::{Body, Request},
+    };
+    use futures::{SinkExt, StreamExt};
+    use http::{header, StatusCode};
+    use yew::html::ChildrenProps;
+
+    #[yew::function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            {for props.children.iter()}
+        }
+    }
+
+    fn upgrade_req() -> Request {
+        Request::builder()
+            .uri("/ws")
+            .header(header::CONNECTION, "keep-alive, Upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_VERSION, "13")
+            .header(header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ==")
+            .header(header::SEC_WEBSOCKET_PROTOCOL, "chat, superchat")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn accept_key_test() {
+        assert_eq!(
+            accept_key(b"dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[tokio::test]
+    async fn websocket_upgrade_test() {
+        let service = App::<Base>::new()
+            .route(Route::get("/ws", |ws: WebSocketUpgrade| async move {
+                ws.protocols(["superchat"])
+                    .on_upgrade(|mut socket: WebSocket| async move {
+                        assert_eq!(socket.protocol(), Some("superchat"));
+
+                        while let Some(Ok(msg)) = socket.recv().await {
+                            if msg.is_close() {
+                                break;
+                            }
+
+                            socket.send(msg).await.unwrap();
+                        }
+                    })
+            }))
+            .build();
+
+        let mut res = service.handle(upgrade_req()).await;
+        assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(
+            res.headers().get(header::SEC_WEBSOCKET_ACCEPT).unwrap(),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(
+            res.headers().get(header::SEC_WEBSOCKET_PROTOCOL).unwrap(),
+            "superchat"
+        );
+
+        // Simulate the adapter upgrade with channels
+        let handler = res.extensions_mut().remove::<WebSocketHandler>().unwrap();
+        let (client_tx, server_rx) = futures::channel::mpsc::unbounded::<Message>();
+        let (server_tx, mut client_rx) = futures::channel::mpsc::unbounded::<Message>();
+        let socket =
+            WebSocket::from_split(server_tx.sink_map_err(BoxError::from), server_rx.map(Ok));
+
+        let task = tokio::spawn(handler.call(socket));
+        client_tx.unbounded_send(Message::text("hello")).unwrap();
+        client_tx
+            .unbounded_send(Message::binary(vec![1, 2, 3]))
+            .unwrap();
+        client_tx.unbounded_send(Message::Close(None)).unwrap();
+
+        assert_eq!(client_rx.next().await, Some(Message::text("hello")));
+        assert_eq!(client_rx.next().await, Some(Message::binary(vec![1, 2, 3])));
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn websocket_upgrade_rejection_test() {
+        let service = App::<Base>::new()
+            .route(Route::get("/ws", |ws: WebSocketUpgrade| async move {
+                ws.on_upgrade(|_| async {})
+            }))
+            .build();
+
+        let req = Request::builder().uri("/ws").body(Body::empty()).unwrap();
+
+        let res = service.handle(req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
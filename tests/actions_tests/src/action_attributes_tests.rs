@@ -0,0 +1,162 @@
+use hashira::{
+    action,
+    app::{App as Hashira, AppService, RequestContext},
+    cors::Cors,
+    rate_limit::{RateLimit, RateLimitKey},
+    routing::Guard,
+    web::{header, status::StatusCode, Body, IntoResponse, Json, Request, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use yew::html::ChildrenProps;
+
+#[yew::function_component]
+pub fn App(props: &ChildrenProps) -> yew::Html {
+    yew::html! {
+       <>
+        {for props.children.iter()}
+       </>
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Message {
+    text: String,
+}
+
+#[action("/api/timeout", timeout = "std::time::Duration::from_millis(10)")]
+pub async fn TimeoutAction() -> String {
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    String::from("done")
+}
+
+fn example_cors() -> Cors {
+    Cors::new().allow_origin("https://example.com")
+}
+
+#[action("/api/cors", cors = "example_cors")]
+pub async fn CorsAction() -> String {
+    String::from("hello")
+}
+
+pub struct ApiKeyGuard;
+
+#[hashira::async_trait]
+impl Guard for ApiKeyGuard {
+    async fn check(&self, ctx: &RequestContext) -> Result<(), Response> {
+        match ctx.request().headers().get("x-api-key") {
+            Some(key) if key == "secret" => Ok(()),
+            _ => Err(StatusCode::UNAUTHORIZED.into_response()),
+        }
+    }
+}
+
+fn api_key_guard() -> ApiKeyGuard {
+    ApiKeyGuard
+}
+
+#[action("/api/guard", guard = "api_key_guard")]
+pub async fn GuardAction() -> String {
+    String::from("allowed")
+}
+
+#[action("/api/body-limit", body_limit = "16")]
+pub async fn BodyLimitAction(input: Json<Message>) -> String {
+    input.into_inner().text
+}
+
+fn client_rate_limit() -> RateLimit {
+    RateLimit::token_bucket(1, Duration::from_secs(60)).key(RateLimitKey::header("x-client-id"))
+}
+
+#[action("/api/rate-limit", rate_limit = "client_rate_limit")]
+pub async fn RateLimitAction() -> String {
+    String::from("ok")
+}
+
+fn create_service() -> AppService {
+    Hashira::<App>::new()
+        .action::<TimeoutAction>()
+        .action::<CorsAction>()
+        .action::<GuardAction>()
+        .action::<BodyLimitAction>()
+        .action::<RateLimitAction>()
+        .build()
+}
+
+fn post(path: &str, headers: &[(&str, &str)], body: impl Into<Body>) -> Request {
+    let mut builder = Request::builder().method("POST").uri(path);
+
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+
+    builder.body(body.into()).unwrap()
+}
+
+#[tokio::test]
+async fn action_timeout_test() {
+    let service = create_service();
+
+    let res = service.handle(post("/api/timeout", &[], "")).await;
+
+    assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn action_cors_test() {
+    let service = create_service();
+
+    let res = service
+        .handle(post("/api/cors", &[("origin", "https://example.com")], ""))
+        .await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .unwrap(),
+        "https://example.com"
+    );
+}
+
+#[tokio::test]
+async fn action_guard_test() {
+    let service = create_service();
+
+    let res = service.handle(post("/api/guard", &[], "")).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = service
+        .handle(post("/api/guard", &[("x-api-key", "secret")], ""))
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn action_body_limit_test() {
+    let service = create_service();
+    let send = |text: &str| {
+        let json = format!(r#"{{"text":"{text}"}}"#);
+        let headers = [("content-type", "application/json")];
+        service.handle(post("/api/body-limit", &headers, json))
+    };
+
+    assert_eq!(send("hi").await.status(), StatusCode::OK);
+    assert_eq!(
+        send("this message is too long").await.status(),
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
+
+#[tokio::test]
+async fn action_rate_limit_test() {
+    let service = create_service();
+    let send = || {
+        let headers = [("x-client-id", "client-1")];
+        service.handle(post("/api/rate-limit", &headers, ""))
+    };
+
+    assert_eq!(send().await.status(), StatusCode::OK);
+    assert_eq!(send().await.status(), StatusCode::TOO_MANY_REQUESTS);
+}
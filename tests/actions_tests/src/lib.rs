@@ -0,0 +1,3 @@
+#![cfg(test)]
+
+mod action_attributes_tests;